@@ -5,34 +5,33 @@ use permutohedron::LexicalPermutation;
 use rand::prelude::*;
 
 pub trait TNextVecEPI {
-    fn init(slcepi: &mut[EPlayerIndex]);
-    fn next(slcepi: &mut[EPlayerIndex]) -> bool;
+    fn init(slcoepi: &mut[Option<EPlayerIndex>]);
+    fn next(slcoepi: &mut[Option<EPlayerIndex>]) -> bool;
 }
 
 pub struct SNextVecEPIShuffle;
 impl TNextVecEPI for SNextVecEPIShuffle {
-    fn init(slcepi: &mut[EPlayerIndex]) {
-        slcepi.shuffle(&mut rand::thread_rng());
+    fn init(slcoepi: &mut[Option<EPlayerIndex>]) {
+        slcoepi.shuffle(&mut rng::thread_rng());
     }
-    fn next(slcepi: &mut[EPlayerIndex]) -> bool {
-        Self::init(slcepi);
+    fn next(slcoepi: &mut[Option<EPlayerIndex>]) -> bool {
+        Self::init(slcoepi);
         true
     }
 }
 
 pub struct SNextVecEPIPermutation;
 impl TNextVecEPI for SNextVecEPIPermutation {
-    fn init(_slcepi: &mut[EPlayerIndex]) {/*noop*/}
-    fn next(slcepi: &mut[EPlayerIndex]) -> bool {
-        slcepi.next_permutation()
+    fn init(_slcoepi: &mut[Option<EPlayerIndex>]) {/*noop*/}
+    fn next(slcoepi: &mut[Option<EPlayerIndex>]) -> bool {
+        slcoepi.next_permutation()
     }
 }
 
 pub struct SHandIterator<NextVecEPI> {
-    epi_fixed : EPlayerIndex,
     veccard_unknown: Vec<SCard>,
-    vecepi: Vec<EPlayerIndex>,
-    hand_known: SHand,
+    vecoepi: Vec<Option<EPlayerIndex>>, // None for cards remaining in the stock
+    ahand_known: EnumMap<EPlayerIndex, SHand>, // empty for players whose hand is unknown
     b_valid: bool,
     phantom: std::marker::PhantomData<NextVecEPI>,
 }
@@ -41,43 +40,57 @@ impl<NextVecEPI: TNextVecEPI> Iterator for SHandIterator<NextVecEPI> {
     type Item = EnumMap<EPlayerIndex, SHand>;
     fn next(&mut self) -> Option<Self::Item> {
         if_then_some!(self.b_valid, {
-            let mut ahand = EPlayerIndex::map_from_fn(|_| SHand::new_from_vec(SHandVector::new()));
-            ahand[self.epi_fixed] = self.hand_known.clone();
-            for (i, epi) in self.vecepi.iter().copied().enumerate() {
-                ahand[epi].add_card(self.veccard_unknown[i]);
+            let mut ahand = self.ahand_known.clone();
+            for (i, oepi) in self.vecoepi.iter().copied().enumerate() {
+                if let Some(epi) = oepi {
+                    ahand[epi].add_card(self.veccard_unknown[i]);
+                }
             }
-            self.b_valid = NextVecEPI::next(self.vecepi.as_mut_slice());
+            self.b_valid = NextVecEPI::next(self.vecoepi.as_mut_slice());
             ahand
         })
     }
 }
 
-fn make_handiterator<NextVecEPI: TNextVecEPI>(stichseq: &SStichSequence, hand_fixed: SHand, epi_fixed: EPlayerIndex) -> SHandIterator<NextVecEPI> {
-    let veccard_unknown = unplayed_cards(stichseq, &hand_fixed).collect::<Vec<_>>();
+fn make_handiterator<NextVecEPI: TNextVecEPI>(stichseq: &SStichSequence, hand_fixed: SHand, epi_fixed: EPlayerIndex, ohand_revealed: Option<(EPlayerIndex, SHand)>) -> SHandIterator<NextVecEPI> {
+    let mut mapepiohand_known = EPlayerIndex::map_from_fn(|_epi| None);
+    if let Some((epi_revealed, hand_revealed)) = ohand_revealed {
+        mapepiohand_known[epi_revealed] = Some(hand_revealed);
+    }
+    mapepiohand_known[epi_fixed] = Some(hand_fixed);
+    let veccard_unknown = unplayed_cards(stichseq, unwrap!(mapepiohand_known[epi_fixed].as_ref()))
+        .filter(|card| !mapepiohand_known.iter().flatten().any(|hand| hand.contains(*card)))
+        .collect::<Vec<_>>();
     let mapepin_cards_per_hand = remaining_cards_per_hand(stichseq);
-    let mut vecepi = Vec::new();
+    let mut vecoepi = Vec::new();
     for epi in EPlayerIndex::values() {
-        if epi==epi_fixed {
-            assert_eq!(mapepin_cards_per_hand[epi], hand_fixed.cards().len());
+        if let Some(ref hand_known) = mapepiohand_known[epi] {
+            assert_eq!(mapepin_cards_per_hand[epi], hand_known.cards().len());
         } else {
-            vecepi.extend(std::iter::repeat(epi).take(mapepin_cards_per_hand[epi]));
+            vecoepi.extend(std::iter::repeat_n(Some(epi), mapepin_cards_per_hand[epi]));
         }
     }
-    assert_eq!(veccard_unknown.len(), vecepi.len());
-    assert!(vecepi.iter().is_sorted_unstable_name_collision());
-    NextVecEPI::init(&mut vecepi);
+    let n_cards_stock = veccard_unknown.len() - vecoepi.len();
+    assert_eq!(
+        n_cards_stock,
+        SCard::values(stichseq.kurzlang()).count() - stichseq.kurzlang().n_players() * stichseq.kurzlang().cards_per_player(),
+    );
+    vecoepi.splice(0..0, std::iter::repeat_n(None, n_cards_stock));
+    assert!(vecoepi.iter().is_sorted_unstable_name_collision());
+    NextVecEPI::init(&mut vecoepi);
     SHandIterator {
-        epi_fixed,
         veccard_unknown,
-        vecepi,
-        hand_known: hand_fixed,
+        vecoepi,
+        ahand_known: mapepiohand_known.map(|ohand| ohand.clone().unwrap_or_else(|| SHand::new_from_vec(SHandVector::new()))),
         b_valid: true, // in the beginning, there should be a valid assignment of cards to players
         phantom: std::marker::PhantomData,
     }
 }
 
-fn make_handiterator_compatible_with_game_so_far<'lifetime, NextVecEPI: TNextVecEPI+'lifetime>(stichseq: &'lifetime SStichSequence, hand_fixed: SHand, epi_fixed: EPlayerIndex, rules: &'lifetime dyn TRules) -> impl Iterator<Item=EnumMap<EPlayerIndex, SHand>> + 'lifetime {
-    make_handiterator::<NextVecEPI>(stichseq, hand_fixed, epi_fixed)
+fn make_handiterator_compatible_with_game_so_far<'lifetime, NextVecEPI: TNextVecEPI+'lifetime>(stichseq: &'lifetime SStichSequence, hand_fixed: SHand, epi_fixed: EPlayerIndex, ohand_revealed: Option<(EPlayerIndex, SHand)>, rules: &'lifetime dyn TRules) -> impl Iterator<Item=EnumMap<EPlayerIndex, SHand>> + 'lifetime {
+    // hands before a card exchange cannot be reconstructed from the current hands, so we only check what happened afterwards
+    let on_stich_card_exchange = rules.card_exchange(stichseq).map(|(n_stich, _epi_active, _epi_partner)| n_stich);
+    make_handiterator::<NextVecEPI>(stichseq, hand_fixed, epi_fixed, ohand_revealed)
         .filter(move |ahand| {
             let stich_current = stichseq.current_stich();
             assert!(!stich_current.is_full());
@@ -86,19 +99,20 @@ fn make_handiterator_compatible_with_game_so_far<'lifetime, NextVecEPI: TNextVec
             let mut ahand_simulate = EPlayerIndex::map_from_fn(|epi| {
                 let mut veccard = ahand[epi].cards().clone();
                 veccard.extend(stich_current.get(epi).copied().into_iter());
-                veccard.extend(stichseq.completed_stichs().iter().rev().map(|stich| stich[epi]));
-                assert_eq!(veccard.len(), stichseq.kurzlang().cards_per_player());
+                veccard.extend(stichseq.completed_stichs().iter().rev().filter_map(|stich| stich.get(epi).copied()));
+                assert_eq!(veccard.len(), stichseq.kurzlang().cards_dealt_to(epi));
                 SHand::new_from_vec(veccard)
             });
-            rules.playerindex().map_or(true, |epi_active|
+            (on_stich_card_exchange.is_some() || rules.playerindex().is_none_or(|epi_active|
                 rules.can_be_played(SFullHand::new(&ahand_simulate[epi_active], stichseq.kurzlang()))
-            )
+            ))
             && {
                 let mut b_valid_up_to_now = true;
                 let mut stichseq_simulate = SStichSequence::new(stichseq.kurzlang());
-                'loopstich: for stich in stichseq.visible_stichs() {
+                'loopstich: for (i_stich, stich) in stichseq.visible_stichs().iter().enumerate() {
+                    let b_check = on_stich_card_exchange.is_none_or(|n_stich| n_stich<=i_stich);
                     for (epi, card) in stich.iter() {
-                        if rules.card_is_allowed(
+                        if !b_check || rules.card_is_allowed(
                             &stichseq_simulate,
                             &ahand_simulate[epi],
                             *card
@@ -117,12 +131,12 @@ fn make_handiterator_compatible_with_game_so_far<'lifetime, NextVecEPI: TNextVec
         })
 }
 
-pub fn all_possible_hands<'lifetime>(stichseq: &'lifetime SStichSequence, hand_fixed: SHand, epi_fixed: EPlayerIndex, rules: &'lifetime dyn TRules) -> impl Iterator<Item=EnumMap<EPlayerIndex, SHand>> + 'lifetime {
-    make_handiterator_compatible_with_game_so_far::<SNextVecEPIPermutation>(stichseq, hand_fixed, epi_fixed, rules)
+pub fn all_possible_hands<'lifetime>(stichseq: &'lifetime SStichSequence, hand_fixed: SHand, epi_fixed: EPlayerIndex, ohand_revealed: Option<(EPlayerIndex, SHand)>, rules: &'lifetime dyn TRules) -> impl Iterator<Item=EnumMap<EPlayerIndex, SHand>> + 'lifetime {
+    make_handiterator_compatible_with_game_so_far::<SNextVecEPIPermutation>(stichseq, hand_fixed, epi_fixed, ohand_revealed, rules)
 }
 
-pub fn forever_rand_hands<'lifetime>(stichseq: &'lifetime SStichSequence, hand_fixed: SHand, epi_fixed: EPlayerIndex, rules: &'lifetime dyn TRules) -> impl Iterator<Item=EnumMap<EPlayerIndex, SHand>> + 'lifetime {
-    make_handiterator_compatible_with_game_so_far::<SNextVecEPIShuffle>(stichseq, hand_fixed, epi_fixed, rules)
+pub fn forever_rand_hands<'lifetime>(stichseq: &'lifetime SStichSequence, hand_fixed: SHand, epi_fixed: EPlayerIndex, ohand_revealed: Option<(EPlayerIndex, SHand)>, rules: &'lifetime dyn TRules) -> impl Iterator<Item=EnumMap<EPlayerIndex, SHand>> + 'lifetime {
+    make_handiterator_compatible_with_game_so_far::<SNextVecEPIShuffle>(stichseq, hand_fixed, epi_fixed, ohand_revealed, rules)
 }
 
 #[test]
@@ -169,6 +183,7 @@ fn test_all_possible_hands() {
                     &stichseq,
                     SHand::new_from_iter(veccard_hand),
                     epi_fixed,
+                    /*ohand_revealed*/None,
                 )
                     .inspect(|ahand| assert_eq!(EnumMap::from_raw(an_size_hand), ahand.map(|hand| hand.cards().len())))
                     .count(),
@@ -179,3 +194,30 @@ fn test_all_possible_hands() {
     }
 }
 
+
+#[test]
+fn test_all_possible_hands_dreier() {
+    use crate::card::card_values::*;
+    let epi_irrelevant = EPlayerIndex::EPI0;
+    let mut stichseq = SStichSequence::new(EKurzLang::Dreier);
+    for acard_stich in [
+        [G7, G8, GA], [S8, HO, S7], [H7, HK, HU], [EO, GO, HZ],
+        [E9, EK, E8], [EA, SA, EU], [SO, H9, GZ], [GU, EZ, SZ],
+    ] {
+        for card in acard_stich {
+            stichseq.zugeben_custom_winner_index(card, |_stich| epi_irrelevant);
+        }
+    }
+    // 6 unknown cards: 2 for each of the other players, 2 in the stock
+    assert_eq!(
+        make_handiterator::<SNextVecEPIPermutation>(
+            &stichseq,
+            SHand::new_from_iter([GK, SK]),
+            /*epi_fixed*/EPlayerIndex::EPI2,
+            /*ohand_revealed*/None,
+        )
+            .inspect(|ahand| assert_eq!(EnumMap::from_raw([2, 2, 2, 0]), ahand.map(|hand| hand.cards().len())))
+            .count(),
+        90
+    );
+}
@@ -0,0 +1,142 @@
+use crate::game::SStichSequence;
+use crate::primitives::*;
+use crate::rules::*;
+use crate::util::*;
+use rand::prelude::*;
+
+// weight of exploration relative to the largest absolute payout encountered so far
+const F_EXPLORATION : f64 = 0.7;
+
+struct SIsmctsNode {
+    vecpaircardi_child: Vec<(SCard, usize)>,
+    epi_card: EPlayerIndex, // who played the card leading to this node
+    n_visits: usize,
+    n_available: usize, // how often the card leading to this node could have been played
+    an_payout_sum: EnumMap<EPlayerIndex, isize>,
+}
+
+impl SIsmctsNode {
+    fn new(epi_card: EPlayerIndex) -> Self {
+        Self {
+            vecpaircardi_child: Vec::new(),
+            epi_card,
+            n_visits: 0,
+            n_available: 0,
+            an_payout_sum: EPlayerIndex::map_from_fn(|_epi| 0),
+        }
+    }
+
+    fn child(&self, card: SCard) -> Option<usize> {
+        self.vecpaircardi_child.iter()
+            .find(|&&(card_child, _i_child)| card_child==card)
+            .map(|&(_card, i_child)| i_child)
+    }
+}
+
+// Single-observer information set Monte Carlo tree search:
+// Each iteration plays a game on another determinization, sharing statistics between all
+// determinizations, so that cards are chosen without knowing the other players' cards.
+pub struct SIsmctsTree {
+    vecnode: Vec<SIsmctsNode>, // vecnode[0] is the root
+}
+
+impl SIsmctsTree {
+    pub fn new(
+        rules: &dyn TRules,
+        stichseq: &SStichSequence,
+        itahand: impl Iterator<Item=EnumMap<EPlayerIndex, SHand>>,
+        tpln_stoss_doubling: (usize, usize),
+        n_stock: isize,
+    ) -> Self {
+        let epi_root = unwrap!(stichseq.current_stich().current_playerindex());
+        let mut ismctstree = SIsmctsTree {
+            vecnode: vec![SIsmctsNode::new(/*epi_card*/epi_root)], // epi_card irrelevant for root
+        };
+        let mut n_payout_abs_max = 1;
+        let mut rng = rng::thread_rng();
+        for mut ahand in itahand {
+            let mut stichseq = stichseq.clone();
+            let mut veci_node = vec![0];
+            let mut b_in_tree = true;
+            while !stichseq.game_finished() {
+                let epi = unwrap!(stichseq.current_stich().current_playerindex());
+                let veccard_allowed = rules.all_allowed_cards(&stichseq, &ahand[epi]);
+                let card = if b_in_tree {
+                    let i_node = *unwrap!(veci_node.last());
+                    let veccard_untried = veccard_allowed.iter().copied()
+                        .filter(|card| ismctstree.vecnode[i_node].child(*card).is_none())
+                        .collect::<Vec<_>>();
+                    for card in veccard_allowed.iter() {
+                        if let Some(i_child) = ismctstree.vecnode[i_node].child(*card) {
+                            ismctstree.vecnode[i_child].n_available += 1;
+                        }
+                    }
+                    let card = if let Some(card_untried) = veccard_untried.choose(&mut rng) {
+                        let i_child = ismctstree.vecnode.len();
+                        let mut node_child = SIsmctsNode::new(epi);
+                        node_child.n_available = 1;
+                        ismctstree.vecnode.push(node_child);
+                        ismctstree.vecnode[i_node].vecpaircardi_child.push((*card_untried, i_child));
+                        b_in_tree = false; // continue with random playout
+                        *card_untried
+                    } else {
+                        let vecnode = &ismctstree.vecnode;
+                        let ucb = |i_child: usize| {
+                            let node = &vecnode[i_child];
+                            node.an_payout_sum[node.epi_card].as_num::<f64>() / node.n_visits.as_num::<f64>()
+                                + F_EXPLORATION * n_payout_abs_max.as_num::<f64>()
+                                    * (node.n_available.as_num::<f64>().ln() / node.n_visits.as_num::<f64>()).sqrt()
+                        };
+                        *unwrap!(veccard_allowed.iter()
+                            .max_by(|card_lhs, card_rhs| unwrap!(
+                                ucb(unwrap!(vecnode[i_node].child(**card_lhs)))
+                                    .partial_cmp(&ucb(unwrap!(vecnode[i_node].child(**card_rhs))))
+                            ))
+                        )
+                    };
+                    veci_node.push(unwrap!(ismctstree.vecnode[i_node].child(card)));
+                    card
+                } else {
+                    *unwrap!(veccard_allowed.choose(&mut rng))
+                };
+                ahand[epi].play_card(card);
+                stichseq.zugeben(card, rules);
+            }
+            let an_payout = rules.payout(
+                SStichSequenceGameFinished::new(&stichseq),
+                tpln_stoss_doubling,
+                n_stock,
+            );
+            for i_node in veci_node {
+                let node = &mut ismctstree.vecnode[i_node];
+                node.n_visits += 1;
+                for epi in EPlayerIndex::values() {
+                    node.an_payout_sum[epi] += an_payout[epi];
+                }
+            }
+            for epi in EPlayerIndex::values() {
+                assign_max(&mut n_payout_abs_max, an_payout[epi].abs());
+            }
+        }
+        assert!(0 < ismctstree.vecnode[0].n_visits);
+        ismctstree
+    }
+
+    // the card explored most often
+    fn best_child(&self) -> (SCard, usize) {
+        *unwrap!(self.vecnode[0].vecpaircardi_child.iter()
+            .max_by_key(|&&(_card, i_child)| self.vecnode[i_child].n_visits)
+        )
+    }
+
+    pub fn best_card(&self) -> SCard {
+        self.best_child().0
+    }
+
+    // value of the game if best_card is played: the average over all playouts
+    // would also count the (mostly worse) cards tried during exploration
+    pub fn avg_payout(&self, epi: EPlayerIndex) -> f64 {
+        let node = &self.vecnode[self.best_child().1];
+        node.an_payout_sum[epi].as_num::<f64>() / node.n_visits.as_num::<f64>()
+    }
+}
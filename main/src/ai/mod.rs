@@ -1,10 +1,11 @@
 pub mod handiterators;
+pub mod ismcts;
 pub mod rulespecific;
 pub mod suspicion;
 #[cfg(test)]
 pub mod test;
 
-use crate::ai::{handiterators::*, suspicion::*};
+use crate::ai::{handiterators::*, ismcts::*, suspicion::*};
 use crate::game::*;
 use crate::primitives::*;
 use crate::rules::*;
@@ -16,10 +17,14 @@ use rayon::prelude::*;
 use std::{
     self,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 pub fn remaining_cards_per_hand(stichseq: &SStichSequence) -> EnumMap<EPlayerIndex, usize> {
     EPlayerIndex::map_from_fn(|epi| {
+        if !stichseq.kurzlang().takes_part(epi) {
+            return 0;
+        }
         stichseq.kurzlang().cards_per_player()
             - stichseq.completed_stichs().len()
             - match stichseq.current_stich().get(epi) {
@@ -33,17 +38,42 @@ pub fn ahand_vecstich_card_count_is_compatible(stichseq: &SStichSequence, ahand:
     ahand.map(|hand| hand.cards().len()) == remaining_cards_per_hand(stichseq)
 }
 
+pub const N_BYTES_SNAPSHOT_CACHE_DEFAULT : usize = 128 * 1024 * 1024;
+const N_REMAINING_CARDS_EXHAUSTIVE : usize = 4; // suggest_card explores all possibilities if there are at most this many cards left
+
 pub enum VAIParams {
     Cheating,
     Simulating {
         n_suggest_card_samples: usize,
     },
+    Ismcts {
+        n_suggest_card_iterations: usize,
+    },
 }
 
 pub struct SAi {
     n_rank_rules_samples: usize,
     n_suggest_card_branches: usize,
     aiparams: VAIParams,
+    oduration_suggest_card: Option<Duration>, // if set, search effort is increased until time runs out
+    b_equivalent_cards: bool, // explore only representatives of equivalent cards
+    n_bytes_snapshot_cache: usize, // memory to cache explored snapshots in suggest_card, 0 to disable
+}
+
+// one cache per exploration used by suggest_card, so that iterative deepening can reuse explored snapshots
+// (the number of remaining cards determines which one is used, so only one of them is filled)
+struct SSuggestCardSnapshotCaches<'rules> {
+    snapshotcache_exact: SSnapshotCache<SMinReachablePayout<'rules>>,
+    snapshotcache_lower_bound: SSnapshotCache<SMinReachablePayoutLowerBoundViaHint<'rules>>,
+}
+
+impl SSuggestCardSnapshotCaches<'_> {
+    fn new(n_bytes_max: usize) -> Self {
+        Self {
+            snapshotcache_exact: SSnapshotCache::new(n_bytes_max),
+            snapshotcache_lower_bound: SSnapshotCache::new(n_bytes_max),
+        }
+    }
 }
 
 pub struct SDetermineBestCard<'game> {
@@ -86,6 +116,9 @@ impl SAi {
             n_rank_rules_samples,
             n_suggest_card_branches,
             aiparams: VAIParams::Cheating,
+            oduration_suggest_card: None,
+            b_equivalent_cards: true,
+            n_bytes_snapshot_cache: N_BYTES_SNAPSHOT_CACHE_DEFAULT,
         }
     }
 
@@ -96,31 +129,80 @@ impl SAi {
             aiparams: VAIParams::Simulating {
                 n_suggest_card_samples,
             },
+            oduration_suggest_card: None,
+            b_equivalent_cards: true,
+            n_bytes_snapshot_cache: N_BYTES_SNAPSHOT_CACHE_DEFAULT,
+        }
+    }
+
+    pub fn new_ismcts(n_rank_rules_samples: usize, n_suggest_card_iterations: usize) -> Self {
+        SAi {
+            n_rank_rules_samples,
+            n_suggest_card_branches: 1, // irrelevant
+            aiparams: VAIParams::Ismcts {
+                n_suggest_card_iterations,
+            },
+            oduration_suggest_card: None,
+            b_equivalent_cards: true,
+            n_bytes_snapshot_cache: N_BYTES_SNAPSHOT_CACHE_DEFAULT,
+        }
+    }
+
+    pub fn with_time_budget(self, duration_suggest_card: Duration) -> Self {
+        SAi {
+            oduration_suggest_card: Some(duration_suggest_card),
+            ..self
+        }
+    }
+
+    pub fn with_equivalent_cards(self, b_equivalent_cards: bool) -> Self {
+        SAi {
+            b_equivalent_cards,
+            ..self
+        }
+    }
+
+    pub fn with_snapshot_cache_size(self, n_bytes_snapshot_cache: usize) -> Self {
+        SAi {
+            n_bytes_snapshot_cache,
+            ..self
         }
     }
 
     pub fn rank_rules(&self, hand_fixed: SFullHand, epi_rank: EPlayerIndex, rules: &dyn TRules, tpln_stoss_doubling: (usize, usize), n_stock: isize) -> f64 {
         // TODO: adjust interface to get whole game in case of VAIParams::Cheating
         let ekurzlang = EKurzLang::from_cards_per_player(hand_fixed.get().cards().len());
-        forever_rand_hands(&SStichSequence::new(ekurzlang), hand_fixed.get().clone(), epi_rank, rules)
-            .take(self.n_rank_rules_samples)
-            .par_bridge() // TODO can we derive a true parallel iterator?
-            .map(|mut ahand| {
-                explore_snapshots(
-                    &mut ahand,
-                    rules,
-                    &mut SStichSequence::new(ekurzlang),
-                    &branching_factor(|_stichseq| (1, 2)),
-                    &SMinReachablePayoutLowerBoundViaHint::new(
+        let stichseq = SStichSequence::new(ekurzlang);
+        let itahand = forever_rand_hands(&stichseq, hand_fixed.get().clone(), epi_rank, /*ohand_revealed*/None, rules)
+            .take(self.n_rank_rules_samples);
+        match self.aiparams {
+            VAIParams::Cheating | VAIParams::Simulating{..} => itahand
+                .par_bridge() // TODO can we derive a true parallel iterator?
+                .map(|mut ahand| {
+                    explore_snapshots(
+                        &mut ahand,
                         rules,
-                        epi_rank,
-                        tpln_stoss_doubling,
-                        n_stock,
-                    ),
-                    /*opath_out_dir*/None,
-                ).t_min[epi_rank]
-            })
-            .sum::<isize>().as_num::<f64>() / (self.n_rank_rules_samples.as_num::<f64>())
+                        &mut SStichSequence::new(ekurzlang),
+                        &branching_factor(|_stichseq| (1, 2)),
+                        &SMinReachablePayoutLowerBoundViaHint::new(
+                            rules,
+                            epi_rank,
+                            tpln_stoss_doubling,
+                            n_stock,
+                        ),
+                        /*osnapshotcache*/None,
+                        /*opath_out_dir*/None,
+                    ).t_min[epi_rank]
+                })
+                .sum::<isize>().as_num::<f64>() / (self.n_rank_rules_samples.as_num::<f64>()),
+            VAIParams::Ismcts{..} => SIsmctsTree::new(
+                rules,
+                &stichseq,
+                itahand,
+                tpln_stoss_doubling,
+                n_stock,
+            ).avg_payout(epi_rank),
+        }
     }
 
     pub fn suggest_card(&self, game: &SGame, opath_out_dir: Option<&std::path::Path>) -> SCard {
@@ -131,73 +213,189 @@ impl SAi {
             .and_then(|airulespecific| airulespecific.suggest_card(game))
         {
             card
-        } else {
-            macro_rules! forward_to_determine_best_card{($func_filter_allowed_cards: expr, $foreachsnapshot: ident, $itahand: expr,) => {{ // TODORUST generic closures
-                determine_best_card(
-                    &determinebestcard,
+        } else if let VAIParams::Ismcts{n_suggest_card_iterations} = self.aiparams {
+            let itahand = forever_rand_hands(determinebestcard.stichseq, determinebestcard.hand_fixed.clone(), determinebestcard.epi_fixed, game.revealed_hand(), determinebestcard.rules);
+            macro_rules! suggest_via{($itahand: expr) => {
+                SIsmctsTree::new(
+                    determinebestcard.rules,
+                    determinebestcard.stichseq,
                     $itahand,
-                    $func_filter_allowed_cards,
-                    &$foreachsnapshot::new(
-                        determinebestcard.rules,
-                        determinebestcard.epi_fixed,
-                        /*tpln_stoss_doubling*/stoss_and_doublings(&game.vecstoss, &game.doublings),
-                        game.n_stock,
-                    ),
-                    opath_out_dir.map(|path_out_dir| {
-                        unwrap!(std::fs::create_dir_all(path_out_dir));
-                        unwrap!(crate::game_analysis::generate_html_auxiliary_files(path_out_dir));
-                        path_out_dir
-                            .join(format!("{}", Local::now().format("%Y%m%d%H%M%S")))
-                    }),
-                )
-            }}}
-            macro_rules! suggest_via{($itahand: expr,) => {{ // TODORUST generic closures
-                *unwrap!(
-                    // TODORUST exhaustive_integer_patterns for isize/usize
-                    // https://github.com/rust-lang/rfcs/pull/2591/commits/46135303146c660f3c5d34484e0ede6295c8f4e7#diff-8fe9cb03c196455367c9e539ea1964e8R70
-                    match /*n_remaining_cards_on_hand*/remaining_cards_per_hand(determinebestcard.stichseq)[determinebestcard.epi_fixed] {
-                        1|2|3 => forward_to_determine_best_card!(
-                            &|_,_| (/*no filtering*/),
-                            SMinReachablePayout,
-                            $itahand,
-                        ),
-                        4 => forward_to_determine_best_card!(
-                            &|_,_| (/*no filtering*/),
-                            SMinReachablePayoutLowerBoundViaHint,
-                            $itahand,
-                        ),
-                        5|6|7|8 => forward_to_determine_best_card!(
-                            &branching_factor(|_stichseq| {
-                                (1, self.n_suggest_card_branches+1)
-                            }),
-                            SMinReachablePayoutLowerBoundViaHint,
-                            $itahand,
-                        ),
-                        n_remaining_cards_on_hand => panic!("internal_suggest_card called with {} cards on hand", n_remaining_cards_on_hand),
-                    }.cards_with_maximum_value().0.first()
+                    /*tpln_stoss_doubling*/stoss_and_doublings(&game.vecstoss, &game.doublings, game.n_bock),
+                    game.n_stock,
+                ).best_card()
+            }}
+            if let Some(duration_suggest_card) = self.oduration_suggest_card {
+                // search can be stopped after any iteration
+                let instant_deadline = Instant::now() + duration_suggest_card;
+                suggest_via!(itahand.enumerate()
+                    .take_while(|&(i_iteration, ref _ahand)| 0==i_iteration || Instant::now() < instant_deadline)
+                    .map(|(_i_iteration, ahand)| ahand)
                 )
-            }}}
-            match self.aiparams {
-                VAIParams::Cheating => {
-                    suggest_via!(
-                        /*itahand*/std::iter::once(game.ahand.clone()),
-                    )
+            } else {
+                suggest_via!(itahand.take(n_suggest_card_iterations))
+            }
+        } else if let Some(duration_suggest_card) = self.oduration_suggest_card {
+            self.suggest_card_within_time_budget(game, &determinebestcard, duration_suggest_card, opath_out_dir).0
+        } else {
+            unwrap!(self.suggest_card_with_effort(
+                game,
+                &determinebestcard,
+                self.n_suggest_card_branches,
+                match self.aiparams {
+                    VAIParams::Cheating => 1, // irrelevant
+                    VAIParams::Ismcts{..} => panic!("Ismcts does not use suggest_card_with_effort"),
+                    VAIParams::Simulating{n_suggest_card_samples} => n_suggest_card_samples,
                 },
-                VAIParams::Simulating{n_suggest_card_samples} => {
-                    let epi_fixed = determinebestcard.epi_fixed;
-                    match /*n_remaining_cards_on_hand*/remaining_cards_per_hand(determinebestcard.stichseq)[epi_fixed] {
-                        1|2|3|4 => suggest_via!(
-                            all_possible_hands(determinebestcard.stichseq, determinebestcard.hand_fixed.clone(), epi_fixed, determinebestcard.rules),
-                        ),
-                        5|6|7|8 => suggest_via!(
-                            forever_rand_hands(determinebestcard.stichseq, determinebestcard.hand_fixed.clone(), epi_fixed, determinebestcard.rules)
-                                .take(n_suggest_card_samples),
-                        ),
-                        n_remaining_cards_on_hand => panic!("internal_suggest_card called with {} cards on hand", n_remaining_cards_on_hand),
-                    }
+                &SSuggestCardSnapshotCaches::new(self.n_bytes_snapshot_cache),
+                /*oinstant_deadline*/None,
+                opath_out_dir,
+            ))
+        }
+    }
+
+    // iterative deepening: start cheap, and increase samples and branches as long as there is time left
+    // returns the best card of the last complete search and the number of searches
+    fn suggest_card_within_time_budget(
+        &self,
+        game: &SGame,
+        determinebestcard: &SDetermineBestCard,
+        duration_suggest_card: Duration,
+        opath_out_dir: Option<&std::path::Path>,
+    ) -> (SCard, usize) {
+        let instant_deadline = Instant::now() + duration_suggest_card;
+        let n_remaining_cards_on_hand = remaining_cards_per_hand(determinebestcard.stichseq)[determinebestcard.epi_fixed];
+        let snapshotcaches = SSuggestCardSnapshotCaches::new(self.n_bytes_snapshot_cache);
+        let mut n_suggest_card_branches = 1;
+        let mut n_suggest_card_samples = 1;
+        let mut card_best = unwrap!(self.suggest_card_with_effort(
+            game,
+            determinebestcard,
+            n_suggest_card_branches,
+            n_suggest_card_samples,
+            &snapshotcaches,
+            /*oinstant_deadline*/None, // we need at least one result
+            opath_out_dir,
+        ));
+        let mut n_searches = 1;
+        while Instant::now() < instant_deadline
+            && !(n_remaining_cards_on_hand<=N_REMAINING_CARDS_EXHAUSTIVE || match self.aiparams {
+                VAIParams::Cheating => n_remaining_cards_on_hand<=n_suggest_card_branches,
+                VAIParams::Simulating{..} => false, // more samples may always help
+                VAIParams::Ismcts{..} => panic!("Ismcts does not use iterative deepening"),
+            })
+        {
+            n_suggest_card_branches += 1;
+            n_suggest_card_samples *= 2;
+            match self.suggest_card_with_effort(
+                game,
+                determinebestcard,
+                n_suggest_card_branches,
+                n_suggest_card_samples,
+                &snapshotcaches,
+                Some(instant_deadline),
+                opath_out_dir,
+            ) {
+                Some(card) => {
+                    card_best = card;
+                    n_searches += 1;
                 },
+                None => break, // time ran out before search was complete
             }
         }
+        (card_best, n_searches)
+    }
+
+    // None if oinstant_deadline has passed before search was complete
+    fn suggest_card_with_effort<'rules>(
+        &self,
+        game: &SGame,
+        determinebestcard: &SDetermineBestCard<'rules>,
+        n_suggest_card_branches: usize,
+        n_suggest_card_samples: usize,
+        snapshotcaches: &SSuggestCardSnapshotCaches<'rules>,
+        oinstant_deadline: Option<Instant>,
+        opath_out_dir: Option<&std::path::Path>,
+    ) -> Option<SCard> {
+        let deadline_passed = move || oinstant_deadline.is_some_and(|instant_deadline| instant_deadline < Instant::now());
+        macro_rules! forward_to_determine_best_card{($func_filter_allowed_cards: expr, $foreachsnapshot: ident, $snapshotcache: expr, $itahand: expr,) => {{ // TODORUST generic closures
+            let func_filter_allowed_cards = $func_filter_allowed_cards;
+            determine_best_card(
+                determinebestcard,
+                $itahand,
+                &|stichseq: &SStichSequence, veccard_allowed: &mut SHandVector| {
+                    if deadline_passed() {
+                        veccard_allowed.truncate(1); // result is discarded anyway, so finish as fast as possible
+                    } else {
+                        func_filter_allowed_cards(stichseq, veccard_allowed);
+                    }
+                },
+                &$foreachsnapshot::new(
+                    determinebestcard.rules,
+                    determinebestcard.epi_fixed,
+                    /*tpln_stoss_doubling*/stoss_and_doublings(&game.vecstoss, &game.doublings, game.n_bock),
+                    game.n_stock,
+                ),
+                if_then_some!(0<self.n_bytes_snapshot_cache, &$snapshotcache),
+                opath_out_dir.map(|path_out_dir| {
+                    unwrap!(std::fs::create_dir_all(path_out_dir));
+                    unwrap!(crate::game_analysis::generate_html_auxiliary_files(path_out_dir));
+                    path_out_dir
+                        .join(format!("{}", Local::now().format("%Y%m%d%H%M%S")))
+                }),
+                oinstant_deadline,
+            )
+        }}}
+        macro_rules! suggest_via{($itahand: expr,) => {{ // TODORUST generic closures
+            *unwrap!(
+                // TODORUST exhaustive_integer_patterns for isize/usize
+                // https://github.com/rust-lang/rfcs/pull/2591/commits/46135303146c660f3c5d34484e0ede6295c8f4e7#diff-8fe9cb03c196455367c9e539ea1964e8R70
+                match /*n_remaining_cards_on_hand*/remaining_cards_per_hand(determinebestcard.stichseq)[determinebestcard.epi_fixed] {
+                    1|2|3 => forward_to_determine_best_card!(
+                        equivalent_cards_filter(determinebestcard.rules, self.b_equivalent_cards),
+                        SMinReachablePayout,
+                        snapshotcaches.snapshotcache_exact,
+                        $itahand,
+                    ),
+                    4 => forward_to_determine_best_card!(
+                        equivalent_cards_filter(determinebestcard.rules, self.b_equivalent_cards),
+                        SMinReachablePayoutLowerBoundViaHint,
+                        snapshotcaches.snapshotcache_lower_bound,
+                        $itahand,
+                    ),
+                    5..=10 => forward_to_determine_best_card!(
+                        branching_factor(|_stichseq| {
+                            (1, n_suggest_card_branches+1)
+                        }),
+                        SMinReachablePayoutLowerBoundViaHint,
+                        snapshotcaches.snapshotcache_lower_bound,
+                        $itahand,
+                    ),
+                    n_remaining_cards_on_hand => panic!("internal_suggest_card called with {} cards on hand", n_remaining_cards_on_hand),
+                }.cards_with_maximum_value().0.first()
+            )
+        }}}
+        let card = match self.aiparams {
+            VAIParams::Cheating => {
+                suggest_via!(
+                    /*itahand*/std::iter::once(game.ahand.clone()),
+                )
+            },
+            VAIParams::Ismcts{..} => panic!("Ismcts does not use suggest_card_with_effort"),
+            VAIParams::Simulating{n_suggest_card_samples: _} => {
+                let epi_fixed = determinebestcard.epi_fixed;
+                match /*n_remaining_cards_on_hand*/remaining_cards_per_hand(determinebestcard.stichseq)[epi_fixed] {
+                    1|2|3|4 => suggest_via!(
+                        all_possible_hands(determinebestcard.stichseq, determinebestcard.hand_fixed.clone(), epi_fixed, game.revealed_hand(), determinebestcard.rules),
+                    ),
+                    5..=10 => suggest_via!(
+                        forever_rand_hands(determinebestcard.stichseq, determinebestcard.hand_fixed.clone(), epi_fixed, game.revealed_hand(), determinebestcard.rules)
+                            .take(n_suggest_card_samples),
+                    ),
+                    n_remaining_cards_on_hand => panic!("internal_suggest_card called with {} cards on hand", n_remaining_cards_on_hand),
+                }
+            },
+        };
+        if_then_some!(!deadline_passed(), card)
     }
 }
 
@@ -330,10 +528,13 @@ pub fn determine_best_card<
     itahand: impl Iterator<Item=EnumMap<EPlayerIndex, SHand>> + Send,
     func_filter_allowed_cards: &(impl Fn(&SStichSequence, &mut SHandVector) + std::marker::Sync),
     foreachsnapshot: &ForEachSnapshot,
-    opath_out_dir: Option<std::path::PathBuf>
+    osnapshotcache: Option<&SSnapshotCache<ForEachSnapshot>>,
+    opath_out_dir: Option<std::path::PathBuf>,
+    oinstant_deadline: Option<Instant>, // no further hands are explored after this, but the first one always is
 ) -> SDetermineBestCardResult<SPayoutStatsPerStrategy>
     where
         ForEachSnapshot::Output: std::fmt::Debug + Send,
+        ForEachSnapshot::CacheParams: Send,
 {
     let mapcardooutput = Arc::new(Mutex::new(
         // aggregate n_payout per card in some way
@@ -341,6 +542,7 @@ pub fn determine_best_card<
     ));
     itahand
         .enumerate()
+        .take_while(|&(i_susp, ref _ahand)| 0==i_susp || oinstant_deadline.is_none_or(|instant_deadline| Instant::now() < instant_deadline))
         .par_bridge() // TODO can we derive a true parallel iterator?
         .flat_map(|(i_susp, ahand)|
             determinebestcard.veccard_allowed.par_iter()
@@ -359,6 +561,7 @@ pub fn determine_best_card<
                 &mut stichseq,
                 func_filter_allowed_cards,
                 foreachsnapshot,
+                osnapshotcache,
                 opath_out_dir.as_ref().map(|path_out_dir| {
                     unwrap!(std::fs::create_dir_all(path_out_dir));
                     unwrap!(std::fs::File::create(
@@ -397,7 +600,7 @@ pub fn branching_factor(fn_stichseq_to_intvl: impl Fn(&SStichSequence)->(usize,
         assert!(!veccard_allowed.is_empty());
         let (n_lo, n_hi) = fn_stichseq_to_intvl(stichseq);
         assert!(n_lo < n_hi);
-        let mut rng = rand::thread_rng();
+        let mut rng = rng::thread_rng();
         let n = rng.gen_range(n_lo..n_hi);
         while n<veccard_allowed.len() {
             veccard_allowed.swap_remove(rng.gen_range(0..veccard_allowed.len()));
@@ -425,8 +628,10 @@ fn test_is_compatible_with_game_so_far() {
             SDoublings::new(SStaticEPI0{}),
             Some(SStossParams::new( // TODO implement tests for SStoss
                 /*n_stoss_max*/ 4,
+                EStossDeadline::NoCardPlayed,
             )),
             rules.box_clone(),
+            /*n_bock*/ 0,
             /*n_stock*/ 0,
         );
         let mut vecpairepitrumpforfarbe_frei = Vec::new();
@@ -450,6 +655,7 @@ fn test_is_compatible_with_game_so_far() {
                 &game.stichseq,
                 game.ahand[unwrap!(game.which_player_can_do_something()).0].clone(),
                 unwrap!(game.which_player_can_do_something()).0,
+                game.revealed_hand(),
                 game.rules.as_ref(),
             )
                 .take(100)
@@ -501,7 +707,7 @@ fn test_very_expensive_exploration() { // this kind of abuses the test mechanism
     use crate::card::card_values::*;
     use crate::game::*;
     use crate::rules::{ruleset::*, rulessolo::*, payoutdecider::*};
-    use crate::game_analysis::TPayoutDeciderSoloLikeDefault;
+    use crate::rules::tests::TPayoutDeciderSoloLikeDefault;
     let epi_active = EPlayerIndex::EPI0;
     let n_payout_base = 50;
     let n_payout_schneider_schwarz = 10;
@@ -515,6 +721,7 @@ fn test_very_expensive_exploration() { // this kind of abuses the test mechanism
         SDoublings::new(SStaticEPI0{}),
         Some(SStossParams::new(
             /*n_stoss_max*/ 4,
+            EStossDeadline::NoCardPlayed,
         )),
         TRulesBoxClone::box_clone(sololike(
             epi_active,
@@ -522,6 +729,7 @@ fn test_very_expensive_exploration() { // this kind of abuses the test mechanism
             ESoloLike::Solo,
             SPayoutDeciderPointBased::default_payoutdecider(n_payout_base, n_payout_schneider_schwarz, SLaufendeParams::new(10, 3)),
         ).as_ref()),
+        /*n_bock*/ 0,
         /*n_stock*/ 0,
     );
     for acard_stich in [[EO, GO, HO, SO], [EU, GU, HU, SU], [HA, E7, E8, E9], [HZ, S7, S8, S9], [HK, G7, G8, G9]] {
@@ -534,6 +742,7 @@ fn test_very_expensive_exploration() { // this kind of abuses the test mechanism
         &game.stichseq,
         game.ahand[epi_active].clone(),
         epi_active,
+        game.revealed_hand(),
         game.rules.as_ref(),
     ) {
         assert!(!game.current_playable_stich().is_full());
@@ -543,7 +752,9 @@ fn test_very_expensive_exploration() { // this kind of abuses the test mechanism
             std::iter::once(ahand),
             /*func_filter_allowed_cards*/&branching_factor(|_stichseq| (1, 2)),
             &SMinReachablePayout::new_from_game(&game),
+            /*osnapshotcache*/None,
             /*opath_out_dir*/None, //Some(&format!("suspicion_test/{:?}", ahand)), // to inspect search tree
+            /*oinstant_deadline*/None,
         );
         for card in [H7, H8, H9] {
             assert!(determinebestcard.veccard_allowed.contains(&card));
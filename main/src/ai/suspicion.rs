@@ -1,20 +1,35 @@
 use crate::game::{stoss_and_doublings, SGame, SStichSequence};
 use crate::primitives::*;
-use crate::rules::*;
+use crate::rules::{*, card_points::points_card};
 use crate::util::*;
 use itertools::Itertools;
 use rand::{self, Rng};
-use std::{cmp::Ordering, fmt, fs, io::Write};
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt, fs,
+    hash::{Hash, Hasher},
+    io::Write,
+    sync::{atomic::{AtomicUsize, Ordering as AtomicOrdering}, Mutex},
+};
 
 pub trait TForEachSnapshot {
     type Output;
+    type Bounds: Copy + Default; // what ancestors already achieved, allowing to skip remaining siblings
+    type CacheParams: Eq + Hash + fmt::Debug; // everything besides the snapshot that outputs depend on
+    fn cache_params(&self) -> Self::CacheParams;
     fn final_output(&self, slcstich: SStichSequenceGameFinished, rulestatecache: &SRuleStateCache) -> Self::Output;
     fn pruned_output(&self, stichseq: &SStichSequence, ahand: &EnumMap<EPlayerIndex, SHand>, rulestatecache: &SRuleStateCache) -> Option<Self::Output>;
-    fn combine_outputs<ItTplCardOutput: Iterator<Item=(SCard, Self::Output)>>(
+    fn combine_outputs(
         &self,
         epi_card: EPlayerIndex,
-        ittplcardoutput: ItTplCardOutput,
+        bounds: Self::Bounds,
+        veccard_allowed: SHandVector,
+        fn_output: impl FnMut(SCard, Self::Bounds) -> Self::Output,
     ) -> Self::Output;
+    fn output_is_exact(&self, _bounds: &Self::Bounds, _output: &Self::Output) -> bool {
+        true // only exact outputs are cached
+    }
 }
 
 trait TSnapshotVisualizer {
@@ -80,7 +95,7 @@ impl TSnapshotVisualizer for SForEachSnapshotHTMLVisualizer<'_> {
     fn begin_snapshot(&mut self, stichseq: &SStichSequence, ahand: &EnumMap<EPlayerIndex, SHand>) {
         let str_item_id = format!("{}{}",
             stichseq.count_played_cards(),
-            rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(16).join(""), // we simply assume no collisions here TODO uuid
+            rng::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(16).join(""), // we simply assume no collisions here TODO uuid
         );
         self.write_all(format!("<li><<input type=\"checkbox\" id=\"{}\" />>\n", str_item_id).as_bytes());
         self.write_all(format!("<label for=\"{}\">{} direct successors<table><tr>\n",
@@ -121,12 +136,15 @@ pub fn explore_snapshots<ForEachSnapshot>(
     stichseq: &mut SStichSequence,
     func_filter_allowed_cards: &impl Fn(&SStichSequence, &mut SHandVector),
     foreachsnapshot: &ForEachSnapshot,
+    osnapshotcache: Option<&SSnapshotCache<ForEachSnapshot>>,
     opairfileepi_visualize: Option<(fs::File, EPlayerIndex)>,
 ) -> ForEachSnapshot::Output 
     where
         ForEachSnapshot: TForEachSnapshot,
-        ForEachSnapshot::Output: fmt::Debug,
+        ForEachSnapshot::Output: fmt::Debug + Clone,
 {
+    // the same snapshot can only be reached via different card orders after two complete stichs
+    let opairsnapshotcachen_stich_min = osnapshotcache.map(|snapshotcache| (snapshotcache, stichseq.completed_stichs().len() + 2));
     macro_rules! forward_to_internal{($snapshotvisualizer: expr) => {
         explore_snapshots_internal(
             ahand,
@@ -139,6 +157,8 @@ pub fn explore_snapshots<ForEachSnapshot>(
             stichseq,
             func_filter_allowed_cards,
             foreachsnapshot,
+            /*bounds*/Default::default(),
+            opairsnapshotcachen_stich_min,
             $snapshotvisualizer,
         )
     }}
@@ -158,6 +178,43 @@ pub fn explore_snapshots<ForEachSnapshot>(
     }
 }
 
+// Groups allowed cards into runs that are adjacent among the cards still in play (i.e. not separated by cards of other players or by forbidden cards) and interchangeable according to rules.
+// Cards of a run win the same stichs, so the only choice is which points go into the current stich.
+// Assuming that payouts are monotonic in the points of each party, it suffices to explore the cards with the least and the most points of each run.
+// If b_equivalent_cards is false, all allowed cards are kept.
+pub fn equivalent_cards_filter(rules: &dyn TRules, b_equivalent_cards: bool) -> impl Fn(&SStichSequence, &mut SHandVector) + '_ {
+    move |stichseq, veccard_allowed| {
+        if !b_equivalent_cards {
+            return;
+        }
+        let mut veccard_in_play = SCard::values(stichseq.kurzlang())
+            .filter(|card| !stichseq.completed_stichs().iter().any(|stich| stich.iter().any(|(_epi, card_stich)| card_stich==card)))
+            .collect::<Vec<_>>();
+        rules.sort_cards_first_trumpf_then_farbe(&mut veccard_in_play);
+        let mut vecveccard_run : Vec<Vec<SCard>> = Vec::new();
+        let mut ocard_prev = None;
+        for card in veccard_in_play {
+            if veccard_allowed.contains(&card) {
+                match ocard_prev {
+                    Some(card_prev) if rules.trumpforfarbe(card_prev)==rules.trumpforfarbe(card)
+                        && rules.cards_interchangeable_if_adjacent(card_prev, card)
+                    => unwrap!(vecveccard_run.last_mut()).push(card),
+                    _ => vecveccard_run.push(vec![card]),
+                }
+                ocard_prev = Some(card);
+            } else {
+                ocard_prev = None;
+            }
+        }
+        veccard_allowed.retain(|card| vecveccard_run.iter().any(|veccard_run|
+            // min_by_key returns the first minimum, so runs with equal points are represented by a single card
+            veccard_run.iter().min_by_key(|card_run| points_card(**card_run))==Some(card)
+                || veccard_run.iter().min_by_key(|card_run| -points_card(**card_run))==Some(card)
+        ));
+        assert!(!veccard_allowed.is_empty());
+    }
+}
+
 fn explore_snapshots_internal<ForEachSnapshot>(
     ahand: &mut EnumMap<EPlayerIndex, SHand>,
     rules: &dyn TRules,
@@ -165,11 +222,13 @@ fn explore_snapshots_internal<ForEachSnapshot>(
     stichseq: &mut SStichSequence,
     func_filter_allowed_cards: &impl Fn(&SStichSequence, &mut SHandVector),
     foreachsnapshot: &ForEachSnapshot,
+    bounds: ForEachSnapshot::Bounds,
+    opairsnapshotcachen_stich_min: Option<(&SSnapshotCache<ForEachSnapshot>, usize)>,
     snapshotvisualizer: &mut impl TSnapshotVisualizer,
 ) -> ForEachSnapshot::Output 
     where
         ForEachSnapshot: TForEachSnapshot,
-        ForEachSnapshot::Output : fmt::Debug,
+        ForEachSnapshot::Output : fmt::Debug + Clone,
 {
     snapshotvisualizer.begin_snapshot(stichseq, &ahand);
     let epi_current = unwrap!(stichseq.current_stich().current_playerindex());
@@ -179,7 +238,7 @@ fn explore_snapshots_internal<ForEachSnapshot>(
     ) {
         macro_rules! for_each_allowed_card{
             (($i_offset_0: expr, $($i_offset: expr,)*), $stichseq: expr) => {{
-                let epi = epi_current.wrapping_add($i_offset_0);
+                let epi = unwrap!($stichseq.current_stich().current_playerindex()); // skips empty seats
                 let card = debug_verify_eq!(
                     ahand[epi].cards(),
                     &rules.all_allowed_cards($stichseq, &ahand[epi])
@@ -206,57 +265,160 @@ fn explore_snapshots_internal<ForEachSnapshot>(
                 output
             }};
         }
-        match stichseq.current_stich().size() {
-            0 => for_each_allowed_card!((0, 1, 2, 3,), stichseq),
-            1 => for_each_allowed_card!((0, 1, 2,), stichseq),
+        match stichseq.current_stich().n_players() - stichseq.current_stich().size() {
+            4 => for_each_allowed_card!((0, 1, 2, 3,), stichseq),
+            3 => for_each_allowed_card!((0, 1, 2,), stichseq),
             2 => for_each_allowed_card!((0, 1,), stichseq),
-            3 => for_each_allowed_card!((0,), stichseq),
-            n_stich_size => {
-                assert_eq!(n_stich_size, 4);
+            1 => for_each_allowed_card!((0,), stichseq),
+            n_cards_remaining => {
+                assert_eq!(n_cards_remaining, 0);
                 for_each_allowed_card!((), stichseq)
             },
         }
     } else {
-        foreachsnapshot.pruned_output(stichseq, &ahand, rulestatecache).unwrap_or_else(|| {
-            let mut veccard_allowed = rules.all_allowed_cards(stichseq, &ahand[epi_current]);
-            func_filter_allowed_cards(stichseq, &mut veccard_allowed);
-            // TODO? use equivalent card optimization
-            foreachsnapshot.combine_outputs(
-                epi_current,
-                veccard_allowed.into_iter().map(|card| {
-                    ahand[epi_current].play_card(card);
-                    let output = stichseq.zugeben_and_restore(card, rules, |stichseq| {
-                        macro_rules! next_step {() => {explore_snapshots_internal(
-                            ahand,
-                            rules,
-                            rulestatecache,
-                            stichseq,
-                            func_filter_allowed_cards,
-                            foreachsnapshot,
-                            snapshotvisualizer,
-                        )}}
-                        if stichseq.current_stich().is_empty() {
-                            let unregisterstich = rulestatecache.register_stich(
-                                unwrap!(stichseq.completed_stichs().last()),
-                                stichseq.current_stich().first_playerindex(),
-                            );
-                            let output = next_step!();
-                            rulestatecache.unregister_stich(unregisterstich);
-                            output
-                        } else {
-                            next_step!()
-                        }
-                    });
-                    ahand[epi_current].add_card(card);
-                    (card, output)
-                })
-            )
-        })
+        let opairsnapshotcachekey = opairsnapshotcachen_stich_min
+            .filter(|&(_snapshotcache, n_stich_min)| {
+                stichseq.current_stich().is_empty() // cards within a stich are played in a fixed order anyway
+                    && n_stich_min <= stichseq.completed_stichs().len()
+                    && N_CARDS_PER_HAND_MIN_SNAPSHOT_CACHE <= ahand[epi_current].cards().len() // small subtrees are cheaper to explore than to cache
+            })
+            .map(|(snapshotcache, _n_stich_min)| (
+                snapshotcache,
+                SSnapshotCacheKey::new(foreachsnapshot, rules, stichseq, ahand, rulestatecache),
+            ));
+        foreachsnapshot.pruned_output(stichseq, ahand, rulestatecache)
+            .or_else(|| opairsnapshotcachekey.as_ref().and_then(|(snapshotcache, snapshotcachekey)| snapshotcache.get(snapshotcachekey)))
+            .unwrap_or_else(|| {
+                let mut veccard_allowed = rules.all_allowed_cards(stichseq, &ahand[epi_current]);
+                func_filter_allowed_cards(stichseq, &mut veccard_allowed);
+                let output = foreachsnapshot.combine_outputs(
+                    epi_current,
+                    bounds,
+                    veccard_allowed,
+                    |card, bounds_card| {
+                        ahand[epi_current].play_card(card);
+                        let output = stichseq.zugeben_and_restore(card, rules, |stichseq| {
+                            macro_rules! next_step {() => {explore_snapshots_internal(
+                                ahand,
+                                rules,
+                                rulestatecache,
+                                stichseq,
+                                func_filter_allowed_cards,
+                                foreachsnapshot,
+                                bounds_card,
+                                opairsnapshotcachen_stich_min,
+                                snapshotvisualizer,
+                            )}}
+                            if stichseq.current_stich().is_empty() {
+                                let unregisterstich = rulestatecache.register_stich(
+                                    unwrap!(stichseq.completed_stichs().last()),
+                                    stichseq.current_stich().first_playerindex(),
+                                );
+                                let output = next_step!();
+                                rulestatecache.unregister_stich(unregisterstich);
+                                output
+                            } else {
+                                next_step!()
+                            }
+                        });
+                        ahand[epi_current].add_card(card);
+                        output
+                    },
+                );
+                if let Some((snapshotcache, snapshotcachekey)) = opairsnapshotcachekey {
+                    if foreachsnapshot.output_is_exact(&bounds, &output) {
+                        snapshotcache.insert(snapshotcachekey, &output);
+                    }
+                }
+                output
+            })
     };
     snapshotvisualizer.end_snapshot(&output);
     output
 }
 
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct SSnapshotCacheKey<CacheParams> {
+    cacheparams: CacheParams,
+    mapepin_cards: EnumMap<EPlayerIndex, u32>, // remaining cards as bit sets
+    epi_first: EPlayerIndex, // current stich is empty
+    rulestatecache: SRuleStateCache,
+    n_discriminator: usize,
+}
+
+impl<CacheParams> SSnapshotCacheKey<CacheParams> {
+    fn new<ForEachSnapshot: TForEachSnapshot<CacheParams=CacheParams>>(
+        foreachsnapshot: &ForEachSnapshot,
+        rules: &dyn TRules,
+        stichseq: &SStichSequence,
+        ahand: &EnumMap<EPlayerIndex, SHand>,
+        rulestatecache: &SRuleStateCache,
+    ) -> Self {
+        assert!(stichseq.current_stich().is_empty());
+        Self {
+            cacheparams: foreachsnapshot.cache_params(),
+            mapepin_cards: ahand.map(|hand| hand.cards().iter().fold(0, |n_cards, card| n_cards | (1 << card.to_usize()))),
+            epi_first: stichseq.current_stich().first_playerindex(),
+            rulestatecache: rulestatecache.clone(),
+            n_discriminator: rules.snapshot_cache_discriminator(stichseq),
+        }
+    }
+}
+
+const N_CARDS_PER_HAND_MIN_SNAPSHOT_CACHE : usize = 3;
+const N_SNAPSHOT_CACHE_SHARDS : usize = 64; // separately locked, so that threads rarely wait for each other
+
+type SSnapshotCacheShard<ForEachSnapshot> = Mutex<HashMap<
+    SSnapshotCacheKey<<ForEachSnapshot as TForEachSnapshot>::CacheParams>,
+    <ForEachSnapshot as TForEachSnapshot>::Output,
+>>;
+
+// outputs of already explored snapshots, shareable among threads exploring the same game
+pub struct SSnapshotCache<ForEachSnapshot: TForEachSnapshot> {
+    vecmapsnapshotcachekeyoutput: Vec<SSnapshotCacheShard<ForEachSnapshot>>,
+    n_entries_per_shard_max: usize,
+}
+
+impl<ForEachSnapshot: TForEachSnapshot> SSnapshotCache<ForEachSnapshot>
+    where
+        ForEachSnapshot::Output: Clone + fmt::Debug,
+{
+    pub fn new(n_bytes_max: usize) -> Self {
+        // rough estimate ignoring the overhead of HashMap
+        let n_bytes_per_entry = std::mem::size_of::<SSnapshotCacheKey<ForEachSnapshot::CacheParams>>()
+            + std::mem::size_of::<ForEachSnapshot::Output>();
+        Self {
+            vecmapsnapshotcachekeyoutput: (0..N_SNAPSHOT_CACHE_SHARDS)
+                .map(|_i_shard| Mutex::new(HashMap::new()))
+                .collect(),
+            n_entries_per_shard_max: n_bytes_max / n_bytes_per_entry / N_SNAPSHOT_CACHE_SHARDS,
+        }
+    }
+
+    fn shard(&self, snapshotcachekey: &SSnapshotCacheKey<ForEachSnapshot::CacheParams>) -> &Mutex<HashMap<SSnapshotCacheKey<ForEachSnapshot::CacheParams>, ForEachSnapshot::Output>> {
+        let mut hasher = DefaultHasher::new();
+        snapshotcachekey.hash(&mut hasher);
+        &self.vecmapsnapshotcachekeyoutput[(hasher.finish() % N_SNAPSHOT_CACHE_SHARDS.as_num::<u64>()).as_num::<usize>()]
+    }
+
+    fn get(&self, snapshotcachekey: &SSnapshotCacheKey<ForEachSnapshot::CacheParams>) -> Option<ForEachSnapshot::Output> {
+        unwrap!(self.shard(snapshotcachekey).lock()).get(snapshotcachekey).cloned()
+    }
+
+    fn insert(&self, snapshotcachekey: SSnapshotCacheKey<ForEachSnapshot::CacheParams>, output: &ForEachSnapshot::Output) {
+        let mut mapsnapshotcachekeyoutput = unwrap!(self.shard(&snapshotcachekey).lock());
+        if mapsnapshotcachekeyoutput.len() < self.n_entries_per_shard_max { // once full, we simply keep what we have
+            mapsnapshotcachekeyoutput.insert(snapshotcachekey, output.clone());
+        }
+    }
+
+    pub fn count_entries(&self) -> usize {
+        self.vecmapsnapshotcachekeyoutput.iter()
+            .map(|mapsnapshotcachekeyoutput| unwrap!(mapsnapshotcachekeyoutput.lock()).len())
+            .sum()
+    }
+}
+
 #[derive(Clone, new)]
 pub struct SMinReachablePayoutBase<'rules, Pruner> {
     rules: &'rules dyn TRules,
@@ -270,7 +432,7 @@ impl<'rules, Pruner> SMinReachablePayoutBase<'rules, Pruner> {
         Self::new(
             game.rules.as_ref(),
             unwrap!(game.current_playable_stich().current_playerindex()),
-            /*tpln_stoss_doubling*/stoss_and_doublings(&game.vecstoss, &game.doublings),
+            /*tpln_stoss_doubling*/stoss_and_doublings(&game.vecstoss, &game.doublings, game.n_bock),
             game.n_stock,
         )
     }
@@ -299,6 +461,12 @@ impl SMinMax {
 
 impl<Pruner: TPruner> TForEachSnapshot for SMinReachablePayoutBase<'_, Pruner> {
     type Output = SMinMax;
+    type Bounds = ();
+    type CacheParams = (EPlayerIndex, (usize, usize), isize);
+
+    fn cache_params(&self) -> Self::CacheParams {
+        (self.epi, self.tpln_stoss_doubling, self.n_stock)
+    }
 
     fn final_output(&self, slcstich: SStichSequenceGameFinished, rulestatecache: &SRuleStateCache) -> Self::Output {
         SMinMax::new_final(self.rules.payout_with_cache(slcstich, self.tpln_stoss_doubling, self.n_stock, rulestatecache))
@@ -308,12 +476,14 @@ impl<Pruner: TPruner> TForEachSnapshot for SMinReachablePayoutBase<'_, Pruner> {
         Pruner::pruned_output(&self, stichseq, ahand, rulestatecache)
     }
 
-    fn combine_outputs<ItTplCardOutput: Iterator<Item=(SCard, Self::Output)>>(
+    fn combine_outputs(
         &self,
         epi_card: EPlayerIndex,
-        ittplcardoutput: ItTplCardOutput,
+        _bounds: Self::Bounds,
+        veccard_allowed: SHandVector,
+        mut fn_output: impl FnMut(SCard, Self::Bounds) -> Self::Output,
     ) -> Self::Output {
-        let itminmax = ittplcardoutput.map(|(_card, minmax)| minmax);
+        let itminmax = veccard_allowed.into_iter().map(|card| fn_output(card, ()));
         unwrap!(if self.epi==epi_card {
             itminmax.fold1(mutate_return!(|minmax_acc, minmax| {
                 // self.epi can always play as good as possible
@@ -374,6 +544,143 @@ impl<Pruner: TPruner> TForEachSnapshot for SMinReachablePayoutBase<'_, Pruner> {
 }
 
 pub type SMinReachablePayout<'rules> = SMinReachablePayoutBase<'rules, SPrunerNothing>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct SAlphaBeta {
+    n_alpha: isize, // payout that epi can already enforce
+    n_beta: isize, // payout that the other players can already restrict epi to
+}
+
+impl Default for SAlphaBeta {
+    fn default() -> Self {
+        Self {
+            n_alpha: isize::MIN,
+            n_beta: isize::MAX,
+        }
+    }
+}
+
+// computes t_min of SMinReachablePayout, skipping cards that cannot affect it
+#[derive(Clone, new)]
+pub struct SMinReachablePayoutAlphaBeta<'rules> {
+    rules: &'rules dyn TRules,
+    epi: EPlayerIndex,
+    tpln_stoss_doubling: (usize, usize),
+    n_stock: isize,
+}
+
+impl TForEachSnapshot for SMinReachablePayoutAlphaBeta<'_> {
+    type Output = EnumMap<EPlayerIndex, isize>;
+    type Bounds = SAlphaBeta;
+    type CacheParams = (EPlayerIndex, (usize, usize), isize);
+
+    fn cache_params(&self) -> Self::CacheParams {
+        (self.epi, self.tpln_stoss_doubling, self.n_stock)
+    }
+
+    fn final_output(&self, slcstich: SStichSequenceGameFinished, rulestatecache: &SRuleStateCache) -> Self::Output {
+        self.rules.payout_with_cache(slcstich, self.tpln_stoss_doubling, self.n_stock, rulestatecache)
+    }
+
+    fn pruned_output(&self, _stichseq: &SStichSequence, _ahand: &EnumMap<EPlayerIndex, SHand>, _rulestatecache: &SRuleStateCache) -> Option<Self::Output> {
+        None
+    }
+
+    fn combine_outputs(
+        &self,
+        epi_card: EPlayerIndex,
+        mut alphabeta: Self::Bounds,
+        veccard_allowed: SHandVector,
+        mut fn_output: impl FnMut(SCard, Self::Bounds) -> Self::Output,
+    ) -> Self::Output {
+        let mut oan_payout_best : Option<Self::Output> = None;
+        for card in veccard_allowed {
+            let an_payout = fn_output(card, alphabeta);
+            let n_payout = an_payout[self.epi];
+            let ordering = if self.epi==epi_card {
+                // self.epi can always play as good as possible
+                assign_max(&mut alphabeta.n_alpha, n_payout);
+                Ordering::Greater
+            } else {
+                // other players may play inconveniently for self.epi
+                assign_min(&mut alphabeta.n_beta, n_payout);
+                Ordering::Less
+            };
+            match oan_payout_best {
+                None => oan_payout_best = Some(an_payout),
+                Some(ref mut an_payout_best) => assign_by_key_ordering(
+                    an_payout_best,
+                    an_payout,
+                    |an_payout| an_payout[self.epi],
+                    ordering,
+                ),
+            }
+            if alphabeta.n_beta <= alphabeta.n_alpha {
+                break; // an ancestor will choose another card anyway
+            }
+        }
+        unwrap!(oan_payout_best)
+    }
+
+    fn output_is_exact(&self, alphabeta: &Self::Bounds, an_payout: &Self::Output) -> bool {
+        // otherwise, an_payout[self.epi] is only a bound
+        alphabeta.n_alpha < an_payout[self.epi] && an_payout[self.epi] < alphabeta.n_beta
+    }
+}
+
+// counts the snapshots explored by foreachsnapshot, e.g. to compare search strategies
+pub struct SNodeCounter<ForEachSnapshot> {
+    foreachsnapshot: ForEachSnapshot,
+    n_nodes: AtomicUsize,
+}
+
+impl<ForEachSnapshot> SNodeCounter<ForEachSnapshot> {
+    pub fn new(foreachsnapshot: ForEachSnapshot) -> Self {
+        Self {
+            foreachsnapshot,
+            n_nodes: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn count_nodes(&self) -> usize {
+        self.n_nodes.load(AtomicOrdering::Relaxed)
+    }
+}
+
+impl<ForEachSnapshot: TForEachSnapshot> TForEachSnapshot for SNodeCounter<ForEachSnapshot> {
+    type Output = ForEachSnapshot::Output;
+    type Bounds = ForEachSnapshot::Bounds;
+    type CacheParams = ForEachSnapshot::CacheParams;
+
+    fn cache_params(&self) -> Self::CacheParams {
+        self.foreachsnapshot.cache_params()
+    }
+
+    fn final_output(&self, slcstich: SStichSequenceGameFinished, rulestatecache: &SRuleStateCache) -> Self::Output {
+        self.n_nodes.fetch_add(1, AtomicOrdering::Relaxed);
+        self.foreachsnapshot.final_output(slcstich, rulestatecache)
+    }
+
+    fn pruned_output(&self, stichseq: &SStichSequence, ahand: &EnumMap<EPlayerIndex, SHand>, rulestatecache: &SRuleStateCache) -> Option<Self::Output> {
+        self.n_nodes.fetch_add(1, AtomicOrdering::Relaxed); // called once per inner node
+        self.foreachsnapshot.pruned_output(stichseq, ahand, rulestatecache)
+    }
+
+    fn combine_outputs(
+        &self,
+        epi_card: EPlayerIndex,
+        bounds: Self::Bounds,
+        veccard_allowed: SHandVector,
+        fn_output: impl FnMut(SCard, Self::Bounds) -> Self::Output,
+    ) -> Self::Output {
+        self.foreachsnapshot.combine_outputs(epi_card, bounds, veccard_allowed, fn_output)
+    }
+
+    fn output_is_exact(&self, bounds: &Self::Bounds, output: &Self::Output) -> bool {
+        self.foreachsnapshot.output_is_exact(bounds, output)
+    }
+}
+
 pub type SMinReachablePayoutLowerBoundViaHint<'rules> = SMinReachablePayoutBase<'rules, SPrunerViaHint>;
 
 pub trait TPruner : Sized {
@@ -1,9 +1,23 @@
 use crate::ai::{suspicion::*, *};
-use crate::game;
+use crate::game::{self, SStichSequence};
 use crate::player::{playerrandom::SPlayerRandom, TPlayer};
 use crate::primitives::*;
 use crate::rules::{ruleset::*, *};
 use crate::util::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+fn announceable_rules_and_ramsch(str_ruleset: &str, rulesramsch: rulesramsch::SRulesRamsch) -> Vec<Box<dyn TRules>> {
+    let ruleset = unwrap!(SRuleSet::from_string(str_ruleset));
+    let mut vecrules : Vec<Box<dyn TRules>> = ruleset.announceable_rules(EPlayerIndex::EPI0).collect();
+    vecrules.push(Box::new(rulesramsch));
+    vecrules
+}
+
+// selfish strategies depend on the order in which ties are encountered, so we only compare the actual bounds
+fn assert_bounds_eq(minmax: &SMinMax, minmax_other: &SMinMax, epi: EPlayerIndex, fn_str_context: impl Fn()->String) {
+    assert_eq!(minmax.t_min[epi], minmax_other.t_min[epi], "{}", fn_str_context());
+    assert_eq!(minmax.t_max[epi], minmax_other.t_max[epi], "{}", fn_str_context());
+}
 
 #[test]
 fn test_determine_best_card() {
@@ -19,6 +33,7 @@ fn test_determine_best_card() {
         game::SDoublings::new(SStaticEPI0{}),
         Some(SStossParams::new(
             /*n_stoss_max*/4,
+            EStossDeadline::NoCardPlayed,
         )),
         <dyn TRules>::box_clone(&rulesrufspiel::SRulesRufspiel::new(EPlayerIndex::EPI0, EFarbe::Eichel, payoutdecider::SPayoutDeciderParams::new(
             /*n_payout_base*/100,
@@ -28,6 +43,7 @@ fn test_determine_best_card() {
                 /*n_lauf_lbound*/3,
             ),
         ))),
+        /*n_bock*/0,
         /*n_stock*/0,
     );
     fn play_stichs(game: &mut SGame, slctplepistich: &[(EPlayerIndex, [SCard; 4])]) {
@@ -85,7 +101,7 @@ fn test_determine_best_card() {
 #[test]
 fn detect_expensive_all_possible_hands() {
     crate::game::run::run_simple_game_loop(
-        EPlayerIndex::map_from_fn(|_epi| Box::new(SPlayerRandom::new(
+        &EPlayerIndex::map_from_fn(|_epi| Box::new(SPlayerRandom::new(
             /*fn_check_ask_for_card*/|game: &SGame| {
                 if game.kurzlang().cards_per_player() - 4 < game.completed_stichs().len() {
                     let epi_fixed = unwrap!(game.current_playable_stich().current_playerindex());
@@ -93,6 +109,7 @@ fn detect_expensive_all_possible_hands() {
                         &game.stichseq,
                         game.ahand[epi_fixed].clone(),
                         epi_fixed,
+                        game.revealed_hand(),
                         game.rules.as_ref()
                     )
                         .collect::<Vec<_>>();
@@ -115,18 +132,23 @@ fn detect_expensive_all_possible_hands() {
                         struct SLeafCounter;
                         impl TForEachSnapshot for SLeafCounter {
                             type Output = usize;
+                            type Bounds = ();
+                            type CacheParams = ();
+                            fn cache_params(&self) -> Self::CacheParams {}
                             fn final_output(&self, _slcstich: SStichSequenceGameFinished, _rulestatecache: &SRuleStateCache) -> Self::Output {
                                 1 // leaf
                             }
                             fn pruned_output(&self, _stichseq: &SStichSequence, _ahand: &EnumMap<EPlayerIndex, SHand>, _rulestatecache: &SRuleStateCache) -> Option<Self::Output> {
                                 None
                             }
-                            fn combine_outputs<ItTplCardOutput: Iterator<Item=(SCard, Self::Output)>>(
+                            fn combine_outputs(
                                 &self,
                                 _epi_card: EPlayerIndex,
-                                ittplcardoutput: ItTplCardOutput,
+                                _bounds: Self::Bounds,
+                                veccard_allowed: SHandVector,
+                                mut fn_output: impl FnMut(SCard, Self::Bounds) -> Self::Output,
                             ) -> Self::Output {
-                                ittplcardoutput.map(|tplcardoutput| tplcardoutput.1).sum()
+                                veccard_allowed.into_iter().map(|card| fn_output(card, ())).sum()
                             }
                         }
                         assert_bound(
@@ -136,6 +158,7 @@ fn detect_expensive_all_possible_hands() {
                                 &mut game.stichseq.clone(),
                                 &|_vecstich_complete, _vecstich_successor| {/*no filtering*/},
                                 &SLeafCounter{},
+                                /*osnapshotcache*/None,
                                 /*opath_out_dir*/None,
                             ),
                             2000
@@ -144,21 +167,440 @@ fn detect_expensive_all_possible_hands() {
                 }
             },
         )) as Box<dyn TPlayer>),
-        /*n_games*/4,
-        unwrap!(SRuleSet::from_string(
-            r"
-            base-price=10
-            solo-price=50
-            lauf-min=3
-            [rufspiel]
-            [solo]
-            [wenz]
-            lauf-min=2
-            [stoss]
-            max=3
-            ",
-        ))
+        &mut crate::game::session::SSession::new(
+            unwrap!(SRuleSet::from_string(
+                r"
+                base-price=10
+                solo-price=50
+                lauf-min=3
+                [rufspiel]
+                [solo]
+                [wenz]
+                lauf-min=2
+                [stoss]
+                max=3
+                ",
+            )),
+            /*dealsource*/crate::game::deal::VDealSource::new_random(EKurzLang::Lang),
+            crate::game::session::VSessionLength::Games(4),
+            /*mapepiparticipant*/EPlayerIndex::map_from_fn(|epi| epi),
+            /*opath_record*/None,
+        ),
+        /*fn_gameresult*/|_gameresult| {},
+    );
+}
+
+#[test]
+fn test_ramsch_pruning_via_hint() {
+    use crate::rules::rulesramsch::*;
+    let mut rng = StdRng::seed_from_u64(0);
+    for rules in [
+        SRulesRamsch::new(/*n_price*/10, VDurchmarsch::None, EJungfrau::Ignore, /*b_schieben*/false, /*b_loser_pays_into_stock*/false),
+        SRulesRamsch::new(/*n_price*/10, VDurchmarsch::All, EJungfrau::DoubleOnce, /*b_schieben*/false, /*b_loser_pays_into_stock*/false),
+        SRulesRamsch::new(/*n_price*/10, VDurchmarsch::AtLeast(91), EJungfrau::DoubleEach, /*b_schieben*/false, /*b_loser_pays_into_stock*/false),
+    ] {
+        for _i_sample in 0..5 {
+            let mut ahand = game::random_ahand(EKurzLang::Lang, &mut rng);
+            let stichseq = game::play_random_cards(&rules, &mut ahand, /*n_cards*/5*EPlayerIndex::SIZE, &mut rng);
+            let mapepipayouthint = rules.payouthints(
+                &stichseq,
+                &ahand,
+                &SRuleStateCache::new(&stichseq, &ahand, |stich| rules.winner_index(stich)),
+            );
+            for epi in EPlayerIndex::values() {
+                let minmax = explore_snapshots(
+                    &mut ahand.clone(),
+                    &rules,
+                    &mut stichseq.clone(),
+                    &|_stichseq, _veccard_allowed| {/*no filtering*/},
+                    &SMinReachablePayout::new(&rules, epi, /*tpln_stoss_doubling*/(0, 0), /*n_stock*/0),
+                    /*osnapshotcache*/None,
+                    /*opath_out_dir*/None,
+                );
+                let minmax_pruned = explore_snapshots(
+                    &mut ahand.clone(),
+                    &rules,
+                    &mut stichseq.clone(),
+                    &|_stichseq, _veccard_allowed| {/*no filtering*/},
+                    &SMinReachablePayoutLowerBoundViaHint::new(&rules, epi, /*tpln_stoss_doubling*/(0, 0), /*n_stock*/0),
+                    /*osnapshotcache*/None,
+                    /*opath_out_dir*/None,
+                );
+                let payout_bound = |opayoutinfo: &Option<SPayoutInfo>| opayoutinfo.as_ref()
+                    .map(|payoutinfo| payoutinfo.payout_including_stock(/*n_stock*/0, /*tpln_stoss_doubling*/(0, 0)));
+                // pruning yields lower bounds, but must not change whether epi wins or loses
+                for (an_payout, an_payout_pruned) in [
+                    (&minmax.t_min, &minmax_pruned.t_min),
+                    (&minmax.t_max, &minmax_pruned.t_max),
+                ] {
+                    assert!(an_payout_pruned[epi] <= an_payout[epi], "{:?}\n{:?}\n{:?}", stichseq, minmax, minmax_pruned);
+                    assert_eq!(0 < an_payout_pruned[epi], 0 < an_payout[epi], "{:?}\n{:?}\n{:?}", stichseq, minmax, minmax_pruned);
+                    // exact payouts lie within the bounds given by the hint
+                    assert!(payout_bound(mapepipayouthint[epi].lower_bound()).is_none_or(|n_lower| n_lower<=an_payout[epi]), "{:?}\n{:?}\n{:?}", stichseq, minmax, mapepipayouthint);
+                    assert!(payout_bound(mapepipayouthint[epi].upper_bound()).is_none_or(|n_upper| an_payout[epi]<=n_upper), "{:?}\n{:?}\n{:?}", stichseq, minmax, mapepipayouthint);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_equivalent_cards_filter() {
+    use std::cell::Cell;
+    let mut rng = StdRng::seed_from_u64(0);
+    let vecrules = announceable_rules_and_ramsch(
+        r"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [rufspiel]
+        [solo]
+        [wenz]
+        [farbwenz]
+        [geier]
+        [farbgeier]
+        [bettel]
+        ",
+        rulesramsch::SRulesRamsch::new(/*n_price*/20, rulesramsch::VDurchmarsch::None, rulesramsch::EJungfrau::Ignore, /*b_schieben*/false, /*b_loser_pays_into_stock*/false),
+    );
+    let n_cards_filtered = Cell::new(0);
+    for rules in vecrules.iter() {
+        let rules = rules.as_ref();
+        for _i_sample in 0..if_dbg_else!({2}{5}) {
+            let mut ahand = game::random_ahand_playable(rules, EKurzLang::Lang, &mut rng);
+            let stichseq = game::play_random_cards(rules, &mut ahand, /*n_cards*/if_dbg_else!({6}{4})*EPlayerIndex::SIZE, &mut rng);
+            let equivalent_cards_filter = equivalent_cards_filter(rules, /*b_equivalent_cards*/true);
+            for epi in EPlayerIndex::values() {
+                let minmax = explore_snapshots(
+                    &mut ahand.clone(),
+                    rules,
+                    &mut stichseq.clone(),
+                    &|_stichseq, _veccard_allowed| {/*no filtering*/},
+                    &SMinReachablePayout::new(rules, epi, /*tpln_stoss_doubling*/(0, 0), /*n_stock*/0),
+                    /*osnapshotcache*/None,
+                    /*opath_out_dir*/None,
+                );
+                let minmax_filtered = explore_snapshots(
+                    &mut ahand.clone(),
+                    rules,
+                    &mut stichseq.clone(),
+                    &|stichseq, veccard_allowed| {
+                        let n_cards_allowed = veccard_allowed.len();
+                        equivalent_cards_filter(stichseq, veccard_allowed);
+                        n_cards_filtered.set(n_cards_filtered.get() + n_cards_allowed - veccard_allowed.len());
+                    },
+                    &SMinReachablePayout::new(rules, epi, /*tpln_stoss_doubling*/(0, 0), /*n_stock*/0),
+                    /*osnapshotcache*/None,
+                    /*opath_out_dir*/None,
+                );
+                assert_bounds_eq(&minmax, &minmax_filtered, epi, || format!("{}\n{:?}\n{:?}\n{:?}\n{:?}", rules, stichseq, ahand, minmax, minmax_filtered));
+            }
+        }
+    }
+    assert!(0 < n_cards_filtered.get());
+    {
+        // GA and GK are interchangeable once GZ is gone, but differ in points; G9 is equivalent to GK
+        use crate::card::card_values::*;
+        let rules = unwrap!(vecrules.iter().find(|rules| rules.to_string()=="HerzSolo")).as_ref();
+        let mut stichseq = SStichSequence::new(EKurzLang::Lang);
+        for card in [GZ, G7, G8, HA] {
+            stichseq.zugeben(card, rules);
+        }
+        let veccard_allowed : SHandVector = [GA, GK, G9, S7].iter().copied().collect();
+        let mut veccard_filtered = veccard_allowed.clone();
+        equivalent_cards_filter(rules, /*b_equivalent_cards*/true)(&stichseq, &mut veccard_filtered);
+        assert_eq!(veccard_filtered.as_slice(), [GA, G9, S7]);
+        let mut veccard_unfiltered = veccard_allowed.clone();
+        equivalent_cards_filter(rules, /*b_equivalent_cards*/false)(&stichseq, &mut veccard_unfiltered);
+        assert_eq!(veccard_unfiltered, veccard_allowed);
+    }
+}
+
+#[test]
+fn test_snapshot_cache() {
+    use rand::prelude::IteratorRandom;
+    let mut rng = StdRng::seed_from_u64(0);
+    let vecrules = announceable_rules_and_ramsch(
+        r"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [rufspiel]
+        [hochzeit]
+        [solo]
+        [wenz]
+        [bettel]
+        ",
+        rulesramsch::SRulesRamsch::new(/*n_price*/20, rulesramsch::VDurchmarsch::All, rulesramsch::EJungfrau::DoubleEach, /*b_schieben*/false, /*b_loser_pays_into_stock*/false),
+    );
+    for rules in vecrules.iter().choose_multiple(&mut rng, 2) {
+        let rules = rules.as_ref();
+        let mut ahand = game::random_ahand_playable(rules, EKurzLang::Lang, &mut rng);
+        let stichseq = game::play_random_cards(rules, &mut ahand, /*n_cards*/if_dbg_else!({6}{3})*EPlayerIndex::SIZE, &mut rng);
+        let epi = unwrap!(EPlayerIndex::values().choose(&mut rng));
+        let explore = |osnapshotcache| explore_snapshots(
+            &mut ahand.clone(),
+            rules,
+            &mut stichseq.clone(),
+            &equivalent_cards_filter(rules, /*b_equivalent_cards*/true),
+            &SMinReachablePayout::new(rules, epi, /*tpln_stoss_doubling*/(0, 0), /*n_stock*/0),
+            osnapshotcache,
+            /*opath_out_dir*/None,
+        );
+        let minmax = explore(None);
+        let snapshotcache_no_memory = SSnapshotCache::new(/*n_bytes_max*/0);
+        assert_eq!(minmax, explore(Some(&snapshotcache_no_memory)));
+        assert_eq!(0, snapshotcache_no_memory.count_entries());
+        let snapshotcache = SSnapshotCache::new(/*n_bytes_max*/1024*1024);
+        // second exploration with cache reuses the outputs of the first one
+        for minmax_cached in [explore(Some(&snapshotcache)), explore(Some(&snapshotcache))] {
+            assert_bounds_eq(&minmax, &minmax_cached, epi, || format!("{}\n{:?}\n{:?}", rules, stichseq, ahand));
+        }
+        // debug builds only explore snapshots too small to be cached
+        assert!(if_dbg_else!({0==snapshotcache.count_entries()}{0<snapshotcache.count_entries()}));
+    }
+}
+
+#[test]
+fn test_alphabeta() {
+    use rand::prelude::IteratorRandom;
+    let mut rng = StdRng::seed_from_u64(0);
+    let vecrules = announceable_rules_and_ramsch(
+        r"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [rufspiel]
+        [hochzeit]
+        [solo]
+        [wenz]
+        [bettel]
+        ",
+        rulesramsch::SRulesRamsch::new(/*n_price*/20, rulesramsch::VDurchmarsch::All, rulesramsch::EJungfrau::DoubleEach, /*b_schieben*/false, /*b_loser_pays_into_stock*/false),
+    );
+    let mut n_nodes_total = 0;
+    let mut n_nodes_alphabeta_total = 0;
+    for rules in vecrules.iter().choose_multiple(&mut rng, 3) {
+        let rules = rules.as_ref();
+        let mut ahand = game::random_ahand_playable(rules, EKurzLang::Lang, &mut rng);
+        let stichseq = game::play_random_cards(rules, &mut ahand, /*n_cards*/if_dbg_else!({5}{3})*EPlayerIndex::SIZE, &mut rng);
+        let epi = unwrap!(EPlayerIndex::values().choose(&mut rng));
+        macro_rules! explore{($foreachsnapshot: expr, $osnapshotcache: expr) => {{
+            let nodecounter = SNodeCounter::new($foreachsnapshot);
+            let output = explore_snapshots(
+                &mut ahand.clone(),
+                rules,
+                &mut stichseq.clone(),
+                &equivalent_cards_filter(rules, /*b_equivalent_cards*/true),
+                &nodecounter,
+                $osnapshotcache,
+                /*opath_out_dir*/None,
+            );
+            (output, nodecounter.count_nodes())
+        }}}
+        let (minmax, n_nodes) = explore!(
+            SMinReachablePayout::new(rules, epi, /*tpln_stoss_doubling*/(0, 0), /*n_stock*/0),
+            /*osnapshotcache*/None
+        );
+        let (an_payout_alphabeta, n_nodes_alphabeta) = explore!(
+            SMinReachablePayoutAlphaBeta::new(rules, epi, /*tpln_stoss_doubling*/(0, 0), /*n_stock*/0),
+            /*osnapshotcache*/None
+        );
+        assert_eq!(minmax.t_min, an_payout_alphabeta, "{}\n{:?}\n{:?}", rules, stichseq, ahand);
+        assert!(n_nodes_alphabeta <= n_nodes);
+        n_nodes_total += n_nodes;
+        n_nodes_alphabeta_total += n_nodes_alphabeta;
+        let snapshotcache = SSnapshotCache::new(/*n_bytes_max*/1024*1024);
+        for _i_exploration in 0..2 {
+            // cache must only contain outputs that do not depend on bounds
+            let (an_payout_alphabeta_cached, _n_nodes) = explore!(
+                SMinReachablePayoutAlphaBeta::new(rules, epi, /*tpln_stoss_doubling*/(0, 0), /*n_stock*/0),
+                Some(&snapshotcache)
+            );
+            assert_eq!(minmax.t_min[epi], an_payout_alphabeta_cached[epi], "{}\n{:?}\n{:?}", rules, stichseq, ahand);
+        }
+    }
+    assert!(n_nodes_alphabeta_total < n_nodes_total);
+}
+
+#[test]
+fn test_der_alte_muss_simulating() {
+    use crate::card::card_values::*;
+    let ruleset = unwrap!(SRuleSet::from_string(r"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [der-alte-muss]
+    "));
+    let fallbackrules = unwrap!(ruleset.ofallbackrules.as_ref());
+    let aisimulating = SAi::new_simulating(/*n_rank_rules_samples*/1, /*n_suggest_card_branches*/1, /*n_suggest_card_samples*/2);
+    for aacard_hand in [
+        [ // der Alte can call the Schelln-Sau
+            [EA,EZ,EK,E9,E8,E7,GA,GZ],
+            [HA,HZ,HK,H9,H8,H7,GK,G9],
+            [G8,G7,SA,SZ,SK,S9,S8,S7],
+            [EO,GO,HO,SO,EU,GU,HU,SK],
+        ],
+        [ // der Alte cannot call any sau
+            [EO,GO,HO,SO,EU,GU,HU,GA],
+            [HA,HZ,HK,H9,H8,H7,GK,G9],
+            [G8,G7,SK,SZ,SU,S9,S8,S7],
+            [EZ,EA,EK,E9,E8,E7,SA,GZ],
+        ],
+    ] {
+        let ahand = EPlayerIndex::map_from_raw(aacard_hand).map_into(SHand::new_from_iter);
+        let (epi_alter, rulegroup) = unwrap!(fallbackrules.der_alte_muss(&ahand));
+        for rules in rulegroup.vecorules.iter().map(|orules| unwrap!(orules.as_ref())) {
+            let mut game = game::SGame::new(
+                ahand.clone(),
+                game::SDoublings::new(SStaticEPI0{}),
+                /*ostossparams*/None,
+                rules.upcast().box_clone(),
+                /*n_bock*/0,
+                /*n_stock*/0,
+            );
+            // play until it is der Alte's turn, then let him decide based on simulated hands
+            while let Some(epi) = game.current_playable_stich().current_playerindex().filter(|&epi| epi!=epi_alter) {
+                let card = unwrap!(rules.all_allowed_cards(&game.stichseq, &game.ahand[epi]).into_iter().next());
+                unwrap!(game.zugeben(card, epi));
+            }
+            let card = aisimulating.suggest_card(&game, /*opath_out_dir*/None);
+            assert!(rules.all_allowed_cards(&game.stichseq, &game.ahand[epi_alter]).contains(&card));
+        }
+    }
+}
+
+#[test]
+fn test_suggest_card_time_budget() {
+    use rand::prelude::{IteratorRandom, SliceRandom};
+    use std::time::Duration;
+    let mut rng = StdRng::seed_from_u64(0);
+    let ruleset = unwrap!(SRuleSet::from_string(
+        r"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [rufspiel]
+        [solo]
+        [wenz]
+        ",
+    ));
+    let n_remaining_cards_on_hand = N_REMAINING_CARDS_EXHAUSTIVE + 1; // fewer cards are explored exhaustively, ignoring the time budget
+    for _i_game in 0..if_dbg_else!({3}{10}) {
+        let rules = unwrap!(ruleset.announceable_rules(EPlayerIndex::EPI0).choose(&mut rng));
+        let mut game = game::SGame::new(
+            game::random_ahand_playable(rules.as_ref(), EKurzLang::Lang, &mut rng),
+            game::SDoublings::new(SStaticEPI0{}),
+            /*ostossparams*/None,
+            rules,
+            /*n_bock*/0,
+            /*n_stock*/0,
+        );
+        for _i_card in 0..(EKurzLang::Lang.cards_per_player()-n_remaining_cards_on_hand)*EPlayerIndex::SIZE {
+            let epi = unwrap!(game.current_playable_stich().current_playerindex());
+            let card = *unwrap!(game.rules.all_allowed_cards(&game.stichseq, &game.ahand[epi]).choose(&mut rng));
+            unwrap!(game.zugeben(card, epi));
+        }
+        let determinebestcard = SDetermineBestCard::new_from_game(&game);
+        assert_eq!(remaining_cards_per_hand(&game.stichseq)[determinebestcard.epi_fixed], n_remaining_cards_on_hand);
+        for ai in [
+            SAi::new_cheating(/*n_rank_rules_samples*/1, /*n_suggest_card_branches*/2),
+            SAi::new_simulating(/*n_rank_rules_samples*/1, /*n_suggest_card_branches*/2, /*n_suggest_card_samples*/10),
+        ] {
+            for (duration_suggest_card, fn_check_n_searches) in [
+                (Duration::ZERO, (|n_searches| n_searches==1) as fn(usize)->bool), // without time left, only the first search is completed
+                (Duration::from_millis(100), |n_searches| 1<=n_searches), // number of searches depends on the machine
+            ] {
+                let (card, n_searches) = ai.suggest_card_within_time_budget(&game, &determinebestcard, duration_suggest_card, /*opath_out_dir*/None);
+                assert!(fn_check_n_searches(n_searches), "{:?}: {} searches", duration_suggest_card, n_searches);
+                assert!(determinebestcard.veccard_allowed.contains(&card));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_determine_best_card_deadline() {
+    use rand::prelude::IteratorRandom;
+    use std::time::Instant;
+    let mut rng = StdRng::seed_from_u64(0);
+    let ruleset = unwrap!(SRuleSet::from_string("base-price=10\nsolo-price=50\nlauf-min=3\n[rufspiel]\n[solo]"));
+    let rules = unwrap!(ruleset.announceable_rules(EPlayerIndex::EPI0).choose(&mut rng));
+    let mut ahand = game::random_ahand_playable(rules.as_ref(), EKurzLang::Lang, &mut rng);
+    let stichseq = game::play_random_cards(rules.as_ref(), &mut ahand, /*n_cards*/6*EPlayerIndex::SIZE, &mut rng);
+    let epi = unwrap!(stichseq.current_stich().current_playerindex());
+    let determinebestcard = SDetermineBestCard::new(rules.as_ref(), &stichseq, &ahand[epi]);
+    // the deadline has already passed, so only the first of infinitely many hands is explored
+    let determinebestcardresult = determine_best_card(
+        &determinebestcard,
+        forever_rand_hands(&stichseq, ahand[epi].clone(), epi, /*ohand_revealed*/None, rules.as_ref()),
+        &equivalent_cards_filter(rules.as_ref(), /*b_equivalent_cards*/true),
+        &SMinReachablePayout::new(rules.as_ref(), epi, /*tpln_stoss_doubling*/(0, 0), /*n_stock*/0),
+        /*osnapshotcache*/None,
+        /*opath_out_dir*/None,
+        /*oinstant_deadline*/Some(Instant::now()),
     );
+    for (_card, payoutstats) in determinebestcardresult.cards_and_ts() {
+        assert_eq!(payoutstats.t_min.n_count, 1);
+    }
+}
+
+#[test]
+fn test_ismcts_not_weaker_than_simulating() {
+    use rand::prelude::IteratorRandom;
+    let mut rng = StdRng::seed_from_u64(0);
+    let ruleset = unwrap!(SRuleSet::from_string(
+        r"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [rufspiel]
+        ",
+    ));
+    // debug builds play fewer deals with less effort on both sides
+    let (n_deals, n_suggest_card_iterations, n_suggest_card_branches, n_suggest_card_samples) = if_dbg_else!({(4, 200, 1, 2)}{(40, 2000, 2, 10)});
+    let aiismcts = SAi::new_ismcts(/*n_rank_rules_samples*/1, n_suggest_card_iterations);
+    let aisimulating = SAi::new_simulating(/*n_rank_rules_samples*/1, n_suggest_card_branches, n_suggest_card_samples);
+    let mut vecn_payout_ismcts = Vec::new(); // per deal
+    for i_deal in 0..n_deals {
+        let rules = unwrap!(ruleset.announceable_rules(EPlayerIndex::EPI0).choose(&mut rng));
+        let ahand = game::random_ahand_playable(rules.as_ref(), EKurzLang::Lang, &mut rng);
+        // duplicate format: both AIs play the same deal from both sides to reduce luck
+        vecn_payout_ismcts.push([false, true].iter().map(|&b_ismcts_even| {
+            let b_ismcts = |epi: EPlayerIndex| epi.to_usize().is_multiple_of(2)==b_ismcts_even;
+            // the last stichs are not part of the comparison: exhaustive simulation is too expensive here,
+            // so both sides play the last N_REMAINING_CARDS_EXHAUSTIVE cards with aiismcts
+            rng::with_seed(/*n_seed*/i_deal, || {
+                // a fresh single-threaded pool draws its random numbers in a reproducible order
+                unwrap!(rayon::ThreadPoolBuilder::new().num_threads(1).build()).install(|| {
+                    let mut game = game::SGame::new(
+                        ahand.clone(),
+                        game::SDoublings::new(SStaticEPI0{}),
+                        /*ostossparams*/None,
+                        rules.clone(),
+                        /*n_bock*/0,
+                        /*n_stock*/0,
+                    );
+                    while let Some((epi, _vecepi_stoss)) = game.which_player_can_do_something() {
+                        let card = if b_ismcts(epi) || remaining_cards_per_hand(&game.stichseq)[epi] <= N_REMAINING_CARDS_EXHAUSTIVE {
+                            &aiismcts
+                        } else {
+                            &aisimulating
+                        }.suggest_card(&game, /*opath_out_dir*/None);
+                        unwrap!(game.zugeben(card, epi));
+                    }
+                    let an_payout = unwrap!(game.finish()).an_payout;
+                    EPlayerIndex::values()
+                        .filter(|epi| b_ismcts(*epi))
+                        .map(|epi| an_payout[epi])
+                        .sum::<isize>()
+                })
+            })
+        }).sum::<isize>());
+    }
+    // ISMCTS must not be weaker: it may lose at most half a base price per deal on average
+    let f_avg = vecn_payout_ismcts.iter().sum::<isize>().as_num::<f64>() / vecn_payout_ismcts.len().as_num::<f64>();
+    assert!(-5. <= f_avg, "{:?}", vecn_payout_ismcts);
 }
 
 // TODO (Sauspiel 964899954)
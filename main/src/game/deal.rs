@@ -0,0 +1,113 @@
+use crate::game::random_ahand;
+use crate::primitives::*;
+use crate::util::*;
+use rand::rngs::StdRng;
+use std::path::Path;
+
+/// Checks that each card of the deck is dealt to at most one player and that each player got the right number of cards.
+pub fn check_deal(ahand: &EnumMap<EPlayerIndex, SHand>, ekurzlang: EKurzLang) -> Result<(), Error> {
+    let mut mapcardoepi = SCard::map_from_fn(|_card| None);
+    for epi in EPlayerIndex::values() {
+        if ahand[epi].cards().len()!=ekurzlang.cards_dealt_to(epi) {
+            bail!("Player {} has {} cards instead of {}.", epi, ahand[epi].cards().len(), ekurzlang.cards_dealt_to(epi));
+        }
+        for &card in ahand[epi].cards().iter() {
+            if !ekurzlang.supports_card(card) {
+                bail!("{} is not part of the deck.", card);
+            }
+            if let Some(epi_other) = mapcardoepi[card].replace(epi) {
+                bail!("{} dealt to players {} and {}.", card, epi_other, epi);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses the cards of all players, starting with `EPI0`.
+/// The cards may be preceded by a description and a colon (as in `testdata/isar_interactive.txt`).
+pub fn parse_deal(str_deal: &str, ekurzlang: EKurzLang) -> Result<EnumMap<EPlayerIndex, SHand>, Error> {
+    let str_cards = unwrap!(str_deal.rsplit(':').next());
+    let veccard = cardvector::parse_cards::<Vec<_>>(str_cards)
+        .ok_or_else(|| format_err!("Could not parse cards."))?;
+    let n_cards_per_player = ekurzlang.cards_per_player();
+    if veccard.len()!=ekurzlang.n_players()*n_cards_per_player {
+        bail!("Expected {} cards, found {}.", ekurzlang.n_players()*n_cards_per_player, veccard.len());
+    }
+    let ahand = EPlayerIndex::map_from_fn(|epi|
+        SHand::new_from_iter(veccard[epi.to_usize()*n_cards_per_player..][..ekurzlang.cards_dealt_to(epi)].iter().copied())
+    );
+    check_deal(&ahand, ekurzlang)?;
+    Ok(ahand)
+}
+
+/// Reads one deal per non-empty line.
+pub fn read_deals(path: &Path, ekurzlang: EKurzLang) -> Result<Vec<EnumMap<EPlayerIndex, SHand>>, Error> {
+    std::fs::read_to_string(path)?
+        .lines()
+        .enumerate()
+        .filter(|(_i_line, str_line)| !str_line.trim().is_empty())
+        .map(|(i_line, str_line)| parse_deal(str_line, ekurzlang)
+            .map_err(|err| format_err!("{}:{}: {}", path.display(), i_line+1, err))
+        )
+        .collect()
+}
+
+/// Provides the hands for successive games.
+#[derive(Debug)]
+pub enum VDealSource {
+    Random{ekurzlang: EKurzLang, rng: Box<StdRng>}, // boxed to keep VDealSource small
+    Predefined{vecahand: Vec<EnumMap<EPlayerIndex, SHand>>, i_deal: usize}, // starts over after the last deal
+}
+
+impl VDealSource {
+    pub fn new_random(ekurzlang: EKurzLang) -> Self {
+        VDealSource::Random{ekurzlang, rng: Box::new(rng::new_rng())}
+    }
+
+    pub fn from_file(path: &Path, ekurzlang: EKurzLang) -> Result<Self, Error> {
+        let vecahand = read_deals(path, ekurzlang)?;
+        if vecahand.is_empty() {
+            bail!("{} contains no deals.", path.display());
+        }
+        Ok(VDealSource::Predefined{vecahand, i_deal: 0})
+    }
+
+    /// Number of different deals, None if dealing randomly.
+    pub fn count_deals(&self) -> Option<usize> {
+        match self {
+            VDealSource::Random{..} => None,
+            VDealSource::Predefined{vecahand, i_deal:_} => Some(vecahand.len()),
+        }
+    }
+
+    pub fn deal(&mut self) -> EnumMap<EPlayerIndex, SHand> {
+        match self {
+            VDealSource::Random{ekurzlang, rng} => random_ahand(*ekurzlang, rng),
+            VDealSource::Predefined{vecahand, i_deal} => {
+                let ahand = vecahand[*i_deal].explicit_clone();
+                *i_deal = (*i_deal + 1) % vecahand.len();
+                ahand
+            },
+        }
+    }
+}
+
+#[test]
+fn test_parse_deal() {
+    use crate::card::card_values::*;
+    let str_lang = "so h7 go eo ho hz hk eu gu h9 su g8 g9 ga gk e9 ea ek ez e7 g7 ha s7 gz sa s9 h8 sz e8 sk hu s8";
+    let ahand = unwrap!(parse_deal(&format!("Rufspiel Blaue von 3: {}", str_lang), EKurzLang::Lang));
+    assert_eq!(ahand[EPlayerIndex::EPI0].cards().as_slice(), &[SO, H7, GO, EO, HO, HZ, HK, EU]);
+    assert_eq!(ahand[EPlayerIndex::EPI3].cards().as_slice(), &[SA, S9, H8, SZ, E8, SK, HU, S8]);
+    assert!(parse_deal(str_lang, EKurzLang::Kurz).is_err());
+    assert!(parse_deal(&str_lang.replace("s8", "so"), EKurzLang::Lang).is_err()); // duplicate
+    assert!(parse_deal(&str_lang.replace(" s8", ""), EKurzLang::Lang).is_err()); // incomplete
+    let str_kurz = "eo go ho so eu gu hu su ea ez ek e9 ga gz gk g9 ha hz hk h9 sa sz sk s9";
+    assert!(parse_deal(str_kurz, EKurzLang::Kurz).is_ok());
+    assert!(parse_deal(&str_kurz.replace("s9", "s7"), EKurzLang::Kurz).is_err()); // not in deck
+    let str_dreier = str_lang.replace(" sk hu", "");
+    let ahand = unwrap!(parse_deal(&str_dreier, EKurzLang::Dreier));
+    assert_eq!(ahand[EPlayerIndex::EPI2].cards().as_slice(), &[G7, HA, S7, GZ, SA, S9, H8, SZ, E8, S8]);
+    assert!(ahand[EPlayerIndex::EPI3].cards().is_empty());
+    assert!(parse_deal(str_lang, EKurzLang::Dreier).is_err());
+}
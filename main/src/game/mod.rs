@@ -2,10 +2,17 @@ use crate::primitives::*;
 use crate::rules::{ruleset::*, *};
 use crate::util::*;
 use arrayvec::ArrayVec;
-use rand::{self, Rng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::mem;
 
+pub mod deal;
+pub mod observer;
+pub mod record;
 pub mod run;
+pub mod session;
+
+use self::{observer::*, record::SGameRecord};
 
 pub trait TGamePhase : Sized {
     type ActivePlayerInfo;
@@ -30,7 +37,9 @@ pub struct SDealCards {
     ahand : EnumMap<EPlayerIndex, SHand>,
     doublings : SDoublings,
     ruleset : SRuleSet,
+    n_bock : usize,
     n_stock : isize,
+    observers : SGameObservers,
 }
 
 impl TGamePhase for SDealCards {
@@ -48,28 +57,36 @@ impl TGamePhase for SDealCards {
         SGamePreparations {
             ahand : self.ahand,
             doublings : self.doublings,
+            gameannouncements : SGameAnnouncements::new_with_players(SStaticEPI0{}, self.ruleset.ekurzlang.n_players()),
             ruleset: self.ruleset,
-            gameannouncements : SGameAnnouncements::new(SStaticEPI0{}),
+            n_bock: self.n_bock,
             n_stock: self.n_stock,
+            observers: self.observers,
         }
     }
 }
 
 impl SDealCards {
-    pub fn new(ruleset: SRuleSet, n_stock: isize) -> SDealCards {
-        let ekurzlang = ruleset.ekurzlang;
-        SDealCards {
-            ahand : {
-                let mut veccard = SCard::values(ekurzlang).collect::<Vec<_>>();
-                assert_eq!(veccard.len(), EPlayerIndex::SIZE*ekurzlang.cards_per_player());
-                EPlayerIndex::map_from_fn(move |_epi|
-                    random_hand(ekurzlang.cards_per_player(), &mut veccard)
-                )
-            },
-            doublings: SDoublings::new(SStaticEPI0{}),
+    pub fn new(ruleset: SRuleSet, n_bock: usize, n_stock: isize, ahand: EnumMap<EPlayerIndex, SHand>) -> Result<SDealCards, Error> {
+        deal::check_deal(&ahand, ruleset.ekurzlang)?;
+        Ok(SDealCards {
+            ahand,
+            doublings: SDoublings::new_with_players(SStaticEPI0{}, ruleset.ekurzlang.n_players()),
             ruleset,
+            n_bock,
             n_stock,
-        }
+            observers: SGameObservers::default(),
+        })
+    }
+
+    /// Subscribes `observer` to this and all subsequent phases of the game, starting with the deal.
+    pub fn add_observer(&mut self, mut observer: Box<dyn TGameObserver>) {
+        observer.on_event(&VGameEvent::Deal{
+            ahand: self.ahand.explicit_clone(),
+            n_bock: self.n_bock,
+            n_stock: self.n_stock,
+        });
+        self.observers.add(observer);
     }
 
     pub fn first_hand_for(&self, epi: EPlayerIndex) -> &[SCard] {
@@ -84,6 +101,7 @@ impl SDealCards {
         }
         self.doublings.push(b_doubling);
         assert!(!self.doublings.is_empty());
+        self.observers.notify(|| VGameEvent::Doubling{epi, b_doubling});
         Ok(())
     }
 }
@@ -96,15 +114,44 @@ pub struct SGamePreparations {
     pub doublings : SDoublings,
     pub ruleset : SRuleSet,
     pub gameannouncements : SGameAnnouncements,
+    pub n_bock : usize,
     pub n_stock : isize,
+    pub observers : SGameObservers,
+}
+
+pub fn random_ahand(ekurzlang: EKurzLang, rng: &mut impl Rng) -> EnumMap<EPlayerIndex, SHand> {
+    let mut veccard = SCard::values(ekurzlang).collect::<Vec<_>>();
+    EPlayerIndex::map_from_fn(|epi| random_hand(ekurzlang.cards_dealt_to(epi), &mut veccard, rng))
 }
 
-pub fn random_hand(n_size: usize, veccard : &mut Vec<SCard>) -> SHand {
+// deals until the rules can be played (e.g. Hochzeit requires a single trumpf)
+#[cfg(test)]
+pub fn random_ahand_playable(rules: &dyn TRules, ekurzlang: EKurzLang, rng: &mut impl Rng) -> EnumMap<EPlayerIndex, SHand> {
+    unwrap!(std::iter::repeat_with(|| random_ahand(ekurzlang, rng))
+        .take(1000)
+        .find(|ahand| rules.playerindex().is_none_or(|epi| rules.can_be_played(SFullHand::new(&ahand[epi], ekurzlang)))))
+}
+
+// plays n_cards random allowed cards from ahand
+#[cfg(test)]
+pub fn play_random_cards(rules: &dyn TRules, ahand: &mut EnumMap<EPlayerIndex, SHand>, n_cards: usize, rng: &mut impl Rng) -> SStichSequence {
+    use rand::prelude::SliceRandom;
+    let mut stichseq = SStichSequence::new(EKurzLang::from_cards_per_player(ahand[EPlayerIndex::EPI0].cards().len()));
+    for _i_card in 0..n_cards {
+        let epi = unwrap!(stichseq.current_stich().current_playerindex());
+        let card = *unwrap!(rules.all_allowed_cards(&stichseq, &ahand[epi]).choose(rng));
+        ahand[epi].play_card(card);
+        stichseq.zugeben(card, rules);
+    }
+    stichseq
+}
+
+pub fn random_hand(n_size: usize, veccard : &mut Vec<SCard>, rng: &mut impl Rng) -> SHand {
     assert!(veccard.len()>=n_size);
     SHand::new_from_vec({
         let mut veccard_hand = SHandVector::new();
         for _i in 0..n_size {
-            let i_card = rand::thread_rng().gen_range(0..veccard.len());
+            let i_card = rng.gen_range(0..veccard.len());
             veccard_hand.push(veccard.swap_remove(i_card));
         }
         assert_eq!(veccard_hand.len(), n_size);
@@ -129,6 +176,12 @@ impl TGamePhase for SGamePreparations {
     }
 
     fn finish_success(self) -> Self::Finish {
+        let bidhistory = SBidHistory {
+            vecorulesid_gameannouncement: self.gameannouncements.iter()
+                .map(|(_epi, orules)| orules.as_ref().map(|rules| rules.to_string()))
+                .collect(),
+            vecpairepiorulesid_determinerules: Vec::new(),
+        };
         let mut vecpairepirules : Vec<(_, Box<dyn TActivelyPlayableRules>)> = self.gameannouncements.into_iter()
             .filter_map(|(epi, orules)| orules.map(|rules| (epi, rules)))
             .collect();
@@ -138,21 +191,45 @@ impl TGamePhase for SGamePreparations {
                 self.doublings,
                 self.ruleset,
                 vecpairepirules,
+                self.n_bock,
                 self.n_stock,
                 pairepirules_current_bid,
+                bidhistory,
+                self.observers,
             ))
         } else {
-            match self.ruleset.stockorramsch {
-                VStockOrT::OrT(ref rulesramsch) => {
-                    VGamePreparationsFinish::DirectGame(SGame::new(
+            match self.ruleset.ofallbackrules {
+                Some(ref fallbackrules) => {
+                    if let Some((epi_alter, rulegroup)) = fallbackrules.der_alte_muss(&self.ahand) {
+                        let rules = TActivelyPlayableRulesBoxClone::box_clone(unwrap!(unwrap!(rulegroup.vecorules.first()).as_ref()).as_ref());
+                        return VGamePreparationsFinish::DetermineRules(SDetermineRules::new_der_alte_muss(
+                            self.ahand,
+                            self.doublings,
+                            self.ruleset,
+                            self.n_bock,
+                            self.n_stock,
+                            (epi_alter, rules),
+                            rulegroup,
+                            bidhistory,
+                            self.observers,
+                        ));
+                    }
+                    let rules = unwrap!(fallbackrules.rules()); // der Alte handled above
+                    let mut game = SGame::new(
                         self.ahand,
                         self.doublings,
                         self.ruleset.ostossparams.clone(),
-                        rulesramsch.clone(),
+                        rules,
+                        self.n_bock,
                         self.n_stock,
-                    ))
+                    );
+                    game.bidhistory = bidhistory;
+                    game.ostr_ruleset = Some(self.ruleset.str_toml.clone());
+                    game.observers = self.observers;
+                    VGamePreparationsFinish::DirectGame(game)
                 },
-                VStockOrT::Stock(n_stock) => {
+                None => {
+                    let n_stock = self.ruleset.on_stock_price.unwrap_or(0); // "no stock" is like a zero stock payment
                     let n_stock = match self.ruleset.oedoublingscope {
                         None | Some(EDoublingScope::Games) => n_stock,
                         Some(EDoublingScope::GamesAndStock) => {
@@ -161,9 +238,22 @@ impl TGamePhase for SGamePreparations {
                             )
                         }
                     };
+                    let ekurzlang = self.ruleset.ekurzlang;
+                    let an_payout = EPlayerIndex::map_from_fn(|epi| if ekurzlang.takes_part(epi) {-n_stock} else {0});
+                    let mut observers = self.observers;
+                    observers.notify(|| VGameEvent::GameResult{an_payout: an_payout.explicit_clone()});
                     VGamePreparationsFinish::Stock(SGameResult{
-                        mapepib_confirmed: EPlayerIndex::map_from_fn(|_epi| false),
-                        an_payout: EPlayerIndex::map_from_fn(|_epi| -n_stock),
+                        mapepib_confirmed: EPlayerIndex::map_from_fn(|epi| !ekurzlang.takes_part(epi)), // empty seats need not confirm
+                        gamerecord: SGameRecord::new_stock(
+                            &self.ruleset,
+                            &self.ahand,
+                            &self.doublings,
+                            bidhistory,
+                            self.n_bock,
+                            self.n_stock,
+                            &an_payout,
+                        ),
+                        an_payout,
                         stockorgame: VStockOrT::Stock(()),
                     })
                 }
@@ -191,20 +281,36 @@ impl SGamePreparations {
         if !orules.as_ref().map_or(true, |rules| rules.can_be_played(self.fullhand(epi))) {
             bail!("Rules cannot be played. {}", self.ahand[epi]);
         }
+        self.observers.notify(|| VGameEvent::Announcement{
+            epi,
+            orules: orules.as_ref().map(|rules| TActivelyPlayableRulesBoxClone::box_clone(rules.as_ref())),
+        });
         self.gameannouncements.push(orules);
         assert!(!self.gameannouncements.is_empty());
         Ok(())
     }
 }
 
+/// Announcements made before the rules of a game were determined, identified by the rules' `to_string`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SBidHistory {
+    pub vecorulesid_gameannouncement: Vec<Option<String>>, // starting with EPI0
+    pub vecpairepiorulesid_determinerules: Vec<(EPlayerIndex, Option<String>)>, // None if player resigned
+}
+
 #[derive(new, Debug)]
 pub struct SDetermineRules {
     pub ahand : EnumMap<EPlayerIndex, SHand>,
     pub doublings : SDoublings,
     pub ruleset : SRuleSet,
     pub vecpairepirules_queued : Vec<(EPlayerIndex, Box<dyn TActivelyPlayableRules>)>,
+    pub n_bock : usize,
     pub n_stock : isize,
     pub pairepirules_current_bid : (EPlayerIndex, Box<dyn TActivelyPlayableRules>),
+    pub bidhistory : SBidHistory,
+    pub observers : SGameObservers,
+    #[new(default)]
+    pub orulegroup_der_alte_muss : Option<SRuleGroup>, // if set, der Alte must choose one of these rules and cannot resign
 }
 
 impl TGamePhase for SDetermineRules {
@@ -222,6 +328,12 @@ impl TGamePhase for SDetermineRules {
         => continue until self.vecpairepirules_queued is empty
     */
     fn which_player_can_do_something(&self) -> Option<Self::ActivePlayerInfo> {
+        if let Some(ref rulegroup_der_alte_muss) = self.orulegroup_der_alte_muss {
+            return self.vecpairepirules_queued.last().as_ref().map(|&&(epi, ref _rules)| (
+                epi,
+                vec![rulegroup_der_alte_muss.clone()],
+            ));
+        }
         self.vecpairepirules_queued.last().as_ref().map(|&&(epi, ref _rules)| (
             epi,
             self.ruleset.avecrulegroup[epi].iter()
@@ -243,19 +355,51 @@ impl TGamePhase for SDetermineRules {
     fn finish_success(self) -> Self::Finish {
         assert!(self.vecpairepirules_queued.is_empty());
         assert_eq!(self.ruleset.ekurzlang, EKurzLang::from_cards_per_player(self.ahand[EPlayerIndex::EPI0].cards().len()));
-        SGame::new(
+        let mut game = SGame::new(
             self.ahand,
             self.doublings,
             self.ruleset.ostossparams.clone(),
             self.pairepirules_current_bid.1.upcast().box_clone(),
+            self.n_bock,
             self.n_stock,
-        )
+        );
+        game.bidhistory = self.bidhistory;
+        game.ostr_ruleset = Some(self.ruleset.str_toml.clone());
+        game.observers = self.observers;
+        game
     }
 }
 
 impl SDetermineRules {
     impl_fullhand!();
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_der_alte_muss(
+        ahand: EnumMap<EPlayerIndex, SHand>,
+        doublings: SDoublings,
+        ruleset: SRuleSet,
+        n_bock: usize,
+        n_stock: isize,
+        pairepirules_default: (EPlayerIndex, Box<dyn TActivelyPlayableRules>),
+        rulegroup_der_alte_muss: SRuleGroup,
+        bidhistory: SBidHistory,
+        observers: SGameObservers,
+    ) -> Self {
+        let mut determinerules = SDetermineRules::new(
+            ahand,
+            doublings,
+            ruleset,
+            /*vecpairepirules_queued*/vec![(pairepirules_default.0, TActivelyPlayableRulesBoxClone::box_clone(pairepirules_default.1.as_ref()))],
+            n_bock,
+            n_stock,
+            pairepirules_default,
+            bidhistory,
+            observers,
+        );
+        determinerules.orulegroup_der_alte_muss = Some(rulegroup_der_alte_muss);
+        determinerules
+    }
+
     pub fn currently_offered_prio(&self) -> (EPlayerIndex, VGameAnnouncementPriority) {
         (self.pairepirules_current_bid.0, self.pairepirules_current_bid.1.priority())
     }
@@ -264,6 +408,9 @@ impl SDetermineRules {
         if Some(epi)!=self.which_player_can_do_something().map(|(epi, ref _vecrulegroup)| epi) {
             bail!("announce_game not allowed for specified EPlayerIndex");
         }
+        if self.orulegroup_der_alte_muss.is_some() {
+            bail!("Der Alte must choose one of the offered rules via announce_game_der_alte.");
+        }
         if rules.priority()<self.currently_offered_prio().1 {
             bail!("announced rules' priority must be at least as large as the latest announced priority");
         }
@@ -274,6 +421,8 @@ impl SDetermineRules {
         assert!(!self.vecpairepirules_queued.is_empty());
         let epi_check = unwrap!(self.vecpairepirules_queued.pop()).0;
         assert_eq!(epi, epi_check);
+        self.bidhistory.vecpairepiorulesid_determinerules.push((epi, Some(rules.to_string())));
+        self.observers.notify(|| VGameEvent::BidRaise{epi, rules: TActivelyPlayableRulesBoxClone::box_clone(rules.as_ref())});
         let mut pairepirules_current_bid = (epi, rules);
         mem::swap(&mut self.pairepirules_current_bid, &mut pairepirules_current_bid);
         self.vecpairepirules_queued.push(pairepirules_current_bid);
@@ -281,20 +430,48 @@ impl SDetermineRules {
         Ok(())
     }
 
+    /// Der Alte chooses the rules at position `i_rules` of the rule group offered to him.
+    pub fn announce_game_der_alte(&mut self, epi: EPlayerIndex, i_rules: usize) -> Result<(), Error> {
+        if Some(epi)!=self.which_player_can_do_something().map(|(epi, ref _vecrulegroup)| epi) {
+            bail!("announce_game_der_alte not allowed for specified EPlayerIndex");
+        }
+        let rules = TActivelyPlayableRulesBoxClone::box_clone(
+            self.orulegroup_der_alte_muss.as_ref()
+                .ok_or_else(|| format_err!("Der Alte does not have to play."))?
+                .vecorules.get(i_rules)
+                .and_then(|orules| orules.as_ref())
+                .ok_or_else(|| format_err!("Der Alte must choose one of the offered rules."))?
+                .as_ref()
+        );
+        assert!(rules.can_be_played(self.fullhand(epi)));
+        let epi_check = unwrap!(self.vecpairepirules_queued.pop()).0;
+        assert_eq!(epi, epi_check);
+        assert!(self.vecpairepirules_queued.is_empty());
+        self.bidhistory.vecpairepiorulesid_determinerules.push((epi, Some(rules.to_string())));
+        self.observers.notify(|| VGameEvent::BidRaise{epi, rules: TActivelyPlayableRulesBoxClone::box_clone(rules.as_ref())});
+        self.pairepirules_current_bid = (epi, rules);
+        Ok(())
+    }
+
     pub fn resign(&mut self, epi: EPlayerIndex) -> Result<(), Error> {
         if Some(epi)!=self.which_player_can_do_something().map(|(epi, ref _vecrulegroup)| epi) {
             bail!("announce_game not allowed for specified EPlayerIndex");
         }
+        if self.orulegroup_der_alte_muss.is_some() {
+            bail!("Der Alte must play.");
+        }
         assert!(!self.vecpairepirules_queued.is_empty());
         let paireplayerindexorules = unwrap!(self.vecpairepirules_queued.pop());
         assert_eq!(epi, paireplayerindexorules.0);
+        self.bidhistory.vecpairepiorulesid_determinerules.push((epi, None));
+        self.observers.notify(|| VGameEvent::BidResign{epi});
         Ok(())
     }
 }
 
 #[derive(Debug, Clone)] // TODO? custom impl Debug
 pub struct SStichSequence {
-    vecstich: ArrayVec<SStich, 9>,
+    vecstich: ArrayVec<SStich, 11>, // one more than cards per player, see EKurzLang::Dreier
     ekurzlang: EKurzLang,
 }
 
@@ -316,7 +493,7 @@ impl SStichSequence {
         let stichseq = SStichSequence {
             vecstich: {
                 let mut vecstich = ArrayVec::new();
-                vecstich.push(SStich::new(EPlayerIndex::EPI0));
+                vecstich.push(SStich::new_with_players(EPlayerIndex::EPI0, ekurzlang.n_players()));
                 vecstich
             },
             ekurzlang,
@@ -364,7 +541,10 @@ impl SStichSequence {
         #[cfg(debug_assertions)]self.assert_invariant();
         unwrap!(self.vecstich.last_mut()).push(card);
         if self.current_stich_no_invariant().is_full() {
-            self.vecstich.push(SStich::new(fn_winner_index(self.current_stich_no_invariant())));
+            self.vecstich.push(SStich::new_with_players(
+                fn_winner_index(self.current_stich_no_invariant()),
+                self.ekurzlang.n_players(),
+            ));
         }
         #[cfg(debug_assertions)]self.assert_invariant();
     }
@@ -392,14 +572,22 @@ impl SStichSequence {
         assert!(!self.current_stich().is_full());
         self.zugeben(card, rules);
         let r = func(self);
+        self.undo_most_recent();
+        debug_assert_eq!(n_len, self.vecstich.len());
+        r
+    }
+
+    pub fn undo_most_recent(&mut self) -> (EPlayerIndex, SCard) {
+        assert!(!self.no_card_played());
         if self.current_stich().is_empty() {
             unwrap!(self.vecstich.pop());
             assert!(self.current_stich_no_invariant().is_full());
         }
-        unwrap!(self.vecstich.last_mut()).undo_most_recent();
-        debug_assert_eq!(n_len, self.vecstich.len());
+        let stich = unwrap!(self.vecstich.last_mut());
+        let (epi, &card) = unwrap!(stich.iter().last());
+        stich.undo_most_recent();
         #[cfg(debug_assertions)]self.assert_invariant();
-        r
+        (epi, card)
     }
 
     pub fn visible_stichs(&self) -> &[SStich] {
@@ -411,13 +599,43 @@ impl SStichSequence {
         self.ekurzlang
     }
 
+    pub fn initial_hand(&self, epi: EPlayerIndex, hand: &SHand) -> SHand {
+        SHand::new_from_iter(hand.cards().iter().copied().chain(
+            self.visible_stichs().iter().filter_map(|stich| stich.get(epi).copied())
+        ))
+    }
+
     pub fn count_played_cards(&self) -> usize {
         #[cfg(debug_assertions)]self.assert_invariant();
-        self.completed_stichs().len() * EPlayerIndex::SIZE
+        self.completed_stichs().len() * self.ekurzlang.n_players()
             + self.current_stich().size()
     }
 }
 
+/// Cards exchanged during the game (see `TRules::card_exchange`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SCardExchange {
+    pub epi_active : EPlayerIndex,
+    pub card_active : SCard, // trumpf given to partner
+    pub epi_partner : EPlayerIndex,
+    pub card_partner : SCard, // card returned to active player
+    pub n_cards_played : usize, // when the cards were exchanged
+}
+
+impl SCardExchange {
+    pub fn revert(&self, epi: EPlayerIndex, hand: &mut SHand) {
+        let mut exchange = |card_received, card_given| {
+            hand.play_card(card_received);
+            hand.add_card(card_given);
+        };
+        if epi==self.epi_active {
+            exchange(self.card_partner, self.card_active);
+        } else if epi==self.epi_partner {
+            exchange(self.card_active, self.card_partner);
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SGame {
     pub ahand : EnumMap<EPlayerIndex, SHand>,
@@ -425,8 +643,14 @@ pub struct SGame {
     pub rules : Box<dyn TRules>,
     pub vecstoss : Vec<SStoss>,
     pub ostossparams : Option<SStossParams>,
+    pub oeschneiderschwarz_announced : Option<ESchneiderSchwarz>,
+    pub ocardexchange : Option<SCardExchange>,
+    pub n_bock : usize,
     pub n_stock : isize,
+    pub bidhistory : SBidHistory,
+    pub ostr_ruleset : Option<String>, // source of the ruleset the game was set up with
     pub stichseq: SStichSequence,
+    pub observers : SGameObservers,
 }
 
 pub type SGameAction = (EPlayerIndex, Vec<EPlayerIndex>);
@@ -440,12 +664,11 @@ impl TGamePhase for SGame {
             self.current_playable_stich().current_playerindex().map(|epi_current| (
                 epi_current,
                 if let Some(ref stossparams) = self.ostossparams {
-                    if self.stichseq.no_card_played() // TODORULES Adjustable latest time of stoss
-                        && self.vecstoss.len() < stossparams.n_stoss_max
-                    {
-                        EPlayerIndex::values()
+                    if self.vecstoss.len() < stossparams.n_stoss_max {
+                        self.stichseq.kurzlang().players()
                             .filter(|epi| {
-                                self.rules.stoss_allowed(*epi, &self.vecstoss, &self.ahand[*epi])
+                                stossparams.before_deadline(*epi, &self.stichseq)
+                                    && self.rules.stoss_allowed(*epi, &self.vecstoss, &self.dealt_hand(*epi))
                             })
                             .collect()
                     } else {
@@ -460,15 +683,19 @@ impl TGamePhase for SGame {
         }
     }
 
-    fn finish_success(self) -> Self::Finish {
+    fn finish_success(mut self) -> Self::Finish {
         assert!(self.kurzlang().cards_per_player()==self.completed_stichs().len());
+        let an_payout = self.rules.payout(
+            SStichSequenceGameFinished::new(&self.stichseq),
+            stoss_and_doublings(&self.vecstoss, &self.doublings, self.n_bock),
+            self.n_stock,
+        );
+        self.observers.notify(|| VGameEvent::GameResult{an_payout: an_payout.explicit_clone()});
+        let ekurzlang = self.kurzlang();
         SGameResult {
-            mapepib_confirmed: EPlayerIndex::map_from_fn(|_epi| false),
-            an_payout : self.rules.payout(
-                SStichSequenceGameFinished::new(&self.stichseq),
-                stoss_and_doublings(&self.vecstoss, &self.doublings),
-                self.n_stock,
-            ),
+            mapepib_confirmed: EPlayerIndex::map_from_fn(|epi| !ekurzlang.takes_part(epi)), // empty seats need not confirm
+            gamerecord: SGameRecord::new_game(&self, &an_payout),
+            an_payout,
             stockorgame: VStockOrT::OrT(self),
         }
     }
@@ -480,18 +707,25 @@ impl SGame {
         doublings : SDoublings,
         ostossparams : Option<SStossParams>,
         rules : Box<dyn TRules>,
+        n_bock : usize,
         n_stock : isize,
     ) -> SGame {
-        let n_cards_per_player = ahand[EPlayerIndex::EPI0].cards().len();
-        assert!(ahand.iter().all(|hand| hand.cards().len()==n_cards_per_player));
+        let ekurzlang = EKurzLang::from_cards_per_player(ahand[EPlayerIndex::EPI0].cards().len());
+        assert!(EPlayerIndex::values().all(|epi| ahand[epi].cards().len()==ekurzlang.cards_dealt_to(epi)));
         SGame {
             ahand,
             doublings,
             rules,
             vecstoss: Vec::new(),
             ostossparams,
+            oeschneiderschwarz_announced: None,
+            ocardexchange: None,
+            n_bock,
             n_stock,
-            stichseq: SStichSequence::new(EKurzLang::from_cards_per_player(n_cards_per_player)),
+            bidhistory: SBidHistory::default(),
+            ostr_ruleset: None,
+            stichseq: SStichSequence::new(ekurzlang),
+            observers: SGameObservers::default(),
         }
     }
 
@@ -500,32 +734,63 @@ impl SGame {
         doublings : SDoublings,
         ostossparams : Option<SStossParams>,
         vecstoss: Vec<SStoss>,
+        oeschneiderschwarz_announced: Option<ESchneiderSchwarz>,
+        ocardexchange: Option<SCardExchange>,
+        n_bock : usize,
         n_stock : isize,
         stichseq: SStichSequenceGameFinished, // TODO take value instead of wrapper
         mut fn_before_zugeben: impl FnMut(&SGame, /*i_stich*/usize, EPlayerIndex, SCard),
     ) -> Result<SGame, Error> {
-        let ahand = EPlayerIndex::map_from_fn(|epi|
+        let mut ahand = EPlayerIndex::map_from_fn(|epi|
             SHand::new_from_iter(
                 stichseq.get()
                     .completed_stichs()
                     .iter()
-                    .map(|stich| stich[epi])
+                    .filter_map(|stich| stich.get(epi).copied())
             )
         );
-        let mut game = SGame::new(ahand, doublings, ostossparams, rules, n_stock);
-        for stoss in vecstoss.into_iter() {
-            if game.stoss(stoss.epi).is_err() {
-                bail!("Error in stoss.")
+        if let Some(ref cardexchange) = ocardexchange {
+            // stichs show the cards after the exchange
+            if !ahand[cardexchange.epi_active].contains(cardexchange.card_partner) || !ahand[cardexchange.epi_partner].contains(cardexchange.card_active) {
+                bail!("Exchanged cards not played by the respective players.");
+            }
+            for epi in EPlayerIndex::values() {
+                cardexchange.revert(epi, &mut ahand[epi]);
+            }
+        }
+        let mut game = SGame::new(ahand, doublings, ostossparams, rules, n_bock, n_stock);
+        if let Some(eschneiderschwarz) = oeschneiderschwarz_announced {
+            let epi_announce = game.rules.playerindex().ok_or_else(|| format_err!("Schneider/Schwarz announced without active player."))?;
+            if game.announce_schneiderschwarz(epi_announce, eschneiderschwarz).is_err() {
+                bail!("Error in announcing schneider/schwarz.")
             }
         }
+        let mut itstoss = vecstoss.into_iter().peekable();
         for (i_stich, stich) in stichseq.get().completed_stichs().iter().enumerate() {
             for (epi, card) in stich.iter() {
+                while let Some(stoss) = itstoss.next_if(|stoss| stoss.n_cards_played==game.stichseq.count_played_cards()) {
+                    if game.stoss(stoss.epi).is_err() {
+                        bail!("Error in stoss.")
+                    }
+                }
+                if let Some(epi_exchange) = game.card_exchange_pending() {
+                    let cardexchange = ocardexchange.as_ref().ok_or_else(|| format_err!("Card exchange missing."))?;
+                    if game.exchange_card(epi_exchange, cardexchange.card_partner).is_err() {
+                        bail!("Error in card exchange.")
+                    }
+                }
                 fn_before_zugeben(&game, i_stich, epi, *card);
                 if game.zugeben(*card, epi).is_err() {
                     bail!("Error in zugeben.")
                 }
             }
         }
+        if itstoss.next().is_some() {
+            bail!("Stoss given at invalid time.")
+        }
+        if game.ocardexchange!=ocardexchange {
+            bail!("Card exchange did not take place as specified.")
+        }
         assert!(game.which_player_can_do_something().is_none());
         Ok(game)
     }
@@ -539,13 +804,11 @@ impl SGame {
         #[cfg(debug_assertions)] {
             let cards_per_player = |epi| {
                 self.ahand[epi].cards().len()
-                    + self.stichseq.completed_stichs().len()
-                    + match self.stichseq.current_stich().get(epi) {
-                        None => 0,
-                        Some(_card) => 1,
-                    }
+                    + self.stichseq.visible_stichs().iter()
+                        .filter(|stich| stich.get(epi).is_some())
+                        .count()
             };
-            assert!(EPlayerIndex::values().all(|epi| cards_per_player(epi)==cards_per_player(EPlayerIndex::EPI0)));
+            assert!(EPlayerIndex::values().all(|epi| cards_per_player(epi)==self.stichseq.ekurzlang.cards_dealt_to(epi)));
             assert_eq!(EKurzLang::from_cards_per_player(cards_per_player(EPlayerIndex::EPI0)), self.stichseq.ekurzlang);
         }
         self.stichseq.ekurzlang
@@ -558,17 +821,104 @@ impl SGame {
                 if !gameaction.1.iter().any(|&epi| epi==epi_stoss) {
                     bail!(format!("Stoss not allowed for specified epi {:?}", gameaction.1));
                 }
-                self.vecstoss.push(SStoss{epi : epi_stoss});
+                self.vecstoss.push(SStoss{epi : epi_stoss, n_cards_played: self.stichseq.count_played_cards()});
+                self.observers.notify(|| VGameEvent::Stoss{epi: epi_stoss});
+                Ok(())
+            }
+        }
+    }
+
+    pub fn schneiderschwarz_announceable(&self) -> Option<(EPlayerIndex, Vec<ESchneiderSchwarz>)> {
+        if self.stichseq.no_card_played() {
+            self.rules.playerindex().and_then(|epi_active| {
+                let veceschneiderschwarz = ESchneiderSchwarz::values()
+                    .filter(|eschneiderschwarz|
+                        self.oeschneiderschwarz_announced < Some(*eschneiderschwarz)
+                            && self.rules.with_schneiderschwarz_announced(*eschneiderschwarz).is_some()
+                    )
+                    .collect::<Vec<_>>();
+                if_then_some!(!veceschneiderschwarz.is_empty(), (epi_active, veceschneiderschwarz))
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn announce_schneiderschwarz(&mut self, epi: EPlayerIndex, eschneiderschwarz: ESchneiderSchwarz) -> Result<(), Error> {
+        match self.schneiderschwarz_announceable() {
+            None => bail!("Schneider/Schwarz cannot be announced."),
+            Some((epi_announce, veceschneiderschwarz)) => {
+                if epi!=epi_announce {
+                    bail!("Wrong player index");
+                }
+                if !veceschneiderschwarz.contains(&eschneiderschwarz) {
+                    bail!(format!("{} cannot be announced {:?}", eschneiderschwarz, veceschneiderschwarz));
+                }
+                self.rules = unwrap!(self.rules.with_schneiderschwarz_announced(eschneiderschwarz));
+                self.oeschneiderschwarz_announced = Some(eschneiderschwarz);
+                self.observers.notify(|| VGameEvent::SchneiderSchwarz{epi, eschneiderschwarz});
                 Ok(())
             }
         }
     }
 
+    /// Player who has to exchange a card with the active player before the game continues.
+    pub fn card_exchange_pending(&self) -> Option<EPlayerIndex> {
+        if self.ocardexchange.is_some() || !self.stichseq.current_stich().is_empty() {
+            return None;
+        }
+        self.rules.card_exchange(&self.stichseq).and_then(|(n_stich, epi_active, epi_partner)| if_then_some!(
+            n_stich==self.stichseq.completed_stichs().len()
+                && self.ahand[epi_active].contains_pred(|card| self.rules.trumpforfarbe(*card).is_trumpf()),
+            epi_partner
+        ))
+    }
+
+    /// `epi` gives `card` to the active player and receives the active player's trumpf.
+    pub fn exchange_card(&mut self, epi: EPlayerIndex, card: SCard) -> Result<(), Error> {
+        if Some(epi)!=self.card_exchange_pending() {
+            bail!("No card exchange pending for player {}", epi);
+        }
+        if !self.ahand[epi].contains(card) {
+            bail!("card not contained in player's hand");
+        }
+        let (_n_stich, epi_active, epi_partner) = unwrap!(self.rules.card_exchange(&self.stichseq));
+        assert_eq!(epi, epi_partner);
+        let card_active = *unwrap!(self.ahand[epi_active].cards().iter()
+            .find(|card| self.rules.trumpforfarbe(**card).is_trumpf()));
+        self.ahand[epi_active].play_card(card_active);
+        self.ahand[epi_active].add_card(card);
+        self.ahand[epi_partner].play_card(card);
+        self.ahand[epi_partner].add_card(card_active);
+        let cardexchange = SCardExchange {
+            epi_active,
+            card_active,
+            epi_partner,
+            card_partner: card,
+            n_cards_played: self.stichseq.count_played_cards(),
+        };
+        self.observers.notify(|| VGameEvent::CardExchange(cardexchange.clone()));
+        self.ocardexchange = Some(cardexchange);
+        Ok(())
+    }
+
+    /// Hand the player was dealt, i.e. before playing and exchanging cards.
+    pub fn dealt_hand(&self, epi: EPlayerIndex) -> SHand {
+        let mut hand = self.stichseq.initial_hand(epi, &self.ahand[epi]);
+        if let Some(ref cardexchange) = self.ocardexchange {
+            cardexchange.revert(epi, &mut hand);
+        }
+        hand
+    }
+
     pub fn zugeben(&mut self, card: SCard, epi: EPlayerIndex) -> Result<(), Error> {
         info!("Player {} wants to play {}", epi, card);
         if Some(epi)!=self.which_player_can_do_something().map(|gameaction| gameaction.0) {
             bail!("Wrong player index");
         }
+        if self.card_exchange_pending().is_some() {
+            bail!("Cards must be exchanged first.");
+        }
         if !self.ahand[epi].contains(card) {
             bail!("card not contained in player's hand");
         }
@@ -577,21 +927,93 @@ impl SGame {
         }
         self.ahand[epi].play_card(card);
         self.stichseq.zugeben(card, self.rules.as_ref());
-        for epi in EPlayerIndex::values() {
+        for epi in self.stichseq.kurzlang().players() {
             info!("Hand {}: {}", epi, self.ahand[epi]);
         }
+        self.observers.notify(|| VGameEvent::CardPlayed{epi, card});
+        if self.stichseq.current_stich().is_empty() {
+            let stichseq = &self.stichseq;
+            self.observers.notify(|| VGameEvent::StichWon{
+                stich: unwrap!(stichseq.completed_stichs().last()).clone(),
+                epi_winner: stichseq.current_stich().first_playerindex(),
+            });
+        }
         Ok(())
     }
 
+    /// Restores the game as it was when `n_cards` cards of stich `i_stich` had been played, dropping Stoss given afterwards.
+    pub fn rewind_to(&mut self, i_stich: usize, n_cards: usize) -> Result<(), Error> {
+        let n_players = self.stichseq.kurzlang().n_players();
+        if n_players<=n_cards {
+            bail!("A stich has only {} cards.", n_players);
+        }
+        let n_cards_played = i_stich * n_players + n_cards;
+        if self.stichseq.count_played_cards()<n_cards_played {
+            bail!("Cannot rewind to stich {} with {} cards, only {} cards played.", i_stich, n_cards, self.stichseq.count_played_cards());
+        }
+        while n_cards_played<self.stichseq.count_played_cards() {
+            let (epi, card) = self.stichseq.undo_most_recent();
+            self.ahand[epi].add_card(card);
+        }
+        self.vecstoss.retain(|stoss| stoss.n_cards_played<=n_cards_played);
+        if let Some(cardexchange) = self.ocardexchange.take() {
+            if cardexchange.n_cards_played<=n_cards_played {
+                self.ocardexchange = Some(cardexchange);
+            } else {
+                for epi in EPlayerIndex::values() {
+                    cardexchange.revert(epi, &mut self.ahand[epi]);
+                }
+            }
+        }
+        self.observers.notify(|| VGameEvent::Rewind{n_cards_played});
+        Ok(())
+    }
+
+    /// Takes back the most recently played card.
+    pub fn undo(&mut self) -> Result<(), Error> {
+        let n_players = self.stichseq.kurzlang().n_players();
+        match self.stichseq.count_played_cards().checked_sub(1) {
+            None => bail!("No card played yet."),
+            Some(n_cards_played) => self.rewind_to(
+                n_cards_played / n_players,
+                n_cards_played % n_players,
+            ),
+        }
+    }
+
+    /// Takes back all cards up to and including the most recent card of `epi`.
+    pub fn undo_until(&mut self, epi: EPlayerIndex) -> Result<(), Error> {
+        let (i_stich, n_cards) = self.stichseq.visible_stichs().iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i_stich, stich)| stich.iter()
+                .position(|(epi_card, _card)| epi_card==epi)
+                .map(|n_cards| (i_stich, n_cards))
+            )
+            .ok_or_else(|| format_err!("Player {} has not played a card yet.", epi))?;
+        self.rewind_to(i_stich, n_cards)
+    }
+
     pub fn completed_stichs(&self) -> &[SStich] {
         self.stichseq.completed_stichs()
     }
+
+    /// Subscribes `observer` to the remainder of the game.
+    pub fn add_observer(&mut self, observer: Box<dyn TGameObserver>) {
+        self.observers.add(observer);
+    }
+
+    /// Hand that is currently visible to all players (e.g. in Bettel Brett).
+    pub fn revealed_hand(&self) -> Option<(EPlayerIndex, SHand)> {
+        self.rules.revealed_hand_playerindex(&self.stichseq)
+            .map(|epi| (epi, self.ahand[epi].clone()))
+    }
 }
 
 #[derive(Debug)]
 pub struct SGameResult {
     mapepib_confirmed: EnumMap<EPlayerIndex, bool>, // TODO? enumset
-    // TODO store all information about finished game
+    pub gamerecord : SGameRecord,
     pub an_payout : EnumMap<EPlayerIndex, isize>,
     pub stockorgame: VStockOrT<(), SGame>,
 }
@@ -622,16 +1044,67 @@ impl SGameResult {
         );
         *n_stock += n_pay_into_stock;
         assert!(0 <= *n_stock);
+        assert_eq!(*n_stock%2, 0); // games take or give half of the stock
     }
 
     pub fn confirm(&mut self, epi: EPlayerIndex) {
         self.mapepib_confirmed[epi] = true;
     }
+
+    pub fn bock_triggered(&self, ebocktrigger: EBockTrigger) -> bool {
+        match self.stockorgame {
+            VStockOrT::Stock(()) => false,
+            VStockOrT::OrT(ref game) => {
+                let gamefinishedstiche = SStichSequenceGameFinished::new(&game.stichseq);
+                let oplayerparties = game.rules.finished_playerparties(gamefinishedstiche);
+                match ebocktrigger {
+                    EBockTrigger::KontraLost => !game.vecstoss.is_empty() && oplayerparties.is_some_and(|playerparties| {
+                        let epi_primary = unwrap!(EPlayerIndex::values().find(|epi| playerparties.is_primary_party(*epi)));
+                        self.an_payout[epi_primary] < 0
+                    }),
+                    EBockTrigger::SechzigSechzig => oplayerparties.is_some_and(|playerparties| {
+                        60==game.stichseq.completed_stichs_custom_winner_index(|stich| game.rules.winner_index(stich))
+                            .filter(|&(_stich, epi_winner)| playerparties.is_primary_party(epi_winner))
+                            .map(|(stich, _epi_winner)| card_points::points_stich(stich))
+                            .sum::<isize>()
+                    }),
+                    EBockTrigger::Durchmarsch => game.rules.durchmarsch(gamefinishedstiche),
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SBock {
+    vecn_games_remaining: Vec<usize>, // one entry per trigger that is still active
+}
+
+impl SBock {
+    pub fn n_bock(&self) -> usize {
+        self.vecn_games_remaining.len()
+    }
+
+    pub fn register_gameresult(&mut self, obockparams: Option<&SBockParams>, gameresult: &SGameResult) {
+        for n_games_remaining in self.vecn_games_remaining.iter_mut() {
+            *n_games_remaining -= 1;
+        }
+        self.vecn_games_remaining.retain(|n_games_remaining| 0 < *n_games_remaining);
+        if let Some(bockparams) = obockparams {
+            for ebocktrigger in bockparams.vecebocktrigger.iter() {
+                if gameresult.bock_triggered(*ebocktrigger) {
+                    self.vecn_games_remaining.push(bockparams.n_games);
+                }
+            }
+        }
+    }
 }
 
-pub fn stoss_and_doublings(vecstoss: &[SStoss], doublings: &SDoublings) -> (usize, usize) {
+/// Number of stoss and number of doublings as expected by `TRules::payout`.
+/// Each active Bock counts as one doubling, since both double the payout in the same way.
+pub fn stoss_and_doublings(vecstoss: &[SStoss], doublings: &SDoublings, n_bock: usize) -> (usize, usize) {
     (
         vecstoss.len(),
-        doublings.iter().filter(|&(_epi, &b_doubling)| b_doubling).count(),
+        doublings.iter().filter(|&(_epi, &b_doubling)| b_doubling).count() + n_bock, // each bock doubles the payout
     )
 }
@@ -0,0 +1,144 @@
+use crate::primitives::*;
+use crate::rules::*;
+use crate::util::*;
+use super::SCardExchange;
+
+/// Something that happened in one of the game phases.
+#[derive(Debug)]
+pub enum VGameEvent {
+    Deal{ahand: EnumMap<EPlayerIndex, SHand>, n_bock: usize, n_stock: isize},
+    Doubling{epi: EPlayerIndex, b_doubling: bool},
+    Announcement{epi: EPlayerIndex, orules: Option<Box<dyn TActivelyPlayableRules>>},
+    BidRaise{epi: EPlayerIndex, rules: Box<dyn TActivelyPlayableRules>},
+    BidResign{epi: EPlayerIndex},
+    Stoss{epi: EPlayerIndex},
+    SchneiderSchwarz{epi: EPlayerIndex, eschneiderschwarz: ESchneiderSchwarz},
+    CardExchange(SCardExchange),
+    CardPlayed{epi: EPlayerIndex, card: SCard},
+    StichWon{stich: SStich, epi_winner: EPlayerIndex},
+    Rewind{n_cards_played: usize}, // cards played afterwards have been taken back
+    GameResult{an_payout: EnumMap<EPlayerIndex, isize>},
+}
+
+pub trait TGameObserver : Send + Sync { // game phases are shared among threads
+    fn on_event(&mut self, gameevent: &VGameEvent);
+}
+
+/// Observers subscribed to a game, handed on from one game phase to the next.
+/// Clones (e.g. games explored by the AI) start without observers.
+#[derive(Default)]
+pub struct SGameObservers(Vec<Box<dyn TGameObserver>>);
+
+impl SGameObservers {
+    pub fn add(&mut self, observer: Box<dyn TGameObserver>) {
+        self.0.push(observer);
+    }
+
+    pub fn notify(&mut self, fn_gameevent: impl FnOnce() -> VGameEvent) {
+        if !self.0.is_empty() {
+            let gameevent = fn_gameevent();
+            for observer in self.0.iter_mut() {
+                observer.on_event(&gameevent);
+            }
+        }
+    }
+}
+
+impl Clone for SGameObservers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl std::fmt::Debug for SGameObservers {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "SGameObservers({})", self.0.len())
+    }
+}
+
+/// Writes all events to the log.
+pub struct SGameEventLogger;
+
+impl TGameObserver for SGameEventLogger {
+    fn on_event(&mut self, gameevent: &VGameEvent) {
+        info!("{:?}", gameevent);
+    }
+}
+
+#[test]
+fn test_game_observer() {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    struct SEventCollector(Arc<Mutex<Vec<&'static str>>>);
+    impl TGameObserver for SEventCollector {
+        fn on_event(&mut self, gameevent: &VGameEvent) {
+            use VGameEvent::*;
+            unwrap!(self.0.lock()).push(match gameevent {
+                Deal{..} => "Deal",
+                Doubling{..} => "Doubling",
+                Announcement{..} => "Announcement",
+                BidRaise{..} => "BidRaise",
+                BidResign{..} => "BidResign",
+                Stoss{..} => "Stoss",
+                SchneiderSchwarz{..} => "SchneiderSchwarz",
+                CardExchange(_) => "CardExchange",
+                CardPlayed{..} => "CardPlayed",
+                StichWon{..} => "StichWon",
+                Rewind{n_cards_played} => {
+                    assert_eq!(*n_cards_played, EPlayerIndex::SIZE);
+                    "Rewind"
+                },
+                GameResult{..} => "GameResult",
+            });
+        }
+    }
+    let ruleset = unwrap!(SRuleSet::from_string("base-price=10\nsolo-price=50\nlauf-min=3\n[ramsch]\nprice=20\n[doubling]"));
+    let mut dealcards = unwrap!(SDealCards::new(
+        ruleset.clone(),
+        /*n_bock*/0,
+        /*n_stock*/0,
+        deal::VDealSource::new_random(ruleset.ekurzlang).deal(),
+    ));
+    let vecstr_gameevent = Arc::new(Mutex::new(Vec::new()));
+    dealcards.add_observer(Box::new(SEventCollector(Arc::clone(&vecstr_gameevent))));
+    for epi in EPlayerIndex::values() {
+        unwrap!(dealcards.announce_doubling(epi, /*b_doubling*/EPlayerIndex::EPI2==epi));
+    }
+    let mut gamepreparations = unwrap!(dealcards.finish());
+    for epi in EPlayerIndex::values() {
+        unwrap!(gamepreparations.announce_game(epi, /*orules*/None));
+    }
+    let mut game = match unwrap!(gamepreparations.finish()) {
+        VGamePreparationsFinish::DirectGame(game) => game,
+        _ => panic!("Expected Ramsch"),
+    };
+    let play_card = |game: &mut SGame| {
+        let epi = unwrap!(game.current_playable_stich().current_playerindex());
+        let card = game.rules.all_allowed_cards(&game.stichseq, &game.ahand[epi])[0];
+        unwrap!(game.zugeben(card, epi));
+    };
+    for _i_card in 0..EPlayerIndex::SIZE+1 {
+        play_card(&mut game);
+    }
+    unwrap!(game.undo());
+    let mut game_clone = game.clone();
+    while game_clone.which_player_can_do_something().is_some() {
+        play_card(&mut game_clone); // does not notify observers of original game
+    }
+    unwrap!(game_clone.finish());
+    while game.which_player_can_do_something().is_some() {
+        play_card(&mut game);
+    }
+    unwrap!(game.finish());
+    let mut vecstr_gameevent_expected = vec!["Deal"];
+    vecstr_gameevent_expected.extend(std::iter::repeat_n("Doubling", EPlayerIndex::SIZE));
+    vecstr_gameevent_expected.extend(std::iter::repeat_n("Announcement", EPlayerIndex::SIZE));
+    let vecstr_stich = std::iter::repeat_n("CardPlayed", EPlayerIndex::SIZE).chain(std::iter::once("StichWon")).collect::<Vec<_>>();
+    vecstr_gameevent_expected.extend(vecstr_stich.iter().copied());
+    vecstr_gameevent_expected.extend(["CardPlayed", "Rewind"].iter().copied());
+    for _i_stich in 1..ruleset.ekurzlang.cards_per_player() {
+        vecstr_gameevent_expected.extend(vecstr_stich.iter().copied());
+    }
+    vecstr_gameevent_expected.push("GameResult");
+    assert_eq!(*unwrap!(vecstr_gameevent.lock()), vecstr_gameevent_expected);
+}
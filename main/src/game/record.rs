@@ -0,0 +1,164 @@
+use crate::game::*;
+use crate::rules::parser::SRuleDescription;
+use serde::{Deserialize, Serialize};
+use std::{fs::OpenOptions, io::Write, path::Path};
+
+/// Everything that happened in a game, from dealing the cards to the payout.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SGameRecord {
+    pub aveccard_dealt: [Vec<SCard>; EPlayerIndex::SIZE],
+    pub vecb_doubling: Vec<bool>, // starting with EPI0
+    pub bidhistory: SBidHistory,
+    pub n_bock: usize,
+    pub n_stock: isize, // stock before the game
+    pub ostr_ruleset: Option<String>, // None if the game was not set up from a ruleset
+    pub ostr_rules: Option<String>, // None if players had to pay into stock
+    pub vecstoss: Vec<SStoss>,
+    pub oeschneiderschwarz_announced: Option<ESchneiderSchwarz>,
+    pub ocardexchange: Option<SCardExchange>,
+    pub veccard_played: Vec<SCard>, // in the order they were played
+    pub an_payout: [isize; EPlayerIndex::SIZE],
+}
+
+fn find_rules<'rules>(mut itorules: impl Iterator<Item=Option<&'rules dyn TActivelyPlayableRules>>, orulesid: &Option<String>) -> Result<Option<&'rules dyn TActivelyPlayableRules>, Error> {
+    itorules
+        .find(|orules| &orules.map(<dyn TActivelyPlayableRules>::to_string)==orulesid)
+        .ok_or_else(|| format_err!("Rules {:?} not allowed.", orulesid))
+}
+
+impl SGameRecord {
+    pub(super) fn new_stock(
+        ruleset: &SRuleSet,
+        ahand: &EnumMap<EPlayerIndex, SHand>,
+        doublings: &SDoublings,
+        bidhistory: SBidHistory,
+        n_bock: usize,
+        n_stock: isize,
+        an_payout: &EnumMap<EPlayerIndex, isize>,
+    ) -> Self {
+        SGameRecord {
+            aveccard_dealt: ahand.map(|hand| hand.cards().to_vec()).into_raw(),
+            vecb_doubling: doublings.iter().map(|(_epi, &b_doubling)| b_doubling).collect(),
+            bidhistory,
+            n_bock,
+            n_stock,
+            ostr_ruleset: Some(ruleset.str_toml.clone()),
+            ostr_rules: None,
+            vecstoss: Vec::new(),
+            oeschneiderschwarz_announced: None,
+            ocardexchange: None,
+            veccard_played: Vec::new(),
+            an_payout: an_payout.explicit_clone().into_raw(),
+        }
+    }
+
+    pub(super) fn new_game(game: &SGame, an_payout: &EnumMap<EPlayerIndex, isize>) -> Self {
+        SGameRecord {
+            aveccard_dealt: EPlayerIndex::map_from_fn(|epi|
+                game.dealt_hand(epi).cards().to_vec()
+            ).into_raw(),
+            vecb_doubling: game.doublings.iter().map(|(_epi, &b_doubling)| b_doubling).collect(),
+            bidhistory: game.bidhistory.clone(),
+            n_bock: game.n_bock,
+            n_stock: game.n_stock,
+            ostr_ruleset: game.ostr_ruleset.clone(),
+            ostr_rules: Some(SRuleDescription(game.rules.as_ref()).to_string()),
+            vecstoss: game.vecstoss.clone(),
+            oeschneiderschwarz_announced: game.oeschneiderschwarz_announced,
+            ocardexchange: game.ocardexchange.clone(),
+            veccard_played: game.stichseq.visible_stichs().iter()
+                .flat_map(|stich| stich.iter().map(|(_epi, card)| *card))
+                .collect(),
+            an_payout: an_payout.explicit_clone().into_raw(),
+        }
+    }
+
+    /// Replays the record under its ruleset, yielding the finished game (or `Stock` if nobody played).
+    pub fn to_game(&self) -> Result<VStockOrT<(), SGame>, Error> {
+        let ruleset = &SRuleSet::from_string(
+            self.ostr_ruleset.as_ref().ok_or_else(|| format_err!("Record does not contain a ruleset."))?
+        )?;
+        if EPlayerIndex::values().any(|epi| self.aveccard_dealt[epi.to_usize()].len()!=ruleset.ekurzlang.cards_dealt_to(epi)) {
+            bail!("Wrong number of cards dealt.");
+        }
+        let mut dealcards = SDealCards::new(
+            ruleset.clone(),
+            self.n_bock,
+            self.n_stock,
+            EPlayerIndex::map_from_raw(self.aveccard_dealt.clone()).map_into(SHand::new_from_iter),
+        )?;
+        for &b_doubling in self.vecb_doubling.iter() {
+            let epi = dealcards.which_player_can_do_something().ok_or_else(|| format_err!("Too many doublings."))?;
+            dealcards.announce_doubling(epi, b_doubling)?;
+        }
+        let mut gamepreparations = dealcards.finish().map_err(|_| format_err!("Doublings incomplete."))?;
+        for orulesid in self.bidhistory.vecorulesid_gameannouncement.iter() {
+            let epi = gamepreparations.which_player_can_do_something().ok_or_else(|| format_err!("Too many game announcements."))?;
+            let orules = find_rules(allowed_rules(&ruleset.avecrulegroup[epi], gamepreparations.fullhand(epi)), orulesid)?
+                .map(TActivelyPlayableRulesBoxClone::box_clone);
+            gamepreparations.announce_game(epi, orules)?;
+        }
+        let game_without_cards = match gamepreparations.finish().map_err(|_| format_err!("Game announcements incomplete."))? {
+            VGamePreparationsFinish::DetermineRules(mut determinerules) => {
+                for (epi, orulesid) in self.bidhistory.vecpairepiorulesid_determinerules.iter() {
+                    let (epi_active, vecrulegroup) = determinerules.which_player_can_do_something()
+                        .ok_or_else(|| format_err!("Too many bids."))?;
+                    if epi_active!=*epi {
+                        bail!("Bid by wrong player.");
+                    }
+                    if orulesid.is_some() {
+                        let rules = unwrap!(find_rules(allowed_rules(&vecrulegroup, determinerules.fullhand(*epi)), orulesid)?);
+                        if determinerules.orulegroup_der_alte_muss.is_some() {
+                            let i_rules = unwrap!(vecrulegroup.iter().find_map(|rulegroup| rulegroup.position(rules)));
+                            determinerules.announce_game_der_alte(*epi, i_rules)?;
+                        } else {
+                            determinerules.announce_game(*epi, TActivelyPlayableRulesBoxClone::box_clone(rules))?;
+                        }
+                    } else {
+                        determinerules.resign(*epi)?;
+                    }
+                }
+                determinerules.finish().map_err(|_| format_err!("Bids incomplete."))?
+            },
+            VGamePreparationsFinish::DirectGame(game) => game,
+            VGamePreparationsFinish::Stock(_gameresult) => {
+                return Ok(VStockOrT::Stock(()));
+            },
+        };
+        let mut stichseq = SStichSequence::new(ruleset.ekurzlang);
+        for &card in self.veccard_played.iter() {
+            if stichseq.game_finished() {
+                bail!("Too many cards played.");
+            }
+            stichseq.zugeben(card, game_without_cards.rules.as_ref());
+        }
+        if !stichseq.game_finished() {
+            bail!("Not enough cards played.");
+        }
+        let mut game = SGame::new_finished(
+            game_without_cards.rules,
+            game_without_cards.doublings,
+            game_without_cards.ostossparams,
+            self.vecstoss.clone(),
+            self.oeschneiderschwarz_announced,
+            self.ocardexchange.clone(),
+            self.n_bock,
+            self.n_stock,
+            SStichSequenceGameFinished::new(&stichseq),
+            /*fn_before_zugeben*/|_game, _i_stich, _epi, _card| {},
+        )?;
+        if Some(SRuleDescription(game.rules.as_ref()).to_string())!=self.ostr_rules { // includes announced schneider/schwarz
+            bail!("Rules do not match {:?}.", self.ostr_rules);
+        }
+        game.bidhistory = game_without_cards.bidhistory;
+        game.ostr_ruleset = game_without_cards.ostr_ruleset;
+        Ok(VStockOrT::OrT(game))
+    }
+
+    /// Appends the record as a single line of JSON.
+    pub fn append_to_file(&self, path: &Path) -> Result<(), Error> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
@@ -6,26 +6,22 @@ use crate::rules::{
 use crate::skui;
 use std::sync::mpsc;
 
-pub struct SAtTable {
-    pub player: Box<dyn TPlayer>,
-    pub n_money: isize,
-}
-
-pub fn run_simple_game_loop(aplayer: EnumMap<EPlayerIndex, Box<dyn TPlayer>>, n_games: usize, ruleset: SRuleSet) -> ([SAtTable; 4], isize) {
-    let mut aattable = aplayer.map_into(|player| SAtTable{player, n_money:0});
-    let mut n_stock = 0;
-    for _i_game in 0..n_games {
+pub fn run_simple_game_loop(aplayer: &EnumMap<EPlayerIndex, Box<dyn TPlayer>>, session: &mut session::SSession<EPlayerIndex>, mut fn_gameresult: impl FnMut(&SGameResult)) {
+    while !session.is_finished() {
         fn communicate_via_channel<T: std::fmt::Debug>(f: impl FnOnce(mpsc::Sender<T>)) -> T {
             let (txt, rxt) = mpsc::channel::<T>();
             f(txt);
             unwrap!(rxt.recv())
         }
-        let mut dealcards = SDealCards::new(ruleset.clone(), n_stock);
+        let mapepiepi_participant = session.seating().explicit_clone();
+        let aattable = mapepiepi_participant.map(|epi_participant| &aplayer[*epi_participant]);
+        let mut dealcards = session.deal_cards();
+        dealcards.add_observer(Box::new(observer::SGameEventLogger));
         while let Some(epi) = dealcards.which_player_can_do_something() {
             unwrap!(dealcards.announce_doubling(
                 epi,
                 /*b_doubling*/communicate_via_channel(|txb_doubling| {
-                    aattable[epi].player.ask_for_doubling(
+                    aattable[epi].ask_for_doubling(
                         dealcards.first_hand_for(epi),
                         txb_doubling
                     );
@@ -38,12 +34,12 @@ pub fn run_simple_game_loop(aplayer: EnumMap<EPlayerIndex, Box<dyn TPlayer>>, n_
             unwrap!(gamepreparations.announce_game(
                 epi,
                 communicate_via_channel(|txorules| {
-                    aattable[epi].player.ask_for_game(
+                    aattable[epi].ask_for_game(
                         epi,
                         gamepreparations.fullhand(epi),
                         &gamepreparations.gameannouncements,
                         &gamepreparations.ruleset.avecrulegroup[epi],
-                        stoss_and_doublings(/*vecstoss*/&[], &gamepreparations.doublings),
+                        stoss_and_doublings(/*vecstoss*/&[], &gamepreparations.doublings, gamepreparations.n_bock),
                         gamepreparations.n_stock,
                         None,
                         txorules
@@ -56,18 +52,23 @@ pub fn run_simple_game_loop(aplayer: EnumMap<EPlayerIndex, Box<dyn TPlayer>>, n_
             VGamePreparationsFinish::DetermineRules(mut determinerules) => {
                 while let Some((epi, vecrulegroup_steigered))=determinerules.which_player_can_do_something() {
                     if let Some(rules) = communicate_via_channel(|txorules| {
-                        aattable[epi].player.ask_for_game(
+                        aattable[epi].ask_for_game(
                             epi,
                             determinerules.fullhand(epi),
                             /*gameannouncements*/&SPlayersInRound::new(SStaticEPI0{}),
                             &vecrulegroup_steigered,
-                            stoss_and_doublings(/*vecstoss*/&[], &determinerules.doublings),
+                            stoss_and_doublings(/*vecstoss*/&[], &determinerules.doublings, determinerules.n_bock),
                             determinerules.n_stock,
                             Some(determinerules.currently_offered_prio()),
                             txorules
                         );
-                    }).map(TActivelyPlayableRulesBoxClone::box_clone) {
-                        unwrap!(determinerules.announce_game(epi, rules));
+                    }) {
+                        if determinerules.orulegroup_der_alte_muss.is_some() {
+                            let i_rules = unwrap!(vecrulegroup_steigered.iter().find_map(|rulegroup| rulegroup.position(rules)));
+                            unwrap!(determinerules.announce_game_der_alte(epi, i_rules));
+                        } else {
+                            unwrap!(determinerules.announce_game(epi, TActivelyPlayableRulesBoxClone::box_clone(rules)));
+                        }
                     } else {
                         unwrap!(determinerules.resign(epi));
                     }
@@ -83,17 +84,31 @@ pub fn run_simple_game_loop(aplayer: EnumMap<EPlayerIndex, Box<dyn TPlayer>>, n_
         };
         let gameresult = match stockorgame {
             VStockOrT::OrT(mut game) => {
+                if let Some((epi_announce, veceschneiderschwarz)) = game.schneiderschwarz_announceable() {
+                    if let Some(eschneiderschwarz) = communicate_via_channel(|txoeschneiderschwarz| {
+                        aattable[epi_announce].ask_for_schneiderschwarz(
+                            &game,
+                            &veceschneiderschwarz,
+                            txoeschneiderschwarz,
+                        );
+                    }) {
+                        unwrap!(game.announce_schneiderschwarz(epi_announce, eschneiderschwarz));
+                    }
+                }
                 while let Some(gameaction)=game.which_player_can_do_something() {
                     if !gameaction.1.is_empty() {
                         if let Some(epi_stoss) = gameaction.1.iter()
                             .find(|epi| {
                                 communicate_via_channel(|txb_stoss| {
-                                    aattable[**epi].player.ask_for_stoss(
+                                    aattable[**epi].ask_for_stoss(
                                         **epi,
                                         &game.doublings,
                                         game.rules.as_ref(),
                                         &game.ahand[**epi],
+                                        &game.stichseq,
+                                        game.ocardexchange.as_ref(),
                                         &game.vecstoss,
+                                        game.n_bock,
                                         game.n_stock,
                                         txb_stoss,
                                     );
@@ -104,27 +119,33 @@ pub fn run_simple_game_loop(aplayer: EnumMap<EPlayerIndex, Box<dyn TPlayer>>, n_
                             continue;
                         }
                     }
-                    unwrap!(game.zugeben(
-                        communicate_via_channel(|txcard| {
-                            aattable[gameaction.0].player.ask_for_card(
-                                &game,
-                                txcard,
-                            );
-                        }),
-                        gameaction.0
-                    ));
+                    if let Some(epi_exchange) = game.card_exchange_pending() {
+                        let card = communicate_via_channel(|txcard| {
+                            aattable[epi_exchange].ask_for_card_exchange(&game, txcard);
+                        });
+                        unwrap!(game.exchange_card(epi_exchange, card));
+                        continue;
+                    }
+                    if let Some(card) = communicate_via_channel(|txocard| {
+                        aattable[gameaction.0].ask_for_card_or_undo(
+                            &game,
+                            txocard,
+                        );
+                    }) {
+                        unwrap!(game.zugeben(card, gameaction.0));
+                    } else {
+                        unwrap!(game.undo_until(gameaction.0));
+                    }
                 }
                 unwrap!(game.finish())
             },
             VStockOrT::Stock(gameresult) => gameresult,
         };
-        gameresult.apply_payout(&mut n_stock, |epi, n_payout| {
-            aattable[epi].n_money += n_payout;
-        });
-        assert_eq!(n_stock + aattable.iter().map(|attable| attable.n_money).sum::<isize>(), 0);
-        skui::print_account_balance(&aattable.map(|attable| attable.n_money), n_stock);
-        aattable.as_raw_mut().rotate_left(1);
+        fn_gameresult(&gameresult);
+        session.register_gameresult(gameresult);
+        skui::print_account_balance(
+            &mapepiepi_participant.map(|epi_participant| session.balance(epi_participant)),
+            session.n_stock(),
+        );
     }
-    (aattable.into_raw(), n_stock)
 }
-
@@ -0,0 +1,280 @@
+use super::{deal::VDealSource, record::SGameRecord, *};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VSessionLength {
+    Unlimited,
+    Games(usize), // e.g. 36
+}
+
+/// Payment to settle the balances at the end of a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct STransfer<Participant> {
+    pub participant_from: Participant,
+    pub participant_to: Participant,
+    pub n_money: isize,
+}
+
+/// A series of games played at one table.
+#[derive(Debug)]
+pub struct SSession<Participant> {
+    pub ruleset: SRuleSet,
+    dealsource: VDealSource,
+    sessionlength: VSessionLength,
+    mapepiparticipant: EnumMap<EPlayerIndex, Participant>, // seat order for the next game
+    n_stock: isize,
+    bock: SBock,
+    vecgamerecord: Vec<SGameRecord>,
+    opath_record: Option<PathBuf>, // if given, each game record is appended to this file
+    vecpairparticipantn_money: Vec<(Participant, isize)>, // in order of first appearance
+}
+
+impl<Participant: Clone + PartialEq + std::fmt::Debug> SSession<Participant> {
+    pub fn new(
+        ruleset: SRuleSet,
+        dealsource: VDealSource,
+        sessionlength: VSessionLength,
+        mapepiparticipant: EnumMap<EPlayerIndex, Participant>,
+        opath_record: Option<PathBuf>,
+    ) -> Self {
+        SSession {
+            ruleset,
+            dealsource,
+            sessionlength,
+            mapepiparticipant,
+            n_stock: 0,
+            bock: SBock::default(),
+            vecgamerecord: Vec::new(),
+            opath_record,
+            vecpairparticipantn_money: Vec::new(),
+        }
+    }
+
+    pub fn seating(&self) -> &EnumMap<EPlayerIndex, Participant> {
+        &self.mapepiparticipant
+    }
+
+    /// Replaces the seat order for the next game, e.g. if participants join or leave the table.
+    pub fn seat(&mut self, mapepiparticipant: EnumMap<EPlayerIndex, Participant>) {
+        self.mapepiparticipant = mapepiparticipant;
+    }
+
+    pub fn n_stock(&self) -> isize {
+        self.n_stock
+    }
+
+    pub fn balance(&self, participant: &Participant) -> isize {
+        self.vecpairparticipantn_money.iter()
+            .find(|(participant_known, _n_money)| participant_known==participant)
+            .map_or(0, |(_participant, n_money)| *n_money)
+    }
+
+    /// Balances of all participants that played at least one game.
+    pub fn balances(&self) -> &[(Participant, isize)] {
+        &self.vecpairparticipantn_money
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match self.sessionlength {
+            VSessionLength::Unlimited => false,
+            VSessionLength::Games(n_games) => n_games<=self.vecgamerecord.len(),
+        }
+    }
+
+    /// Ends the session after the round (one game per dealer) that the current game belongs to.
+    pub fn finish_current_round(&mut self) {
+        let n_games = self.vecgamerecord.len() + 1; // including the game currently played
+        let n_players = self.ruleset.ekurzlang.n_players();
+        let n_games_round_complete = n_games.div_ceil(n_players) * n_players;
+        self.sessionlength = match self.sessionlength {
+            VSessionLength::Games(n_games_planned) if n_games_planned<n_games_round_complete => VSessionLength::Games(n_games_planned),
+            VSessionLength::Unlimited | VSessionLength::Games(_) => VSessionLength::Games(n_games_round_complete),
+        };
+    }
+
+    pub fn deal_cards(&mut self) -> SDealCards {
+        assert!(!self.is_finished());
+        unwrap!(SDealCards::new(
+            self.ruleset.clone(),
+            self.bock.n_bock(),
+            self.n_stock,
+            self.dealsource.deal(),
+        ))
+    }
+
+    /// Pays the seated participants, records the game and passes the deal on to the next player.
+    ///
+    /// With fewer than four players, only the first seats play and rotate.
+    pub fn register_gameresult(&mut self, gameresult: SGameResult) {
+        if let Some(ref path_record) = self.opath_record {
+            if let Err(err) = gameresult.gamerecord.append_to_file(path_record) {
+                error!("Error writing game record: {}", err);
+            }
+        }
+        self.vecgamerecord.push(gameresult.gamerecord.clone());
+        self.bock.register_gameresult(self.ruleset.obockparams.as_ref(), &gameresult);
+        let ekurzlang = self.ruleset.ekurzlang;
+        for participant in &self.mapepiparticipant.as_raw()[..ekurzlang.n_players()] {
+            if !self.vecpairparticipantn_money.iter().any(|(participant_known, _n_money)| participant_known==participant) {
+                self.vecpairparticipantn_money.push((participant.clone(), 0));
+            }
+        }
+        let mapepiparticipant = &self.mapepiparticipant;
+        let vecpairparticipantn_money = &mut self.vecpairparticipantn_money;
+        gameresult.apply_payout(&mut self.n_stock, |epi, n_payout| {
+            if !ekurzlang.takes_part(epi) {
+                assert_eq!(n_payout, 0);
+                return;
+            }
+            unwrap!(vecpairparticipantn_money.iter_mut()
+                .find(|(participant, _n_money)| participant==&mapepiparticipant[epi])
+            ).1 += n_payout;
+        });
+        assert_eq!(
+            self.n_stock + self.vecpairparticipantn_money.iter().map(|(_participant, n_money)| n_money).sum::<isize>(),
+            0
+        );
+        self.mapepiparticipant.as_raw_mut()[..ekurzlang.n_players()].rotate_left(1);
+    }
+
+    /// Who pays whom how much, after the remaining stock has been split evenly among all participants.
+    pub fn settlement(&self) -> Vec<STransfer<Participant>> {
+        let mut vecpairparticipantn_money = self.vecpairparticipantn_money.clone();
+        let n_participants = vecpairparticipantn_money.len().as_num::<isize>();
+        for (i_participant, (_participant, n_money)) in vecpairparticipantn_money.iter_mut().enumerate() {
+            *n_money += self.n_stock.div_euclid(n_participants)
+                + if i_participant.as_num::<isize>() < self.n_stock.rem_euclid(n_participants) {1} else {0};
+        }
+        assert_eq!(vecpairparticipantn_money.iter().map(|(_participant, n_money)| n_money).sum::<isize>(), 0);
+        let mut vectransfer = Vec::new();
+        let mut itpairparticipantn_money_debtor = vecpairparticipantn_money.iter().filter(|(_participant, n_money)| *n_money<0).cloned().peekable();
+        let mut itpairparticipantn_money_creditor = vecpairparticipantn_money.iter().filter(|(_participant, n_money)| 0<*n_money).cloned().peekable();
+        while let (Some((participant_debtor, n_debt)), Some((participant_creditor, n_credit))) = (
+            itpairparticipantn_money_debtor.peek_mut(),
+            itpairparticipantn_money_creditor.peek_mut(),
+        ) {
+            let n_money = (-*n_debt).min(*n_credit);
+            vectransfer.push(STransfer{
+                participant_from: participant_debtor.clone(),
+                participant_to: participant_creditor.clone(),
+                n_money,
+            });
+            *n_debt += n_money;
+            *n_credit -= n_money;
+            if 0==*n_debt {
+                itpairparticipantn_money_debtor.next();
+            }
+            if 0==*n_credit {
+                itpairparticipantn_money_creditor.next();
+            }
+        }
+        vectransfer
+    }
+}
+
+#[test]
+fn test_settlement() {
+    let mut session = SSession::new(
+        unwrap!(SRuleSet::from_string("base-price=10\nsolo-price=50\nlauf-min=3\n[rufspiel]\n[stock]")),
+        VDealSource::new_random(EKurzLang::Lang),
+        VSessionLength::Games(6),
+        EPlayerIndex::map_from_fn(|epi| epi),
+        /*opath_record*/None,
+    );
+    session.vecpairparticipantn_money = vec![
+        (EPlayerIndex::EPI0, 40),
+        (EPlayerIndex::EPI1, -70),
+        (EPlayerIndex::EPI2, 20),
+        (EPlayerIndex::EPI3, -11),
+    ];
+    session.n_stock = 21;
+    assert_eq!(
+        session.settlement(),
+        vec![
+            STransfer{participant_from: EPlayerIndex::EPI1, participant_to: EPlayerIndex::EPI0, n_money: 46},
+            STransfer{participant_from: EPlayerIndex::EPI1, participant_to: EPlayerIndex::EPI2, n_money: 19},
+            STransfer{participant_from: EPlayerIndex::EPI3, participant_to: EPlayerIndex::EPI2, n_money: 6},
+        ]
+    );
+    assert!(!session.is_finished());
+    let gamerecord = SGameRecord {
+        aveccard_dealt: Default::default(),
+        vecb_doubling: Vec::new(),
+        bidhistory: SBidHistory::default(),
+        n_bock: 0,
+        n_stock: 0,
+        ostr_ruleset: None,
+        ostr_rules: None,
+        vecstoss: Vec::new(),
+        oeschneiderschwarz_announced: None,
+        ocardexchange: None,
+        veccard_played: Vec::new(),
+        an_payout: [0; EPlayerIndex::SIZE],
+    };
+    for (n_games, sessionlength, sessionlength_finish_round) in [
+        (3, VSessionLength::Unlimited, VSessionLength::Games(4)),
+        (4, VSessionLength::Unlimited, VSessionLength::Games(8)),
+        (4, VSessionLength::Games(6), VSessionLength::Games(6)),
+        (6, VSessionLength::Games(36), VSessionLength::Games(8)),
+    ] {
+        session.vecgamerecord = vec![gamerecord.clone(); n_games];
+        session.sessionlength = sessionlength;
+        session.finish_current_round();
+        assert_eq!(session.sessionlength, sessionlength_finish_round);
+    }
+}
+
+#[test]
+fn test_register_gameresult() {
+    let gameresult = |an_payout: [isize; EPlayerIndex::SIZE]| SGameResult {
+        mapepib_confirmed: EPlayerIndex::map_from_fn(|_epi| true),
+        gamerecord: SGameRecord {
+            aveccard_dealt: Default::default(),
+            vecb_doubling: Vec::new(),
+            bidhistory: SBidHistory::default(),
+            n_bock: 0,
+            n_stock: 0,
+            ostr_ruleset: None,
+            ostr_rules: None,
+            vecstoss: Vec::new(),
+            oeschneiderschwarz_announced: None,
+            ocardexchange: None,
+            veccard_played: Vec::new(),
+            an_payout,
+        },
+        an_payout: EnumMap::from_raw(an_payout),
+        stockorgame: VStockOrT::Stock(()),
+    };
+    let mut session = SSession::new(
+        unwrap!(SRuleSet::from_string("base-price=10\nsolo-price=50\nlauf-min=3\n[rufspiel]\n[stock]")),
+        VDealSource::new_random(EKurzLang::Lang),
+        VSessionLength::Games(3),
+        EnumMap::from_raw(["A", "B", "C", "D"]),
+        /*opath_record*/None,
+    );
+    for (an_payout, an_seating, vecpairparticipantn_money, n_stock) in [
+        ([30, -10, -10, -10], ["B", "C", "D", "A"], vec![("A", 30), ("B", -10), ("C", -10), ("D", -10)], 0),
+        ([-10, -10, -10, -10], ["C", "D", "A", "B"], vec![("A", 20), ("B", -20), ("C", -20), ("D", -20)], 40),
+        ([60, -20, 0, 0], ["D", "A", "B", "C"], vec![("A", 20), ("B", -20), ("C", 40), ("D", -40)], 0),
+    ] {
+        assert!(!session.is_finished());
+        session.register_gameresult(gameresult(an_payout));
+        assert_eq!(session.seating().as_raw(), &an_seating);
+        assert_eq!(session.balances(), vecpairparticipantn_money.as_slice());
+        assert_eq!(session.n_stock(), n_stock);
+    }
+    assert!(session.is_finished());
+    let mut session = SSession::new(
+        unwrap!(SRuleSet::from_string("base-price=20\nsolo-price=50\nlauf-min=3\ndeck=\"lang\"\nplayers=3\n[solo]")),
+        VDealSource::new_random(EKurzLang::Dreier),
+        VSessionLength::Unlimited,
+        EnumMap::from_raw(["A", "B", "C", "<EMPTY>"]),
+        /*opath_record*/None,
+    );
+    session.register_gameresult(gameresult([100, -50, -50, 0]));
+    assert_eq!(session.seating().as_raw(), &["B", "C", "A", "<EMPTY>"]); // the empty seat does not rotate
+    assert_eq!(session.balances(), &[("A", 100), ("B", -50), ("C", -50)]);
+    session.register_gameresult(gameresult([-20, 40, -20, 0]));
+    assert_eq!(session.balances(), &[("A", 80), ("B", -70), ("C", -10)]);
+    assert_eq!(session.n_stock(), 0);
+}
@@ -1,7 +1,6 @@
 use crate::ai::{handiterators::*, suspicion::*, *};
 use crate::game::*;
 use crate::primitives::*;
-use crate::rules::{payoutdecider::*, rulessolo::*, *};
 use crate::util::*;
 use itertools::Itertools;
 use std::{
@@ -9,26 +8,6 @@ use std::{
     time::{Instant, Duration},
 };
 
-pub trait TPayoutDeciderSoloLikeDefault : TPayoutDeciderSoloLike {
-    fn default_payoutdecider(n_payout_base: isize, n_payout_schneider_schwarz: isize, laufendeparams: SLaufendeParams) -> Self;
-}
-impl TPayoutDeciderSoloLikeDefault for SPayoutDeciderPointBased<VGameAnnouncementPrioritySoloLike> {
-    fn default_payoutdecider(n_payout_base: isize, n_payout_schneider_schwarz: isize, laufendeparams: SLaufendeParams) -> Self {
-        Self::new(
-            SPayoutDeciderParams::new(n_payout_base, n_payout_schneider_schwarz, laufendeparams),
-            VGameAnnouncementPrioritySoloLike::SoloSimple(0),
-        )
-    }
-}
-impl TPayoutDeciderSoloLikeDefault for SPayoutDeciderTout {
-    fn default_payoutdecider(n_payout_base: isize, n_payout_schneider_schwarz: isize, laufendeparams: SLaufendeParams) -> Self {
-        Self::new(
-            SPayoutDeciderParams::new(n_payout_base, n_payout_schneider_schwarz, laufendeparams),
-            0,
-        )
-    }
-}
-
 #[cfg(test)]
 pub fn make_stich_vector(vecpairepiacard_stich: &[(EPlayerIndex, [SCard; 4])]) -> Vec<SStich> {
     vecpairepiacard_stich.iter()
@@ -59,7 +38,7 @@ pub struct SGameAnalysis {
     pub duration: Duration,
 }
 
-pub fn analyze_game(str_description: &str, str_link: &str, game_in: SGame) -> SGameAnalysis {
+pub fn analyze_game(str_description: &str, str_link: &str, game_in: SGame, b_equivalent_cards: bool) -> SGameAnalysis {
     let instant_begin = Instant::now();
     let mut vecanalysisimpr = Vec::new();
     let an_payout = unwrap!(game_in.clone().finish()).an_payout;
@@ -76,6 +55,9 @@ pub fn analyze_game(str_description: &str, str_link: &str, game_in: SGame) -> SG
         game_in.doublings.clone(),
         game_in.ostossparams.clone(),
         game_in.vecstoss.clone(),
+        game_in.oeschneiderschwarz_announced,
+        game_in.ocardexchange.clone(),
+        game_in.n_bock,
         game_in.n_stock,
         SStichSequenceGameFinished::new(&game_in.stichseq),
         /*fn_before_zugeben*/|game, i_stich, epi, card| {
@@ -86,9 +68,11 @@ pub fn analyze_game(str_description: &str, str_link: &str, game_in: SGame) -> SG
                         let determinebestcardresult = determine_best_card(
                             &determinebestcard,
                             $itahand,
-                            &|_,_| (/*no filtering*/),
+                            &equivalent_cards_filter(game.rules.as_ref(), b_equivalent_cards),
                             &SMinReachablePayout::new_from_game(game),
+                            Some(&SSnapshotCache::new(N_BYTES_SNAPSHOT_CACHE_DEFAULT)),
                             /*ostr_file_out*/None,
+                            /*oinstant_deadline*/None,
                         );
                         let (veccard, minmax) = determinebestcardresult.cards_with_maximum_value();
                         if 
@@ -114,6 +98,7 @@ pub fn analyze_game(str_description: &str, str_link: &str, game_in: SGame) -> SG
                             &game.stichseq,
                             game.ahand[determinebestcard.epi_fixed].clone(),
                             determinebestcard.epi_fixed,
+                            game.revealed_hand(),
                             game.rules.as_ref(),
                         ),
                     )
@@ -271,7 +256,7 @@ pub struct SGameWithDesc {
     pub resgame: Result<SGame, failure::Error>,
 }
 
-pub fn analyze_games(path_analysis: &std::path::Path, fn_link: impl Fn(&str)->String, itgamewithdesc: impl Iterator<Item=SGameWithDesc>) -> Result<(), failure::Error> {
+pub fn analyze_games(path_analysis: &std::path::Path, fn_link: impl Fn(&str)->String, itgamewithdesc: impl Iterator<Item=SGameWithDesc>, b_equivalent_cards: bool) -> Result<(), failure::Error> {
     create_dir_if_not_existent(&path_analysis)?;
     generate_html_auxiliary_files(path_analysis)?;
     let str_date = format!("{}", chrono::Local::now().format("%Y%m%d%H%M%S"));
@@ -291,11 +276,11 @@ pub fn analyze_games(path_analysis: &std::path::Path, fn_link: impl Fn(&str)->St
     str_index_html += "<table>";
     for gamewithdesc in itgamewithdesc {
         if let Ok(game) = gamewithdesc.resgame {
-            let str_rules = format!("{}", game.rules);
+            let str_rules = crate::rules::parser::SRuleDescription(game.rules.as_ref()).to_string();
             let path_analysis_game = path_analysis.join(gamewithdesc.str_description.replace("/", "_").replace(".", "_"));
             create_dir_if_not_existent(&path_analysis_game)?;
             let path = path_analysis_game.join("analysis.html");
-            let gameanalysis = analyze_game(&gamewithdesc.str_description, &fn_link(&gamewithdesc.str_description), game);
+            let gameanalysis = analyze_game(&gamewithdesc.str_description, &fn_link(&gamewithdesc.str_description), game, b_equivalent_cards);
             let path = write_html(path, &gameanalysis.str_html)?;
             str_index_html += &format!(
                 r#"<tr>
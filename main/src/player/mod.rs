@@ -6,6 +6,7 @@ pub mod playerrandom;
 use crate::game::*;
 use crate::primitives::*;
 use crate::rules::{ruleset::*, *};
+use crate::util::*;
 
 use std::sync::mpsc;
 
@@ -17,6 +18,12 @@ pub trait TPlayer {
     );
 
     fn ask_for_card(&self, game: &SGame, txcard: mpsc::Sender<SCard>);
+    /// Like `ask_for_card`, but the player may send `None` to take back their most recent card.
+    fn ask_for_card_or_undo(&self, game: &SGame, txocard: mpsc::Sender<Option<SCard>>) {
+        let (txcard, rxcard) = mpsc::channel();
+        self.ask_for_card(game, txcard);
+        unwrap!(txocard.send(Some(unwrap!(rxcard.recv()))));
+    }
     // TODO: players need information about who already wants to play
     fn ask_for_game<'rules>(
         &self,
@@ -30,16 +37,30 @@ pub trait TPlayer {
         txorules: mpsc::Sender<Option<&'rules dyn TActivelyPlayableRules>>
     );
 
+    #[allow(clippy::too_many_arguments)]
     fn ask_for_stoss(
         &self,
         epi: EPlayerIndex,
         doublings: &SDoublings,
         rules: &dyn TRules,
         hand: &SHand,
+        stichseq: &SStichSequence,
+        ocardexchange: Option<&SCardExchange>,
         vecstoss: &[SStoss],
+        n_bock: usize,
         n_stock: isize,
         txb: mpsc::Sender<bool>,
     );
 
+    fn ask_for_schneiderschwarz(
+        &self,
+        game: &SGame,
+        veceschneiderschwarz: &[ESchneiderSchwarz],
+        txoeschneiderschwarz: mpsc::Sender<Option<ESchneiderSchwarz>>,
+    );
+
+    /// Asks for the card the player returns to the active player in exchange for the active player's trumpf (see `SGame::card_exchange_pending`).
+    fn ask_for_card_exchange(&self, game: &SGame, txcard: mpsc::Sender<SCard>);
+
     fn name(&self) -> &str;
 }
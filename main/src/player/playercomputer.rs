@@ -1,7 +1,7 @@
 use crate::ai::{
     handiterators::forever_rand_hands,
     suspicion::{
-        explore_snapshots, SMinReachablePayout,
+        explore_snapshots, SMinReachablePayoutAlphaBeta,
     },
     *,
 };
@@ -80,22 +80,34 @@ impl TPlayer for SPlayerComputer {
         doublings: &SDoublings,
         rules: &dyn TRules,
         hand: &SHand,
+        stichseq: &SStichSequence,
+        ocardexchange: Option<&SCardExchange>,
         vecstoss: &[SStoss],
+        n_bock: usize,
         n_stock: isize,
         txb: mpsc::Sender<bool>,
     ) {
         let n_samples_per_stoss = 5; // TODO move to ai, make adjustable
-        let ekurzlang = EKurzLang::from_cards_per_player(hand.cards().len());
-        let mut vecpairahandf_suspicion = forever_rand_hands(/*stichseq*/&SStichSequence::new(ekurzlang), hand.clone(), epi, rules)
+        let ekurzlang = stichseq.kurzlang();
+        let mut vecpairahandf_suspicion = forever_rand_hands(stichseq, hand.clone(), epi, /*ohand_revealed*/None, rules)
+            .filter(|ahand| ocardexchange.is_none_or(|cardexchange| {
+                // exchanged cards are known to everyone, but not to the hand iterators
+                stichseq.initial_hand(cardexchange.epi_active, &ahand[cardexchange.epi_active]).contains(cardexchange.card_partner)
+                    && stichseq.initial_hand(cardexchange.epi_partner, &ahand[cardexchange.epi_partner]).contains(cardexchange.card_active)
+            }))
             .take(2*n_samples_per_stoss)
             .map(|ahand| {
                 let f_rank_rules = rules.playerindex().map_or(0f64, |epi_active| {
                     if epi!=epi_active {
+                        let mut hand_dealt = stichseq.initial_hand(epi_active, &ahand[epi_active]);
+                        if let Some(cardexchange) = ocardexchange {
+                            cardexchange.revert(epi_active, &mut hand_dealt);
+                        }
                         self.ai.rank_rules(
-                            SFullHand::new(&ahand[epi_active], ekurzlang),
+                            SFullHand::new(&hand_dealt, ekurzlang),
                             /*epi_rank*/epi_active,
                             rules,
-                            stoss_and_doublings(vecstoss, doublings),
+                            stoss_and_doublings(vecstoss, doublings, n_bock),
                             n_stock,
                         )
                     } else {
@@ -116,16 +128,17 @@ impl TPlayer for SPlayerComputer {
                     explore_snapshots(
                         &mut ahand,
                         rules,
-                        &mut SStichSequence::new(ekurzlang),
+                        &mut stichseq.clone(),
                         &branching_factor(|_stichseq| (1, 2)),
-                        &SMinReachablePayout::new(
+                        &SMinReachablePayoutAlphaBeta::new(
                             rules,
                             epi,
-                            /*tpln_stoss_doubling*/stoss_and_doublings(vecstoss, doublings),
+                            /*tpln_stoss_doubling*/stoss_and_doublings(vecstoss, doublings, n_bock),
                             n_stock,
                         ),
+                        /*osnapshotcache*/None,
                         /*opath_out_dir*/None,
-                    ).t_min[epi]
+                    )[epi]
                 })
                 .sum::<isize>().as_num::<f64>()
                 / n_samples_per_stoss.as_num::<f64>()
@@ -133,6 +146,40 @@ impl TPlayer for SPlayerComputer {
         ))
     }
 
+    fn ask_for_schneiderschwarz(
+        &self,
+        game: &SGame,
+        veceschneiderschwarz: &[ESchneiderSchwarz],
+        txoeschneiderschwarz: mpsc::Sender<Option<ESchneiderSchwarz>>,
+    ) {
+        let epi = unwrap!(game.rules.playerindex());
+        let hand = &game.ahand[epi];
+        let rank = |rules: &dyn TRules| self.ai.rank_rules(
+            SFullHand::new(hand, game.kurzlang()),
+            /*epi_rank*/epi,
+            rules,
+            stoss_and_doublings(&game.vecstoss, &game.doublings, game.n_bock),
+            game.n_stock,
+        );
+        unwrap!(txoeschneiderschwarz.send(unwrap!(std::iter::once((None, rank(game.rules.as_ref())))
+            .chain(veceschneiderschwarz.iter().map(|eschneiderschwarz| (
+                Some(*eschneiderschwarz),
+                rank(unwrap!(game.rules.with_schneiderschwarz_announced(*eschneiderschwarz)).as_ref()),
+            )))
+            .max_by(|&(_oeschneiderschwarz_lhs, f_payout_avg_lhs), &(_oeschneiderschwarz_rhs, f_payout_avg_rhs)| {
+                unwrap!(f_payout_avg_lhs.partial_cmp(&f_payout_avg_rhs))
+            })
+        ).0));
+    }
+
+    fn ask_for_card_exchange(&self, game: &SGame, txcard: mpsc::Sender<SCard>) {
+        // TODO more intelligent exchange strategy
+        // keep trumpf for the own party, and give away as few points as possible
+        unwrap!(txcard.send(*unwrap!(game.ahand[unwrap!(game.card_exchange_pending())].cards().iter()
+            .min_by_key(|card| (game.rules.trumpforfarbe(**card).is_trumpf(), card_points::points_card(**card)))
+        )));
+    }
+
     fn name(&self) -> &str {
         "SPlayerComputer" // TODO
     }
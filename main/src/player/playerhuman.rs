@@ -45,6 +45,41 @@ fn choose_ruleset_or_rules<'t, T>(
     )
 }
 
+impl SPlayerHuman {
+    fn internal_ask_for_card(&self, game: &SGame, b_undo_allowed: bool) -> Option<SCard> {
+        skui::print_stichseq(unwrap!(game.current_playable_stich().current_playerindex()), &game.stichseq);
+        let hand = {
+            let mut veccard = game.ahand[unwrap!(game.which_player_can_do_something()).0].cards().clone();
+            game.rules.sort_cards_first_trumpf_then_farbe(&mut veccard);
+            SHand::new_from_vec(veccard)
+        };
+        let veccard_allowed = game.rules.all_allowed_cards(&game.stichseq, &hand);
+        skui::ask_for_alternative_or_undo(
+            hand.cards(),
+            &if b_undo_allowed {
+                skui::choose_card_from_hand_or_undo_key_bindings()
+            } else {
+                skui::choose_card_from_hand_key_bindings()
+            },
+            |card| {veccard_allowed.iter().any(|card_allowed| card_allowed==card)},
+            |ncwin, i_card_chosen, ocard_suggest| {
+                if let Some(card) = *ocard_suggest {
+                    skui::wprintln(ncwin, &format!("AI: {}", card));
+                }
+                if b_undo_allowed {
+                    skui::wprintln(ncwin, "Press u to take back your last card.");
+                }
+                skui::print_hand(hand.cards(), Some(i_card_chosen));
+                skui::print_game_info(game.rules.as_ref(), &game.doublings, &game.vecstoss);
+            },
+            || Some(self.ai.suggest_card(
+                game,
+                /*opath_out_dir*/Some(&std::path::Path::new("suspicion"))
+            ))
+        ).copied()
+    }
+}
+
 impl TPlayer for SPlayerHuman {
     fn ask_for_doubling(
         &self,
@@ -72,31 +107,15 @@ impl TPlayer for SPlayerHuman {
     }
 
     fn ask_for_card(&self, game: &SGame, txcard: mpsc::Sender<SCard>) {
-        skui::print_stichseq(unwrap!(game.current_playable_stich().current_playerindex()), &game.stichseq);
-        let hand = {
-            let mut veccard = game.ahand[unwrap!(game.which_player_can_do_something()).0].cards().clone();
-            game.rules.sort_cards_first_trumpf_then_farbe(&mut veccard);
-            SHand::new_from_vec(veccard)
-        };
-        let veccard_allowed = game.rules.all_allowed_cards(&game.stichseq, &hand);
-        if txcard.send(
-            *skui::ask_for_alternative(
-                hand.cards(),
-                &skui::choose_card_from_hand_key_bindings(),
-                |card| {veccard_allowed.iter().any(|card_allowed| card_allowed==card)},
-                |ncwin, i_card_chosen, ocard_suggest| {
-                    if let Some(card) = *ocard_suggest {
-                        skui::wprintln(ncwin, &format!("AI: {}", card));
-                    }
-                    skui::print_hand(hand.cards(), Some(i_card_chosen));
-                    skui::print_game_info(game.rules.as_ref(), &game.doublings, &game.vecstoss);
-                },
-                || Some(self.ai.suggest_card(
-                    game,
-                    /*opath_out_dir*/Some(&std::path::Path::new("suspicion"))
-                ))
-            )
-        ).is_err() {
+        if txcard.send(unwrap!(self.internal_ask_for_card(game, /*b_undo_allowed*/false))).is_err() {
+            unimplemented!() // we possibly want to be able to deal with "blocked" plays (timeout etc.)
+        }
+    }
+
+    fn ask_for_card_or_undo(&self, game: &SGame, txocard: mpsc::Sender<Option<SCard>>) {
+        let epi = unwrap!(game.which_player_can_do_something()).0;
+        let b_undo_allowed = game.stichseq.visible_stichs().iter().any(|stich| stich.get(epi).is_some());
+        if txocard.send(self.internal_ask_for_card(game, b_undo_allowed)).is_err() {
             unimplemented!() // we possibly want to be able to deal with "blocked" plays (timeout etc.)
         }
     }
@@ -153,7 +172,10 @@ impl TPlayer for SPlayerHuman {
         doublings: &SDoublings,
         rules: &dyn TRules,
         hand: &SHand,
+        _stichseq: &SStichSequence,
+        _ocardexchange: Option<&SCardExchange>,
         vecstoss: &[SStoss],
+        _n_bock: usize,
         _n_stock: isize,
         txb: mpsc::Sender<bool>,
     ) {
@@ -188,6 +210,59 @@ impl TPlayer for SPlayerHuman {
         )))
     }
 
+    fn ask_for_schneiderschwarz(
+        &self,
+        game: &SGame,
+        veceschneiderschwarz: &[ESchneiderSchwarz],
+        txoeschneiderschwarz: mpsc::Sender<Option<ESchneiderSchwarz>>,
+    ) {
+        let epi = unwrap!(game.rules.playerindex());
+        let vecoeschneiderschwarz = std::iter::once(None)
+            .chain(veceschneiderschwarz.iter().copied().map(Some))
+            .collect::<Vec<_>>();
+        unwrap!(txoeschneiderschwarz.send(*skui::ask_for_alternative(
+            &vecoeschneiderschwarz,
+            &skui::choose_alternative_from_list_key_bindings(),
+            |_| true, // all alternatives allowed
+            |ncwin, i_oeschneiderschwarz_chosen, ooeschneiderschwarz_suggest| {
+                assert!(ooeschneiderschwarz_suggest.is_none());
+                skui::print_game_info(game.rules.as_ref(), &game.doublings, &game.vecstoss);
+                {
+                    let mut veccard = game.ahand[epi].cards().clone();
+                    game.rules.sort_cards_first_trumpf_then_farbe(veccard.as_mut_slice());
+                    skui::print_hand(&veccard, None);
+                }
+                for (i_oeschneiderschwarz, oeschneiderschwarz) in vecoeschneiderschwarz.iter().enumerate() {
+                    skui::wprintln(ncwin, &format!("{} {}",
+                        if i_oeschneiderschwarz==i_oeschneiderschwarz_chosen {"*"} else {" "},
+                        match oeschneiderschwarz {
+                            None => "No announcement".to_string(),
+                            Some(eschneiderschwarz) => format!("Announce {}", eschneiderschwarz),
+                        },
+                    ));
+                }
+            },
+            || None, // TODO implement suggestions
+        )))
+    }
+
+    fn ask_for_card_exchange(&self, game: &SGame, txcard: mpsc::Sender<SCard>) {
+        let mut veccard = game.ahand[unwrap!(game.card_exchange_pending())].cards().clone();
+        game.rules.sort_cards_first_trumpf_then_farbe(veccard.as_mut_slice());
+        unwrap!(txcard.send(*skui::ask_for_alternative(
+            &veccard,
+            &skui::choose_card_from_hand_key_bindings(),
+            |_card| true, // any card can be returned
+            |ncwin, i_card_chosen, ocard_suggest| {
+                assert!(ocard_suggest.is_none());
+                skui::wprintln(ncwin, &format!("Which card do you give to {} in exchange for the trumpf?", unwrap!(game.rules.playerindex())));
+                skui::print_hand(&veccard, Some(i_card_chosen));
+                skui::print_game_info(game.rules.as_ref(), &game.doublings, &game.vecstoss);
+            },
+            || None, // TODO implement suggestions
+        )))
+    }
+
     fn name(&self) -> &str {
         "SPlayerHuman" // TODO
     }
@@ -17,7 +17,7 @@ impl<FnCheckAskForCard: Fn(&SGame)> TPlayer for SPlayerRandom<FnCheckAskForCard>
         _veccard: &[SCard],
         txb_doubling: mpsc::Sender<bool>,
     ) {
-        unwrap!(txb_doubling.send(rand::random()));
+        unwrap!(txb_doubling.send(rng::thread_rng().gen()));
     }
 
     fn ask_for_card(&self, game: &SGame, txcard: mpsc::Sender<SCard>) {
@@ -27,7 +27,7 @@ impl<FnCheckAskForCard: Fn(&SGame)> TPlayer for SPlayerRandom<FnCheckAskForCard>
                 game.rules.all_allowed_cards(
                     &game.stichseq,
                     &game.ahand[unwrap!(game.which_player_can_do_something()).0],
-                ).choose(&mut rand::thread_rng()).copied()
+                ).choose(&mut rng::thread_rng()).copied()
             )
         ));
     }
@@ -44,7 +44,7 @@ impl<FnCheckAskForCard: Fn(&SGame)> TPlayer for SPlayerRandom<FnCheckAskForCard>
         txorules: mpsc::Sender<Option<&'rules dyn TActivelyPlayableRules>>
     ) {
         unwrap!(txorules.send(
-            unwrap!(allowed_rules(vecrulegroup, hand).choose(&mut rand::thread_rng()))
+            unwrap!(allowed_rules(vecrulegroup, hand).choose(&mut rng::thread_rng()))
         ));
     }
 
@@ -54,11 +54,33 @@ impl<FnCheckAskForCard: Fn(&SGame)> TPlayer for SPlayerRandom<FnCheckAskForCard>
         _doublings: &SDoublings,
         _rules: &dyn TRules,
         _hand: &SHand,
+        _stichseq: &SStichSequence,
+        _ocardexchange: Option<&SCardExchange>,
         _vecstoss: &[SStoss],
+        _n_bock: usize,
         _n_stock: isize,
         txb: mpsc::Sender<bool>,
     ) {
-        unwrap!(txb.send(rand::random()));
+        unwrap!(txb.send(rng::thread_rng().gen()));
+    }
+
+    fn ask_for_schneiderschwarz(
+        &self,
+        _game: &SGame,
+        veceschneiderschwarz: &[ESchneiderSchwarz],
+        txoeschneiderschwarz: mpsc::Sender<Option<ESchneiderSchwarz>>,
+    ) {
+        unwrap!(txoeschneiderschwarz.send(
+            unwrap!(std::iter::once(None)
+                .chain(veceschneiderschwarz.iter().copied().map(Some))
+                .choose(&mut rng::thread_rng()))
+        ));
+    }
+
+    fn ask_for_card_exchange(&self, game: &SGame, txcard: mpsc::Sender<SCard>) {
+        unwrap!(txcard.send(
+            *unwrap!(game.ahand[unwrap!(game.card_exchange_pending())].cards().choose(&mut rng::thread_rng()))
+        ));
     }
 
     fn name(&self) -> &str {
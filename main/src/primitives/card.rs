@@ -1,3 +1,4 @@
+use crate::primitives::eplayerindex::EPlayerIndex;
 use crate::util::*;
 use std::fmt;
 use serde::{Serialize, Deserialize};
@@ -48,6 +49,7 @@ impl fmt::Display for ESchlag {
 plain_enum_mod!{modekurzlang, EKurzLang {
     Kurz,
     Lang,
+    Dreier, // three players get 10 cards of the long deck each, 2 cards remain in the stock and their points count for nobody
 }}
 
 impl EKurzLang {
@@ -55,6 +57,31 @@ impl EKurzLang {
         match self {
             Self::Kurz => 6,
             Self::Lang => 8,
+            Self::Dreier => 10,
+        }
+    }
+
+    pub fn n_players(self) -> usize {
+        match self {
+            Self::Kurz | Self::Lang => EPlayerIndex::SIZE,
+            Self::Dreier => 3,
+        }
+    }
+
+    // players take the seats starting at EPI0, the remaining seats stay empty
+    pub fn players(self) -> impl Iterator<Item=EPlayerIndex> + Clone {
+        EPlayerIndex::values().take(self.n_players())
+    }
+
+    pub fn takes_part(self, epi: EPlayerIndex) -> bool {
+        epi.to_usize() < self.n_players()
+    }
+
+    pub fn cards_dealt_to(self, epi: EPlayerIndex) -> usize {
+        if self.takes_part(epi) {
+            self.cards_per_player()
+        } else {
+            0
         }
     }
 
@@ -66,6 +93,7 @@ impl EKurzLang {
         match n_cards_per_player {
             6 => fn_ok(EKurzLang::Kurz),
             8 => fn_ok(EKurzLang::Lang),
+            10 => fn_ok(EKurzLang::Dreier),
             _ => fn_err(),
         }
     }
@@ -84,7 +112,7 @@ impl EKurzLang {
 
     pub fn supports_card(self, card: SCard) -> bool {
         match self {
-            Self::Lang => true,
+            Self::Lang | Self::Dreier => true,
             Self::Kurz => card.schlag()!=ESchlag::S7 && card.schlag()!=ESchlag::S8,
         }
     }
@@ -151,7 +179,7 @@ impl SCard {
                 EKurzLang::Kurz => if ESchlag::S7==eschlag || ESchlag::S8==eschlag {
                     return None;
                 },
-                EKurzLang::Lang => (),
+                EKurzLang::Lang | EKurzLang::Dreier => (),
             }
             Some(SCard::new(efarbe, eschlag))
         })
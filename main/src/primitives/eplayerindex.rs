@@ -1,9 +1,9 @@
 use crate::util::*;
 use arrayvec::{self, ArrayVec};
 use std::{fmt, ops::Index, slice, str::FromStr};
-use serde_repr::Serialize_repr;
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
-plain_enum_mod!(modepi, derive(Serialize_repr,), map_derive(), EPlayerIndex {
+plain_enum_mod!(modepi, derive(Serialize_repr, Deserialize_repr, Hash,), map_derive(), EPlayerIndex {
     EPI0, EPI1, EPI2, EPI3,
 });
 define_static_value!(pub SStaticEPI0, EPlayerIndex, EPlayerIndex::EPI0);
@@ -29,12 +29,14 @@ impl FromStr for EPlayerIndex {
 #[derive(Clone, Debug)]
 pub struct SPlayersInRound<T, PlayerIndex: TStaticOrDynamicValue<EPlayerIndex>> {
     pub epi_first: PlayerIndex,
+    n_players: usize, // players sit at EPI0, EPI1, ..., remaining seats are skipped
     vect: ArrayVec<T, {EPlayerIndex::SIZE}>,
 }
 
 impl<T: PartialEq, PlayerIndex: TStaticOrDynamicValue<EPlayerIndex>+Copy> PartialEq for SPlayersInRound<T, PlayerIndex> {
     fn eq(&self, playersinround_other: &Self) -> bool {
-        self.size()==playersinround_other.size()
+        self.n_players==playersinround_other.n_players
+        && self.size()==playersinround_other.size()
         && self.equal_up_to_size(playersinround_other, self.size())
     }
 }
@@ -43,13 +45,14 @@ impl<T: Eq, PlayerIndex: TStaticOrDynamicValue<EPlayerIndex>+Copy> Eq for SPlaye
 pub struct SPlayersInRoundIterator<InternalIter> {
     iter: InternalIter,
     n_epi: usize,
+    n_players: usize,
 }
 
 impl<InternalIter: Iterator> Iterator for SPlayersInRoundIterator<InternalIter> {
     type Item = (EPlayerIndex, InternalIter::Item);
     fn next(&mut self) -> Option<(EPlayerIndex, InternalIter::Item)> {
         let item_next = self.iter.next()
-            .map(|t| (EPlayerIndex::wrapped_from_usize(self.n_epi), t));
+            .map(|t| (EPlayerIndex::wrapped_from_usize(self.n_epi % self.n_players), t));
         self.n_epi += 1;
         item_next
     }
@@ -74,14 +77,21 @@ impl<T: PartialEq, PlayerIndex: TStaticOrDynamicValue<EPlayerIndex>+Copy> SPlaye
 
 impl<T, PlayerIndex: TStaticOrDynamicValue<EPlayerIndex>+Copy> SPlayersInRound<T, PlayerIndex> {
     pub fn new(epi_first: PlayerIndex) -> Self {
+        Self::new_with_players(epi_first, EPlayerIndex::SIZE)
+    }
+    pub fn new_with_players(epi_first: PlayerIndex, n_players: usize) -> Self {
+        assert!(n_players<=EPlayerIndex::SIZE);
+        assert!(epi_first.value().to_usize()<n_players);
         SPlayersInRound {
             epi_first,
+            n_players,
             vect: ArrayVec::new(),
         }
     }
     pub fn new_full(epi_first: PlayerIndex, at: [T; EPlayerIndex::SIZE]) -> Self {
         SPlayersInRound {
             epi_first,
+            n_players: EPlayerIndex::SIZE,
             vect: ArrayVec::from(at),
         }
     }
@@ -91,14 +101,17 @@ impl<T, PlayerIndex: TStaticOrDynamicValue<EPlayerIndex>+Copy> SPlayersInRound<T
     pub fn current_playerindex(&self) -> Option<EPlayerIndex> {
         if_then_some!(
             !self.is_full(),
-            self.first_playerindex().wrapping_add(self.size())
+            EPlayerIndex::wrapped_from_usize((self.first_playerindex().to_usize() + self.size()) % self.n_players)
         )
     }
     pub fn size(&self) -> usize {
         self.vect.len()
     }
+    pub fn n_players(&self) -> usize {
+        self.n_players
+    }
     pub fn is_full(&self) -> bool {
-        self.size()==EPlayerIndex::SIZE
+        self.size()==self.n_players
     }
     pub fn is_empty(&self) -> bool {
         self.size()==0
@@ -120,13 +133,14 @@ impl<T, PlayerIndex: TStaticOrDynamicValue<EPlayerIndex>+Copy> SPlayersInRound<T
         SPlayersInRoundIterator {
             iter: self.vect.iter(),
             n_epi: self.epi_first.value().to_usize(),
+            n_players: self.n_players,
         }
     }
     fn position(&self, epi: EPlayerIndex) -> usize {
-        epi.wrapped_difference_usize(self.epi_first.value())
+        (epi.to_usize() + self.n_players - self.epi_first.value().to_usize()) % self.n_players
     }
     fn valid_index(&self, epi: EPlayerIndex) -> bool {
-        self.position(epi)<self.size()
+        epi.to_usize()<self.n_players && self.position(epi)<self.size()
     }
     pub fn get(&self, epi: EPlayerIndex) -> Option<&T> {
         if_then_some!(self.valid_index(epi), &self[epi])
@@ -140,6 +154,7 @@ impl<T, PlayerIndex: TStaticOrDynamicValue<EPlayerIndex>> IntoIterator for SPlay
         SPlayersInRoundIterator {
             iter: self.vect.into_iter(),
             n_epi: self.epi_first.value().to_usize(),
+            n_players: self.n_players,
         }
     }
 }
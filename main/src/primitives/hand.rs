@@ -2,7 +2,7 @@ use crate::primitives::card::*;
 use arrayvec::ArrayVec;
 use std::fmt;
 
-pub type SHandVector = ArrayVec<SCard, 8>;
+pub type SHandVector = ArrayVec<SCard, 10>; // EKurzLang::Dreier deals the most cards per player
 
 #[derive(Clone, Debug)]
 pub struct SHand {
@@ -6,7 +6,7 @@ pub type SStich = SPlayersInRound<SCard, EPlayerIndex>;
 
 impl fmt::Display for SStich {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for epi in EPlayerIndex::values() {
+        for epi in EPlayerIndex::values().take(self.n_players()) {
             if epi==self.epi_first {
                 write!(f, ">")?;
             } else {
@@ -51,4 +51,19 @@ fn test_stich() {
         assert!(stich[EPlayerIndex::EPI3]==SCard::new(EFarbe::Gras, ESchlag::S7));
         assert_eq!(stich.iter().count(), 2);
     }
+    {
+        use crate::card::card_values::*;
+        let mut stich = SStich::new_with_players(EPlayerIndex::EPI1, /*n_players*/3);
+        for card in [E7, E8, E9] {
+            assert!(!stich.is_full());
+            stich.push(card);
+        }
+        assert!(stich.is_full());
+        assert_eq!(
+            stich.iter().map(|(epi, card)| (epi, *card)).collect::<Vec<_>>(),
+            vec![(EPlayerIndex::EPI1, E7), (EPlayerIndex::EPI2, E8), (EPlayerIndex::EPI0, E9)]
+        );
+        assert_eq!(stich.get(EPlayerIndex::EPI3), None);
+        assert_eq!(stich.to_string(), " E9>E7 E8");
+    }
 }
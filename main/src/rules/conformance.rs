@@ -0,0 +1,373 @@
+// Plays random games for all rules offered by the rulesets and checks invariants every TRules implementation must obey.
+
+use crate::game::*;
+use crate::primitives::*;
+use crate::rules::{ruleset::*, *};
+use crate::util::*;
+use itertools::Itertools;
+use rand::{prelude::*, rngs::StdRng};
+use std::{cmp::Ordering, panic::{catch_unwind, AssertUnwindSafe}};
+
+macro_rules! check {
+    ($n_cards_played: expr, $cond: expr, $($arg: tt)*) => {
+        if !$cond {
+            return Err(($n_cards_played, format!($($arg)*)));
+        }
+    };
+}
+
+#[derive(Debug)]
+struct SConformanceCase {
+    rules: Box<dyn TRules>,
+    ahand: EnumMap<EPlayerIndex, SHand>,
+    n_stock: isize,
+    tpln_stoss_doubling: (usize, usize),
+    b_loser_pays_into_stock: bool, // ramsch loser pays into the stock instead of paying the other players
+}
+
+fn str_panic(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload.downcast_ref::<&str>().map(|str_msg| str_msg.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "<unknown panic>".to_owned())
+}
+
+/// Plays `veccard` (possibly only the beginning of a game) and reports the first violated invariant
+/// together with the number of cards played up to then.
+fn check_conformance(case: &SConformanceCase, veccard: &[SCard]) -> Result<(), (usize, String)> {
+    let rules = case.rules.as_ref();
+    let mut n_cards_played = 0;
+    catch_unwind(AssertUnwindSafe(|| {
+        let ekurzlang = EKurzLang::from_cards_per_player(case.ahand[EPlayerIndex::EPI0].cards().len());
+        let mut stichseq = SStichSequence::new(ekurzlang);
+        let mut ahand = case.ahand.explicit_clone();
+        let mut rulestatecache = SRuleStateCache::new(&stichseq, &ahand, |stich| rules.winner_index(stich));
+        let mut mapepipayouthint = rules.payouthints(&stichseq, &ahand, &rulestatecache);
+        let check_allowed_cards = |stichseq: &SStichSequence, ahand: &EnumMap<EPlayerIndex, SHand>, n_cards_played| -> Result<SHandVector, (usize, String)> {
+            let epi = unwrap!(stichseq.current_stich().current_playerindex());
+            let veccard_allowed = rules.all_allowed_cards(stichseq, &ahand[epi]);
+            check!(n_cards_played, !veccard_allowed.is_empty(), "No card allowed for {}", epi);
+            check!(n_cards_played, veccard_allowed.iter().all(|card| ahand[epi].contains(*card)),
+                "Allowed cards {:?} not in hand {}", veccard_allowed, ahand[epi]
+            );
+            Ok(veccard_allowed)
+        };
+        for &card in veccard.iter() {
+            n_cards_played += 1; // including card, so that it is part of the reported sequence
+            let epi = unwrap!(stichseq.current_stich().current_playerindex());
+            check!(n_cards_played, check_allowed_cards(&stichseq, &ahand, n_cards_played)?.contains(&card),
+                "{} played {}, which is not allowed", epi, card
+            );
+            ahand[epi].play_card(card);
+            stichseq.zugeben(card, rules);
+            if stichseq.current_stich().is_empty() {
+                let stich = unwrap!(stichseq.completed_stichs().last());
+                let epi_winner = rules.winner_index(stich);
+                check!(n_cards_played, epi_winner==stichseq.current_stich().first_playerindex(),
+                    "Stich {} was won by {}, but {} plays first afterwards", stich, epi_winner, stichseq.current_stich().first_playerindex()
+                );
+                let card_winner = stich[epi_winner];
+                for (epi_other, card_other) in stich.iter().filter(|&(epi_other, _card)| epi_other!=epi_winner) {
+                    check!(n_cards_played, rules.compare_cards(card_winner, *card_other)!=Some(Ordering::Less),
+                        "Stich {} won by {}, but {} of {} is higher", stich, card_winner, card_other, epi_other
+                    );
+                    check!(n_cards_played, rules.compare_cards(card_winner, *card_other)==rules.compare_cards(*card_other, card_winner).map(Ordering::reverse),
+                        "compare_cards not antisymmetric for {} and {}", card_winner, card_other
+                    );
+                }
+                check!(n_cards_played, epi_winner==stich.first_playerindex() || rules.compare_cards(card_winner, *stich.first())==Some(Ordering::Greater),
+                    "Stich {} won by {}, which does not beat the first card", stich, card_winner
+                );
+                let unregisterstich = rulestatecache.register_stich(stich, epi_winner);
+                rulestatecache.unregister_stich(unregisterstich);
+                rulestatecache.register_stich(stich, epi_winner);
+            }
+            let rulestatecache_recomputed = SRuleStateCache::new(&stichseq, &ahand, |stich| rules.winner_index(stich));
+            check!(n_cards_played, rulestatecache==rulestatecache_recomputed,
+                "Rule state cache {:?} differs from recomputation {:?}", rulestatecache, rulestatecache_recomputed
+            );
+            let mapepipayouthint_after = rules.payouthints(&stichseq, &ahand, &rulestatecache);
+            check!(n_cards_played,
+                mapepipayouthint.iter().zip(mapepipayouthint_after.iter())
+                    .all(|(payouthint, payouthint_after)| payouthint.contains_payouthint(payouthint_after)),
+                "Payout hints {:?} do not contain subsequent payout hints {:?}", mapepipayouthint, mapepipayouthint_after
+            );
+            mapepipayouthint = mapepipayouthint_after;
+        }
+        if stichseq.game_finished() {
+            let gamefinishedstiche = SStichSequenceGameFinished::new(&stichseq);
+            let apayoutinfo = rules.payoutinfos(gamefinishedstiche, &rulestatecache);
+            check!(n_cards_played,
+                mapepipayouthint.iter().zip(apayoutinfo.iter())
+                    .all(|(payouthint, payoutinfo)| payouthint.contains_payouthint(&SPayoutHint::new((Some(payoutinfo.clone()), Some(payoutinfo.clone()))))),
+                "Payout hints {:?} do not contain payout {:?}", mapepipayouthint, apayoutinfo
+            );
+            let an_payout = rules.payout_with_cache(gamefinishedstiche, case.tpln_stoss_doubling, case.n_stock, &rulestatecache);
+            let an_payout_uncached = rules.payout(gamefinishedstiche, case.tpln_stoss_doubling, case.n_stock);
+            check!(n_cards_played, an_payout.iter().eq(an_payout_uncached.iter()),
+                "Payout {:?} differs from uncached payout {:?}", an_payout, an_payout_uncached
+            );
+            let n_stock_after = case.n_stock - an_payout.iter().sum::<isize>();
+            let count_stockaction = |estockaction| apayoutinfo.iter()
+                .filter(|payoutinfo| payoutinfo.estockaction==estockaction)
+                .count()
+                .as_num::<isize>();
+            let n_paid_into_stock_by_loser = if case.b_loser_pays_into_stock && an_payout.iter().all(|n_payout| *n_payout<=0) {
+                check!(n_cards_played, an_payout.iter().filter(|n_payout| **n_payout<0).count()==1,
+                    "Payout {:?} into stock not paid by a single loser", an_payout
+                );
+                -an_payout.iter().sum::<isize>()
+            } else {
+                0
+            };
+            let n_stock_after_expected = case.n_stock
+                + n_paid_into_stock_by_loser
+                + (count_stockaction(EStockAction::GiveHalf) - count_stockaction(EStockAction::TakeHalf)) * (case.n_stock/2);
+            check!(n_cards_played, n_stock_after==n_stock_after_expected && 0<=n_stock_after,
+                "Payout {:?} changes stock from {} to {}, expected {}", an_payout, case.n_stock, n_stock_after, n_stock_after_expected
+            );
+        } else {
+            check_allowed_cards(&stichseq, &ahand, n_cards_played)?;
+        }
+        Ok(())
+    }))
+        .unwrap_or_else(|payload| Err((n_cards_played, format!("Panic: {}", str_panic(payload)))))
+}
+
+/// Continues the game given by `veccard_prefix`, choosing cards via `fn_choose` (given the hand and the allowed cards).
+/// Stops early if the rules do not allow any card.
+fn complete_game(case: &SConformanceCase, veccard_prefix: &[SCard], mut fn_choose: impl FnMut(&SHand, &SHandVector) -> SCard) -> Vec<SCard> {
+    let rules = case.rules.as_ref();
+    let ekurzlang = EKurzLang::from_cards_per_player(case.ahand[EPlayerIndex::EPI0].cards().len());
+    let mut stichseq = SStichSequence::new(ekurzlang);
+    let mut ahand = case.ahand.explicit_clone();
+    let mut veccard = veccard_prefix.to_vec();
+    for &card in veccard_prefix.iter() {
+        ahand[unwrap!(stichseq.current_stich().current_playerindex())].play_card(card);
+        stichseq.zugeben(card, rules);
+    }
+    while !stichseq.game_finished() {
+        let epi = unwrap!(stichseq.current_stich().current_playerindex());
+        match catch_unwind(AssertUnwindSafe(|| rules.all_allowed_cards(&stichseq, &ahand[epi]))) {
+            Ok(veccard_allowed) if !veccard_allowed.is_empty() => {
+                let card = fn_choose(&ahand[epi], &veccard_allowed);
+                ahand[epi].play_card(card);
+                stichseq.zugeben(card, rules);
+                veccard.push(card);
+            },
+            Ok(_) | Err(_) => break,
+        }
+    }
+    veccard
+}
+
+/// The cards allowed before each card of `veccard` (empty if the rules panic).
+fn allowed_cards_per_card(case: &SConformanceCase, veccard: &[SCard]) -> Vec<SHandVector> {
+    let rules = case.rules.as_ref();
+    let mut stichseq = SStichSequence::new(EKurzLang::from_cards_per_player(case.ahand[EPlayerIndex::EPI0].cards().len()));
+    let mut ahand = case.ahand.explicit_clone();
+    veccard.iter().map(|&card| {
+        let epi = unwrap!(stichseq.current_stich().current_playerindex());
+        let veccard_allowed = catch_unwind(AssertUnwindSafe(|| rules.all_allowed_cards(&stichseq, &ahand[epi])))
+            .unwrap_or_default();
+        ahand[epi].play_card(card);
+        stichseq.zugeben(card, rules);
+        veccard_allowed
+    }).collect()
+}
+
+/// Simplifies a failing game as long as it still fails:
+/// Resets `n_stock` and `tpln_stoss_doubling` to 0 and retries each card choice with the other allowed cards,
+/// keeping the subsequent cards where possible and playing the first allowed card otherwise.
+/// A change is only kept if it makes the game shorter or chooses earlier allowed cards, so that shrinking terminates.
+/// Returns the failing cards up to the violation.
+fn shrink(case: &mut SConformanceCase, veccard: &[SCard]) -> (Vec<SCard>, String) {
+    fn failure(case: &SConformanceCase, veccard: Vec<SCard>) -> Option<(Vec<SCard>, String)> {
+        check_conformance(case, &veccard).err()
+            .map(|(n_cards_played, str_err)| (veccard[..n_cards_played].to_vec(), str_err))
+    }
+    fn rank(case: &SConformanceCase, veccard: &[SCard]) -> (usize, Vec<usize>) {
+        (
+            veccard.len(),
+            veccard.iter().zip(allowed_cards_per_card(case, veccard))
+                .map(|(card, veccard_allowed)| veccard_allowed.iter().position(|card_allowed| card_allowed==card).unwrap_or(veccard_allowed.len()))
+                .collect(),
+        )
+    }
+    let (mut veccard, mut str_err) = unwrap!(failure(case, veccard.to_vec()));
+    loop {
+        let (n_stock, tpln_stoss_doubling) = (case.n_stock, case.tpln_stoss_doubling);
+        for fn_reset in [
+            (|case: &mut SConformanceCase| case.n_stock = 0) as fn(&mut SConformanceCase),
+            |case| case.tpln_stoss_doubling.0 = 0,
+            |case| case.tpln_stoss_doubling.1 = 0,
+        ] {
+            let (n_stock_prev, tpln_stoss_doubling_prev) = (case.n_stock, case.tpln_stoss_doubling);
+            fn_reset(case);
+            if let Some((veccard_candidate, str_err_candidate)) = failure(case, veccard.clone()) {
+                veccard = veccard_candidate;
+                str_err = str_err_candidate;
+            } else {
+                case.n_stock = n_stock_prev;
+                case.tpln_stoss_doubling = tpln_stoss_doubling_prev;
+            }
+        }
+        let rank_current = rank(case, &veccard);
+        let ofailure_card = allowed_cards_per_card(case, &veccard).iter().enumerate()
+            .flat_map(|(i_card, veccard_allowed)| veccard_allowed.iter()
+                .filter(|card_allowed| **card_allowed!=veccard[i_card])
+                .map(move |card_allowed| (i_card, *card_allowed))
+                .collect::<Vec<_>>()
+            )
+            .filter_map(|(i_card, card_allowed)| {
+                let mut itcard_subsequent = veccard[i_card+1..].iter().copied();
+                let veccard_candidate = complete_game(
+                    case,
+                    &veccard[..i_card].iter().copied().chain(std::iter::once(card_allowed)).collect::<Vec<_>>(),
+                    |hand, veccard_allowed| itcard_subsequent.next()
+                        .filter(|card| hand.contains(*card))
+                        .unwrap_or(veccard_allowed[0]),
+                );
+                failure(case, veccard_candidate)
+            })
+            .find(|(veccard_candidate, _str_err)| rank(case, veccard_candidate) < rank_current);
+        let b_shrunk = (n_stock, tpln_stoss_doubling)!=(case.n_stock, case.tpln_stoss_doubling) || ofailure_card.is_some();
+        if let Some((veccard_candidate, str_err_candidate)) = ofailure_card {
+            veccard = veccard_candidate;
+            str_err = str_err_candidate;
+        }
+        if !b_shrunk {
+            return (veccard, str_err);
+        }
+    }
+}
+
+fn all_rules(ruleset: &SRuleSet) -> Vec<Box<dyn TRules>> {
+    let mut vecrules : Vec<Box<dyn TRules>> = ruleset.ekurzlang.players()
+        .flat_map(|epi| ruleset.announceable_rules(epi))
+        .collect();
+    let vecrules_schneiderschwarz = vecrules.iter()
+        .flat_map(|rules| ESchneiderSchwarz::values().filter_map(move |eschneiderschwarz| rules.with_schneiderschwarz_announced(eschneiderschwarz)))
+        .collect::<Vec<_>>();
+    vecrules.extend(vecrules_schneiderschwarz);
+    vecrules
+}
+
+/// Deals until the rules can be played (e.g. Hochzeit requires a single trumpf).
+fn random_case(ekurzlang: EKurzLang, rng: &mut StdRng, rules: Box<dyn TRules>, b_loser_pays_into_stock: bool) -> SConformanceCase {
+    SConformanceCase{
+        ahand: random_ahand_playable(rules.as_ref(), ekurzlang, rng),
+        rules,
+        n_stock: 0,
+        tpln_stoss_doubling: (0, 0),
+        b_loser_pays_into_stock,
+    }
+}
+
+type VecFnCase<'ruleset> = Vec<Box<dyn Fn(&mut StdRng) -> SConformanceCase + 'ruleset>>;
+
+fn check_random_games(ruleset: &SRuleSet, n_games_per_rules: usize, rng: &mut StdRng) {
+    let mut vecfn_case : VecFnCase = Vec::new();
+    for rules in all_rules(ruleset) {
+        vecfn_case.push(Box::new(move |rng| {
+            random_case(ruleset.ekurzlang, rng, rules.box_clone(), /*b_loser_pays_into_stock*/false)
+        }));
+    }
+    if let Some(ref fallbackrules) = ruleset.ofallbackrules {
+        vecfn_case.push(Box::new(move |rng| {
+            let b_loser_pays_into_stock = matches!(fallbackrules, VFallbackRules::Ramsch(rulesramsch) if rulesramsch.loser_pays_into_stock());
+            match fallbackrules.rules() {
+                Some(rules) => random_case(ruleset.ekurzlang, rng, rules, b_loser_pays_into_stock),
+                None => {
+                    let ahand = random_ahand(ruleset.ekurzlang, rng);
+                    let (_epi_alter, rulegroup) = unwrap!(fallbackrules.der_alte_muss(&ahand));
+                    SConformanceCase{
+                        rules: unwrap!(unwrap!(rulegroup.vecorules.choose(rng)).as_ref()).upcast().box_clone(), // der Alte can play all of them
+                        ahand,
+                        n_stock: 0,
+                        tpln_stoss_doubling: (0, 0),
+                        b_loser_pays_into_stock,
+                    }
+                },
+            }
+        }));
+    }
+    for fn_case in vecfn_case.iter() {
+        for _i_game in 0..n_games_per_rules {
+            let mut case = fn_case(rng);
+            case.n_stock = 2*rng.gen_range(0..25); // stock is always even
+            case.tpln_stoss_doubling = (rng.gen_range(0..3), rng.gen_range(0..3));
+            let veccard = complete_game(&case, /*veccard_prefix*/&[], |_hand, veccard_allowed| *unwrap!(veccard_allowed.choose(rng)));
+            if check_conformance(&case, &veccard).is_err() {
+                let (veccard_shrunk, str_err) = shrink(&mut case, &veccard);
+                panic!("{}\nrules: {}\nahand: {}\nn_stock: {}, tpln_stoss_doubling: {:?}\ncards: {}",
+                    str_err,
+                    case.rules,
+                    case.ahand.iter().join(" | "),
+                    case.n_stock,
+                    case.tpln_stoss_doubling,
+                    veccard_shrunk.iter().join(" "),
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rules_conformance() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for str_ruleset in [
+        include_str!("../../../rulesets/default.toml"),
+        include_str!("../../../rulesets/bettel.toml"),
+        include_str!("../../../rulesets/bettel_only.toml"),
+        include_str!("../../../rulesets/doubling_stock.toml"),
+        include_str!("../../../rulesets/dreier.toml"),
+        include_str!("../../../rulesets/force_stock.toml"),
+        include_str!("../../../rulesets/only_ramsch.toml"),
+        include_str!("../../../rulesets/only_rufspiel.toml"),
+        "base-price=10\nsolo-price=50\nlauf-min=3\ndeck=\"kurz\"\n[solo]\n[wenz]\n[geier]\n[bettel]\n[ramsch]\nprice=10\njungfrau=\"each\"",
+        "base-price=10\nsolo-price=50\nlauf-min=3\n[rufspiel]\n[hochzeit]\n[kreuzspiel]",
+        "base-price=10\nsolo-price=50\nlauf-min=3\n[solo]\nannounce-schneider-schwarz=true\n[der-alte-muss]",
+        "base-price=10\nsolo-price=50\nlauf-min=3\n[rufspiel]\n[solo]\n[ramsch]\nprice=10\nloser-pays-into-stock=true\n[stock]",
+    ] {
+        let ruleset = unwrap!(SRuleSet::from_string(str_ruleset));
+        check_random_games(&ruleset, /*n_games_per_rules*/if_dbg_else!({5}{100}), &mut rng);
+    }
+}
+
+#[test]
+fn test_shrink() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let ruleset = unwrap!(SRuleSet::from_string(include_str!("../../../rulesets/default.toml")));
+    for rules in all_rules(&ruleset) {
+        let mut case = random_case(ruleset.ekurzlang, &mut rng, rules, /*b_loser_pays_into_stock*/false);
+        case.n_stock = 20;
+        case.tpln_stoss_doubling = (1, 2);
+        let veccard_valid = complete_game(&case, /*veccard_prefix*/&[], |_hand, veccard_allowed| *unwrap!(veccard_allowed.choose(&mut rng)));
+        assert!(check_conformance(&case, &veccard_valid).is_ok());
+        // replace the first card that could have been chosen differently by a forbidden one
+        let mut stichseq = SStichSequence::new(ruleset.ekurzlang);
+        let mut ahand = case.ahand.explicit_clone();
+        let oveccard_invalid = veccard_valid.iter().enumerate().find_map(|(i_card, &card)| {
+            let epi = unwrap!(stichseq.current_stich().current_playerindex());
+            let veccard_allowed = case.rules.all_allowed_cards(&stichseq, &ahand[epi]);
+            let ocard_forbidden = ahand[epi].cards().iter().copied().find(|card_hand| !veccard_allowed.contains(card_hand));
+            ahand[epi].play_card(card);
+            stichseq.zugeben(card, case.rules.as_ref());
+            ocard_forbidden.map(|card_forbidden| {
+                let mut veccard_invalid = veccard_valid.clone();
+                veccard_invalid[i_card] = card_forbidden; // subsequent cards are inconsistent, but never replayed
+                (i_card, veccard_invalid)
+            })
+        });
+        if let Some((i_card_forbidden, veccard_invalid)) = oveccard_invalid {
+            let (veccard_shrunk, str_err) = shrink(&mut case, &veccard_invalid);
+            assert!(veccard_shrunk.len()<=i_card_forbidden+1);
+            assert!(str_err.contains("not allowed"), "{}", str_err);
+            assert_eq!(check_conformance(&case, &veccard_shrunk), Err((veccard_shrunk.len(), str_err)));
+            assert_eq!((case.n_stock, case.tpln_stoss_doubling), (0, (0, 0))); // irrelevant for forbidden cards
+            if i_card_forbidden==0 {
+                assert_eq!(veccard_shrunk, veccard_invalid[..1].to_vec());
+            }
+        }
+    }
+}
@@ -3,15 +3,18 @@ pub mod trumpfdecider;
 #[macro_use]
 pub mod singleplay;
 pub mod rulesrufspiel;
-// TODORULES implement Hochzeit
 pub mod card_points;
 pub mod parser;
 pub mod payoutdecider;
 pub mod rulesbettel;
+pub mod ruleshochzeit;
+pub mod ruleskreuzspiel;
 pub mod ruleset;
 pub mod rulesramsch;
 pub mod rulessolo;
 
+#[cfg(test)]
+mod conformance;
 #[cfg(test)]
 pub mod tests;
 
@@ -21,6 +24,7 @@ use crate::game::SStichSequence;
 use crate::primitives::*;
 use crate::rules::card_points::points_stich;
 use crate::util::*;
+use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, fmt};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -38,9 +42,21 @@ impl VTrumpfOrFarbe {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SStoss {
     pub epi : EPlayerIndex,
+    pub n_cards_played : usize, // when the Stoss was given
+}
+
+plain_enum_mod!(modeschneiderschwarz, derive(Serialize, Deserialize,), map_derive(), ESchneiderSchwarz {
+    Schneider,
+    Schwarz,
+});
+
+impl fmt::Display for ESchneiderSchwarz {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
 }
 
 fn all_allowed_cards_within_stich_distinguish_farbe_frei (
@@ -74,6 +90,7 @@ pub struct SPayoutInfo {
 }
 
 impl SPayoutInfo {
+    // tpln_stoss_doubling.1 includes active Bocks (see game::stoss_and_doublings)
     pub fn payout_including_stock(&self, n_stock: isize, tpln_stoss_doubling: (usize, usize)) -> isize {
         assert_eq!(n_stock%2, 0);
         assert!(self.estockaction!=EStockAction::TakeHalf || 0<self.n_payout);
@@ -92,7 +109,7 @@ pub struct SPayoutHint {
 }
 
 impl SPayoutHint {
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, test))]
     fn contains_payouthint(&self, payouthint_other: &SPayoutHint) -> bool {
         (match (&self.tpln_payout.0, &payouthint_other.tpln_payout.0) {
             (None, _) => true,
@@ -110,6 +127,10 @@ impl SPayoutHint {
     pub fn lower_bound(&self) -> &Option<SPayoutInfo> {
         &self.tpln_payout.0
     }
+
+    pub fn upper_bound(&self) -> &Option<SPayoutInfo> {
+        &self.tpln_payout.1
+    }
 }
 
 pub trait TPlayerParties {
@@ -117,9 +138,9 @@ pub trait TPlayerParties {
     fn multiplier(&self, epi: EPlayerIndex) -> isize;
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 pub struct SRuleStateCacheFixed {
-    mapcardoepi: EnumMap<SCard, Option<EPlayerIndex>>, // TODO? Option<EPlayerIndex> is clean for EKurzLang. Does it incur runtime overhead?
+    mapcardoepi: EnumMap<SCard, Option<EPlayerIndex>>, // None for cards not in the deck or in the stock // TODO? does Option<EPlayerIndex> incur runtime overhead?
 }
 impl SRuleStateCacheFixed {
     fn new(stichseq: &SStichSequence, ahand: &EnumMap<EPlayerIndex, SHand>) -> Self {
@@ -138,25 +159,28 @@ impl SRuleStateCacheFixed {
             }
         }
         assert!(EPlayerIndex::values().all(|epi| {
-            mapcardoepi.iter().filter_map(|&oepi_card| oepi_card).filter(|epi_card| *epi_card==epi).count()==stichseq.kurzlang().cards_per_player()
+            mapcardoepi.iter().filter_map(|&oepi_card| oepi_card).filter(|epi_card| *epi_card==epi).count()==stichseq.kurzlang().cards_dealt_to(epi)
         }));
-        assert!(SCard::values(stichseq.kurzlang()).all(|card| mapcardoepi[card].is_some()));
+        assert_eq!(
+            mapcardoepi.iter().filter(|oepi_card| oepi_card.is_some()).count(),
+            stichseq.kurzlang().n_players() * stichseq.kurzlang().cards_per_player(),
+        );
         Self {mapcardoepi}
     }
-    fn who_has_card(&self, card: SCard) -> EPlayerIndex {
-        unwrap!(self.mapcardoepi[card])
+    fn who_has_card(&self, card: SCard) -> Option<EPlayerIndex> { // None for cards in the stock
+        self.mapcardoepi[card]
     }
 }
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 pub struct SPointStichCount {
     pub n_stich: usize,
     pub n_point: isize,
 }
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 pub struct SRuleStateCacheChanging {
     pub mapepipointstichcount: EnumMap<EPlayerIndex, SPointStichCount>,
 }
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
 pub struct SRuleStateCache { // TODO should we have a cache typer per rules? (Would possibly forbid having TRules trait objects.)
     pub fixed: SRuleStateCacheFixed,
     pub changing: SRuleStateCacheChanging,
@@ -218,6 +242,7 @@ impl SRuleStateCache {
 #[derive(new)]
 pub struct SPlayerParties13 {
     epi: EPlayerIndex,
+    ekurzlang: EKurzLang,
 }
 
 impl SPlayerParties13 {
@@ -231,10 +256,41 @@ impl TPlayerParties for SPlayerParties13 {
         self.epi==epi
     }
     fn multiplier(&self, epi: EPlayerIndex) -> isize {
-        if self.is_primary_party(epi) {3} else {1}
+        if self.is_primary_party(epi) {
+            (self.ekurzlang.n_players()-1).as_num::<isize>() // single player plays against all others
+        } else if self.ekurzlang.takes_part(epi) {
+            1
+        } else {
+            0
+        }
     }
 }
 
+/// Parameter of rules that is not implied by their name (see `parser::SRuleDescription`).
+#[derive(Clone, Debug)]
+pub enum VRuleParam {
+    LaufMin(usize),
+    LaufPrice(isize),
+    Priority(VGameAnnouncementPriority),
+    AnnounceSchneiderSchwarzPrice(isize),
+    SchneiderSchwarzAnnounced(ESchneiderSchwarz),
+    ToutSieLaufende(bool),
+    ToutSieSchneiderSchwarz(bool),
+    Stichzwang(bool),
+    Durchmarsch(rulesramsch::VDurchmarsch),
+    Jungfrau(rulesramsch::EJungfrau),
+    Schieben(bool),
+    LoserPaysIntoStock(bool),
+}
+
+/// Tariffs and parameters that determine rules together with their name and active player.
+#[derive(Clone, Debug)]
+pub struct SRuleParams {
+    pub on_payout_extra : Option<isize>, // None if the rules do not pay extra (schneider/schwarz, laufende)
+    pub n_payout_base : isize,
+    pub vecruleparam : Vec<VRuleParam>,
+}
+
 pub trait TRulesNoObj : TRules {
     type TrumpfDecider: trumpfdecider::TTrumpfDecider;
 }
@@ -252,6 +308,29 @@ pub trait TRules : fmt::Display + TAsRules + Sync + fmt::Debug + TRulesBoxClone
 
     fn stoss_allowed(&self, epi: EPlayerIndex, vecstoss: &[SStoss], hand: &SHand) -> bool;
 
+    fn revealed_hand_playerindex(&self, _stichseq: &SStichSequence) -> Option<EPlayerIndex> {
+        None // probably, only Bettel Brett shows a hand to the other players
+    }
+
+    fn with_schneiderschwarz_announced(&self, _eschneiderschwarz: ESchneiderSchwarz) -> Option<Box<dyn TRules>> {
+        None // only some sololike games support announcing schneider/schwarz
+    }
+
+    fn ruleparams(&self) -> SRuleParams;
+
+    // None if the players do not form parties (e.g. Ramsch)
+    fn finished_playerparties(&self, gamefinishedstiche: SStichSequenceGameFinished) -> Option<Box<dyn TPlayerParties>>;
+
+    // (n_stich, epi_active, epi_partner) if the completed stichs oblige epi_active to hand his trumpf
+    // to epi_partner in exchange for another card, after the first n_stich stichs
+    fn card_exchange(&self, _stichseq: &SStichSequence) -> Option<(usize, EPlayerIndex, EPlayerIndex)> {
+        None // probably, only Hochzeit exchanges cards during the game
+    }
+
+    fn durchmarsch(&self, _gamefinishedstiche: SStichSequenceGameFinished) -> bool {
+        false // probably, only Ramsch can be won by durchmarsch
+    }
+
     fn payout(&self, gamefinishedstiche: SStichSequenceGameFinished, tpln_stoss_doubling: (usize, usize), n_stock: isize) -> EnumMap<EPlayerIndex, isize> {
         self.payout_with_cache(
             gamefinishedstiche,
@@ -281,7 +360,7 @@ pub trait TRules : fmt::Display + TAsRules + Sync + fmt::Debug + TRulesBoxClone
             let mut mapepipayouthint = EPlayerIndex::map_from_fn(|_epi| SPayoutHint::new((None, None)));
             let mut stichseq_check = SStichSequence::new(gamefinishedstiche.get().kurzlang());
             let mut ahand_check = EPlayerIndex::map_from_fn(|epi|
-                SHand::new_from_iter(gamefinishedstiche.get().completed_stichs().iter().map(|stich| stich[epi]))
+                SHand::new_from_iter(gamefinishedstiche.get().completed_stichs().iter().filter_map(|stich| stich.get(epi).copied()))
             );
             for stich in gamefinishedstiche.get().completed_stichs().iter() {
                 for (epi, card) in stich.iter() {
@@ -379,6 +458,20 @@ pub trait TRules : fmt::Display + TAsRules + Sync + fmt::Debug + TRulesBoxClone
         });
     }
 
+    // explore_snapshots may reuse outputs of snapshots that agree in remaining hands, SRuleStateCache and this value
+    fn snapshot_cache_discriminator(&self, _stichseq: &SStichSequence) -> usize {
+        // payouts depend on who won which stichs (recorded in SRuleStateCache), and allowed cards only on the current stich;
+        // rules where earlier stichs decide about parties or allowed cards must override this
+        0
+    }
+
+    // card_fst and card_snd belong to the same player, have the same trumpforfarbe, and no other card in play lies between them
+    fn cards_interchangeable_if_adjacent(&self, _card_fst: SCard, _card_snd: SCard) -> bool {
+        // such cards beat and are beaten by the same cards, so they win the same stichs;
+        // their points may differ, but equivalent_cards_filter keeps the cards with the least and the most points
+        true
+    }
+
     fn rulespecific_ai<'rules>(&'rules self) -> Option<Box<dyn TRuleSpecificAI + 'rules>> {
         None
     }
@@ -1,122 +1,620 @@
-use crate::game_analysis::*;
 use crate::primitives::*;
-use crate::rules::*;
+use crate::rules::{
+    payoutdecider::*, rulesbettel::*, ruleshochzeit::*, ruleskreuzspiel::*, rulesramsch::*, rulesrufspiel::*, rulessolo::*, *,
+};
 use crate::util::*;
+use std::fmt;
+use combine::{char::*, error::StreamError, stream::{state::State, StreamErrorFor}, *};
 use itertools::Itertools;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ESoloLikePayout {
+    PointBased,
+    Tout,
+    Sie,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VRuleDescription {
+    Rufspiel(EFarbe),
+    Hochzeit,
+    SoloLike(Option<EFarbe>, ESoloLike, ESoloLikePayout),
+    Bettel{b_brett: bool},
+    Ramsch,
+    Kreuzspiel,
+}
+
+fn keyword_parser<I: Stream<Item=char>>(str_keyword: &'static str) -> impl Parser<Input = I, Output = &'static str>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    fn eq_ignore_case(chr_lhs: char, chr_rhs: char) -> bool {
+        chr_lhs.to_lowercase().eq(chr_rhs.to_lowercase())
+    }
+    attempt(string_cmp(str_keyword, eq_ignore_case as fn(char, char) -> bool))
+}
+
+macro_rules! keywords_parser {($($str_keyword: expr),+) => {
+    // longer keywords must precede their prefixes
+    choice([$(keyword_parser($str_keyword)),+])
+}}
+
+fn separator_parser<I: Stream<Item=char>>() -> impl Parser<Input = I, Output = ()>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    skip_many(satisfy(|chr: char| chr.is_whitespace() || '-'==chr))
+}
+
+fn farbe_description_parser<I: Stream<Item=char>>() -> impl Parser<Input = I, Output = EFarbe>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    choice!(
+        keywords_parser!("eichel", "alten", "alte", "alt").map(|_str| EFarbe::Eichel),
+        keywords_parser!("gras", "grüne", "grün", "laub", "blatt", "blaue", "blau").map(|_str| EFarbe::Gras),
+        keywords_parser!("herz", "rote", "rot").map(|_str| EFarbe::Herz),
+        keywords_parser!("schelln", "schelle", "pumpe", "pump", "hundsgfickte").map(|_str| EFarbe::Schelln)
+    )
+}
+
+fn rule_description_parser<I: Stream<Item=char>>() -> impl Parser<Input = I, Output = VRuleDescription>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    choice!(
+        (
+            keywords_parser!("rufspiel", "sauspiel"),
+            separator_parser(),
+            optional(attempt((
+                keywords_parser!("mit", "auf"),
+                separator_parser(),
+                keywords_parser!("der", "die", "den"),
+                separator_parser(),
+            ))),
+            farbe_description_parser(),
+            optional(attempt((separator_parser(), keyword_parser("sau")))),
+        ).and_then(|(_str_rufspiel, (), _otpl_article, efarbe, _otpl_sau)| match efarbe {
+            EFarbe::Herz => Err(StreamErrorFor::<I>::message_static_message("Rufspiel incompatible with Herz")),
+            EFarbe::Eichel | EFarbe::Gras | EFarbe::Schelln => Ok(VRuleDescription::Rufspiel(efarbe)),
+        }),
+        keyword_parser("hochzeit").map(|_str| VRuleDescription::Hochzeit),
+        (
+            keyword_parser("bettel"),
+            optional(attempt(separator_parser().with(keywords_parser!("brett", "ouvert")))),
+        ).map(|(_str_bettel, ostr_brett)| VRuleDescription::Bettel{b_brett: ostr_brett.is_some()}),
+        keyword_parser("ramsch").map(|_str| VRuleDescription::Ramsch),
+        keyword_parser("kreuzspiel").map(|_str| VRuleDescription::Kreuzspiel),
+        (
+            optional(attempt(farbe_description_parser().skip(separator_parser()))),
+            choice!(
+                keywords_parser!("solo", "sticht").map(|_str| (ESoloLike::Solo, /*b_farb*/false)),
+                keyword_parser("farbwenz").map(|_str| (ESoloLike::Wenz, /*b_farb*/true)),
+                keyword_parser("wenz").map(|_str| (ESoloLike::Wenz, /*b_farb*/false)),
+                keyword_parser("farbgeier").map(|_str| (ESoloLike::Geier, /*b_farb*/true)),
+                keyword_parser("geier").map(|_str| (ESoloLike::Geier, /*b_farb*/false))
+            ),
+            optional(attempt(separator_parser().with(choice!(
+                keyword_parser("tout").map(|_str| ESoloLikePayout::Tout),
+                keyword_parser("sie").map(|_str| ESoloLikePayout::Sie)
+            )))),
+        ).and_then(|(oefarbe, (esololike, b_farb), oesololikepayout)| {
+            if b_farb && oefarbe.is_none() {
+                Err(StreamErrorFor::<I>::message_static_message("Farbwenz and Farbgeier require a farbe"))
+            } else {
+                Ok(VRuleDescription::SoloLike(
+                    oefarbe,
+                    esololike,
+                    oesololikepayout.unwrap_or(ESoloLikePayout::PointBased),
+                ))
+            }
+        })
+    )
+}
+
+fn unsigned_parser<I: Stream<Item=char>, N: std::str::FromStr>() -> impl Parser<Input = I, Output = N>
+    where
+        I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+        N::Err: fmt::Display,
+{
+    from_str(many1::<String, _>(digit()))
+}
+
+fn signed_parser<I: Stream<Item=char>>() -> impl Parser<Input = I, Output = isize>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    from_str(
+        (optional(char('-')), many1::<String, _>(digit()))
+            .map(|(ochr_minus, str_digits)| format!("{}{}", ochr_minus.map_or("", |_chr| "-"), str_digits))
+    )
+}
+
+fn bool_parser<I: Stream<Item=char>>() -> impl Parser<Input = I, Output = bool>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    choice!(
+        keyword_parser("true").map(|_str| true),
+        keyword_parser("false").map(|_str| false)
+    )
+}
+
+fn tarif_parser<I: Stream<Item=char>>() -> impl Parser<Input = I, Output = (Option<isize>, isize, Option<isize>)>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    // same order as on sauspiel.de: extra / rufspiel / solo (solo defaults to rufspiel)
+    // a single number is the price of games without extra payments (e.g. Ramsch, Bettel)
+    (
+        char('(').skip(spaces()),
+        sep_by1::<Vec<_>, _, _>(unsigned_parser::<_, isize>().skip(spaces()), char('/').skip(spaces())),
+        char(')'),
+    ).and_then(|(_chr_open, vecn_tarif, _chr_close)| match *vecn_tarif.as_slice() {
+        [n_tarif_ruf] => Ok((None, n_tarif_ruf, None)),
+        [n_tarif_extra, n_tarif_ruf] => Ok((Some(n_tarif_extra), n_tarif_ruf, None)),
+        [n_tarif_extra, n_tarif_ruf, n_tarif_solo] => Ok((Some(n_tarif_extra), n_tarif_ruf, Some(n_tarif_solo))),
+        _ => Err(StreamErrorFor::<I>::message_static_message("At most three tariffs (extra/rufspiel/solo) supported")),
+    })
+}
+
+fn priority_parser<I: Stream<Item=char>>() -> impl Parser<Input = I, Output = VGameAnnouncementPriority>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    choice!(
+        keyword_parser("rufspiel").map(|_str| VGameAnnouncementPriority::RufspielLike),
+        attempt(keyword_parser("steigern").skip(char(':'))).with((signed_parser(), char(':'), signed_parser()))
+            .map(|(n_points_to_win, _chr_colon, n_step)| VGameAnnouncementPriority::SoloLike(
+                VGameAnnouncementPrioritySoloLike::SoloSteigern{n_points_to_win, n_step}
+            )),
+        attempt(keyword_parser("tout").skip(char(':'))).with(signed_parser())
+            .map(VGameAnnouncementPriority::SoloTout),
+        keyword_parser("sie").map(|_str| VGameAnnouncementPriority::SoloSie),
+        signed_parser().map(|i_prio| VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(i_prio)))
+    )
+}
+
+fn ruleparam_parser<I: Stream<Item=char>>() -> impl Parser<Input = I, Output = VRuleParam>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    macro_rules! ruleparam {($str_key: expr, $parser_value: expr, $fn_ruleparam: expr) => {
+        attempt((keyword_parser($str_key), spaces(), char('='), spaces()))
+            .with($parser_value)
+            .map($fn_ruleparam)
+    }}
+    // same keys as in rulesets
+    choice!(
+        ruleparam!("lauf-min", unsigned_parser(), VRuleParam::LaufMin),
+        ruleparam!("lauf-price", unsigned_parser(), VRuleParam::LaufPrice),
+        ruleparam!("priority", priority_parser(), VRuleParam::Priority),
+        ruleparam!("announce-schneider-schwarz-price", unsigned_parser(), VRuleParam::AnnounceSchneiderSchwarzPrice),
+        ruleparam!(
+            "announced",
+            choice!(
+                keyword_parser("schneider").map(|_str| ESchneiderSchwarz::Schneider),
+                keyword_parser("schwarz").map(|_str| ESchneiderSchwarz::Schwarz)
+            ),
+            VRuleParam::SchneiderSchwarzAnnounced
+        ),
+        ruleparam!("tout-sie-laufende", bool_parser(), VRuleParam::ToutSieLaufende),
+        ruleparam!("tout-sie-schneider-schwarz", bool_parser(), VRuleParam::ToutSieSchneiderSchwarz),
+        ruleparam!("stichzwang", bool_parser(), VRuleParam::Stichzwang),
+        ruleparam!(
+            "durchmarsch",
+            choice!(
+                keyword_parser("none").map(|_str| VDurchmarsch::None),
+                keyword_parser("all").map(|_str| VDurchmarsch::All),
+                unsigned_parser().map(VDurchmarsch::AtLeast)
+            ),
+            VRuleParam::Durchmarsch
+        ),
+        ruleparam!(
+            "jungfrau",
+            choice!(
+                keyword_parser("ignore").map(|_str| EJungfrau::Ignore),
+                keyword_parser("once").map(|_str| EJungfrau::DoubleOnce),
+                keyword_parser("each").map(|_str| EJungfrau::DoubleEach)
+            ),
+            VRuleParam::Jungfrau
+        ),
+        ruleparam!("schieben", bool_parser(), VRuleParam::Schieben),
+        ruleparam!("loser-pays-into-stock", bool_parser(), VRuleParam::LoserPaysIntoStock)
+    )
+}
+
+fn player_parser<I: Stream<Item=char>>() -> impl Parser<Input = I, Output = String>
+    where I::Error: ParseError<I::Item, I::Range, I::Position>, // Necessary due to rust-lang/rust#24159
+{
+    many1(satisfy(|chr: char| !chr.is_whitespace() && '('!=chr && '['!=chr))
+}
+
+/// Parses descriptions like "Rufspiel mit der Eichel-Sau von 2", "Gras-Wenz Tout von 0 (10/20/50)",
+/// "Wenz von 1 (10/50) [lauf-min=2]" or "Ramsch (20) [durchmarsch=all, jungfrau=once]".
+/// Parameters not given in the description default to the rules played on sauspiel.de.
+/// The inverse is given by SRuleDescription.
 pub fn parse_rule_description(
     str_rules_with_player: &str,
     (n_tarif_extra, n_tarif_ruf, n_tarif_solo): (isize, isize, isize),
     fn_player_to_epi: impl FnOnce(&str)->Result<EPlayerIndex, Error>,
 ) -> Result<Box<dyn TRules>, Error> {
-    use crate::rules::rulesrufspiel::*;
-    use crate::rules::rulessolo::*;
-    use crate::rules::rulesramsch::*;
-    use crate::rules::payoutdecider::*;
-    let vecstr_rule_parts = str_rules_with_player.split(" von ").collect::<Vec<_>>();
-    let epi_active = if_then_some!(2==vecstr_rule_parts.len(), vecstr_rule_parts[1])
-        .ok_or_else(|| format_err!("Cannot determine active player: {}", str_rules_with_player)) // TODO not needed for ramsch
-        .and_then(fn_player_to_epi)?;
+    let (ruledescription, ostr_player, otpln_tarif, ovecruleparam) = (
+        spaces().with(rule_description_parser()),
+        optional(attempt((separator_parser(), keyword_parser("von"), skip_many1(space()))).with(player_parser())),
+        optional(attempt(spaces().with(tarif_parser()))),
+        optional(attempt(spaces().with((
+            char('[').skip(spaces()),
+            sep_by::<Vec<_>, _, _>(ruleparam_parser().skip(spaces()), char(',').skip(spaces())),
+            char(']'),
+        )))).map(|otpl_ruleparams| otpl_ruleparams.map(|(_chr_open, vecruleparam, _chr_close)| vecruleparam)),
+    )
+        .skip((spaces(), eof()))
+        .easy_parse(State::new(str_rules_with_player))
+        .map(|pairoutconsumed| pairoutconsumed.0)
+        .map_err(|err| format_err!("Error in parsing rule description \"{}\": {}", str_rules_with_player, err))?;
+    let (n_tarif_extra, n_tarif_ruf, n_tarif_solo) = match otpln_tarif {
+        None => (n_tarif_extra, n_tarif_ruf, n_tarif_solo),
+        Some((on_tarif_extra, n_tarif_ruf, on_tarif_solo)) => (
+            on_tarif_extra.unwrap_or(n_tarif_extra),
+            n_tarif_ruf,
+            on_tarif_solo.unwrap_or(n_tarif_ruf),
+        ),
+    };
+    let oepi = ostr_player.map(|str_player| fn_player_to_epi(&str_player)).transpose()?;
+    let epi_active = || oepi.ok_or_else(|| format_err!("Cannot determine active player: {}", str_rules_with_player));
+    let mut vecruleparam = ovecruleparam.unwrap_or_default();
+    macro_rules! take_ruleparam {($eruleparam: ident) => {
+        vecruleparam.iter()
+            .position(|ruleparam| matches!(ruleparam, VRuleParam::$eruleparam(_)))
+            .map(|i_ruleparam| match vecruleparam.remove(i_ruleparam) {
+                VRuleParam::$eruleparam(val) => val,
+                _ => unreachable!(),
+            })
+    }}
+    let invalid_priority = |prio| format_err!("Invalid priority {:?}: {}", prio, str_rules_with_player);
     // Regarding laufende:
     // https://www.sauspiel.de/hilfe#71-beim-farbwenz-wurden-meine-laufende-nicht-berechnet
     // https://www.schafkopfschule.de/index.php/regeln.html?file=files/inhalte/dokumente/Spielen/Regeln/Schafkopfregeln-Aktuell-29.3.2007.pdf (Section 4.2 Spielabrechnung)
-    let str_rules = vecstr_rule_parts[0].to_lowercase();
-    let str_rules_contains = |slcstr: &[&str]| slcstr.iter().any(|str| str_rules.contains(str));
-    // determine oefarbe
-    let oefarbe = match [
-        (EFarbe::Eichel, &["eichel", "alt"] as &[&str]),
-        (EFarbe::Gras, &["gras", "grün", "laub", "blatt", "blau"]),
-        (EFarbe::Herz, &["herz", "rot"]),
-        (EFarbe::Schelln, &["schelln", "schelle", "pump", "hundsgfickte"]),
-    ].into_iter()
-        .filter(|(_efarbe, slcstr_farbe)| str_rules_contains(slcstr_farbe))
-        .exactly_one()
-    {
-        Ok((efarbe, _)) => Some(efarbe),
-        Err(itefarbeslcstr) => {
-            if 0==itefarbeslcstr.count() {
-                None
+    macro_rules! payoutparams {($n_payout_base: expr, $n_lauf_lbound_default: expr) => {
+        SPayoutDeciderParams::new(
+            $n_payout_base,
+            /*n_payout_schneider_schwarz*/n_tarif_extra,
+            SLaufendeParams::new(
+                /*n_payout_single_player*/take_ruleparam!(LaufPrice).unwrap_or(n_tarif_extra),
+                /*n_lauf_lbound*/take_ruleparam!(LaufMin).unwrap_or($n_lauf_lbound_default),
+            ),
+        )
+    }}
+    let rules = match ruledescription {
+        VRuleDescription::Rufspiel(efarbe) => Box::new(SRulesRufspiel::new(
+            epi_active()?,
+            efarbe,
+            payoutparams!(/*n_payout_base*/n_tarif_ruf, /*n_lauf_lbound_default*/3),
+        )) as Box<dyn TRules>,
+        VRuleDescription::Hochzeit => Box::new(SRulesHochzeit::new(
+            epi_active()?,
+            payoutparams!(/*n_payout_base*/n_tarif_ruf, /*n_lauf_lbound_default*/3),
+        )),
+        VRuleDescription::SoloLike(oefarbe, esololike, esololikepayout) => {
+            let payoutparams = payoutparams!(
+                /*n_payout_base*/n_tarif_solo,
+                /*n_lauf_lbound_default*/if oefarbe.is_some() {3} else {2}
+            );
+            macro_rules! toutsieparams {() => {
+                SToutSieParams::new(
+                    /*b_laufende*/take_ruleparam!(ToutSieLaufende).unwrap_or(true),
+                    /*b_schneider_schwarz*/take_ruleparam!(ToutSieSchneiderSchwarz).unwrap_or(false),
+                )
+            }}
+            let epi = epi_active()?;
+            match esololikepayout {
+                ESoloLikePayout::PointBased => {
+                    let pointstowin = match take_ruleparam!(Priority) {
+                        None => VGameAnnouncementPrioritySoloLike::SoloSimple(0),
+                        Some(VGameAnnouncementPriority::SoloLike(prio)) => prio,
+                        Some(prio) => return Err(invalid_priority(prio)),
+                    };
+                    let schneiderschwarzannouncement = SSchneiderSchwarzAnnouncement {
+                        on_payout_announced: take_ruleparam!(AnnounceSchneiderSchwarzPrice),
+                        oeschneiderschwarz: None,
+                    };
+                    let schneiderschwarzannouncement = match take_ruleparam!(SchneiderSchwarzAnnounced) {
+                        None => schneiderschwarzannouncement,
+                        Some(eschneiderschwarz) => schneiderschwarzannouncement.announced(eschneiderschwarz)
+                            .ok_or_else(|| format_err!("Cannot announce {}: {}", eschneiderschwarz, str_rules_with_player))?,
+                    };
+                    sololike(epi, oefarbe, esololike, SPayoutDeciderPointBased{
+                        payoutparams,
+                        pointstowin,
+                        schneiderschwarzannouncement,
+                    })
+                },
+                ESoloLikePayout::Tout => {
+                    let i_prio = match take_ruleparam!(Priority) {
+                        None => 0,
+                        Some(VGameAnnouncementPriority::SoloTout(i_prio)) => i_prio,
+                        Some(prio) => return Err(invalid_priority(prio)),
+                    };
+                    sololike(epi, oefarbe, esololike, SPayoutDeciderTout::new(payoutparams, i_prio, toutsieparams!()))
+                },
+                ESoloLikePayout::Sie => sololike(epi, oefarbe, esololike, SPayoutDeciderSie::new(payoutparams, toutsieparams!())),
+            }.upcast_box()
+        },
+        VRuleDescription::Bettel{b_brett} => {
+            let epi = epi_active()?;
+            let prio = take_ruleparam!(Priority)
+                /*large negative number to make less important than any sololike*/
+                .unwrap_or(VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(-999_999)));
+            if take_ruleparam!(Stichzwang).unwrap_or(false) {
+                Box::new(SRulesBettel::<SBettelAllAllowedCardsWithinStichStichzwang>::new(epi, prio, /*n_payout_base*/n_tarif_ruf, b_brett)) as Box<dyn TRules>
             } else {
-                return Err(format_err!("Could not clearly determine efarbe."))
+                Box::new(SRulesBettel::<SBettelAllAllowedCardsWithinStichNormal>::new(epi, prio, /*n_payout_base*/n_tarif_ruf, b_brett))
             }
         },
-    };
-    let make_sololike = |esololike| {
-        macro_rules! make_sololike_internal {($payoutdecider: ident) => {
-            Ok(sololike(
-                epi_active,
-                oefarbe,
-                esololike,
-                $payoutdecider::default_payoutdecider(
-                    /*n_payout_base*/n_tarif_solo,
-                    /*n_payout_schneider_schwarz*/n_tarif_extra,
-                    SLaufendeParams::new(
-                        /*n_payout_single_player*/n_tarif_extra,
-                        /*n_lauf_lbound*/if let Some(_efarbe)=oefarbe {3} else {2},
-                    ),
-                ),
-            ).upcast_box())
-        }}
-        if str_rules_contains(&["tout"]) {
-            make_sololike_internal!(SPayoutDeciderTout)
-        } else {
-            make_sololike_internal!(SPayoutDeciderPointBased)
-        }
-    };
-    match [
-        (&["rufspiel", "sauspiel"] as &[&str], {
-            match oefarbe {
-                None => Err(format_err!("Rufspiel requires efarbe")),
-                Some(efarbe) => match efarbe {
-                    EFarbe::Herz => Err(format_err!("Rufspiel incompatible with EFarbe::Herz")),
-                    EFarbe::Eichel | EFarbe::Gras | EFarbe::Schelln => {
-                        Ok(Box::new(SRulesRufspiel::new(
-                            epi_active,
-                            efarbe,
-                            SPayoutDeciderParams::new(
-                                /*n_payout_base*/n_tarif_ruf,
-                                /*n_payout_schneider_schwarz*/n_tarif_extra,
-                                SLaufendeParams::new(
-                                    /*n_payout_single_player*/n_tarif_extra,
-                                    /*n_lauf_lbound*/3,
-                                ),
-                            )
-                        )) as Box<dyn TRules>)
-                    }
-                }
+        VRuleDescription::Ramsch | VRuleDescription::Kreuzspiel if oepi.is_some() => {
+            bail!("{:?} does not have an active player: {}", ruledescription, str_rules_with_player)
+        },
+        VRuleDescription::Ramsch => {
+            let b_loser_pays_into_stock = take_ruleparam!(LoserPaysIntoStock).unwrap_or(false);
+            if b_loser_pays_into_stock && 0!=n_tarif_ruf%2 {
+                bail!("Price must be even if the loser pays into the stock: {}", str_rules_with_player);
             }
-        }),
-        (&["solo", "sticht"], make_sololike(ESoloLike::Solo)),
-        (&["wenz"], make_sololike(ESoloLike::Wenz)),
-        (&["geier"], make_sololike(ESoloLike::Geier)),
-        (&["ramsch"], {
-            Ok(Box::new(SRulesRamsch::new(
+            Box::new(SRulesRamsch::new(
                 /*n_price*/n_tarif_ruf,
-                VDurchmarsch::AtLeast(91), // https://www.sauspiel.de/blog/66-bei-sauspiel-wird-jetzt-mit-ramsch-gespielt
-                // TODO Jungfrau
-            )) as Box<dyn TRules>)
-        }),
-    ].into_iter()
-        .filter(|(slcstr, _)| str_rules_contains(slcstr))
-        .exactly_one()
-    {
-        Ok((_, Ok(rules))) => Ok(rules.box_clone()), // TODORUST avoid box_clone once arrays support proper into_iter
-        Ok((_, Err(ref e))) => Err(format_err!("{}", &e.to_string())),
-        Err(itslcstrrules) => {
-            if 0==itslcstrrules.count() {
-                Err(format_err!("Cannot understand rule description."))
-            } else {
-                Err(format_err!("Rule description is ambiguous."))
-            }
-        }
+                take_ruleparam!(Durchmarsch).unwrap_or(VDurchmarsch::AtLeast(91)), // https://www.sauspiel.de/blog/66-bei-sauspiel-wird-jetzt-mit-ramsch-gespielt
+                take_ruleparam!(Jungfrau).unwrap_or(EJungfrau::DoubleEach),
+                /*b_schieben*/take_ruleparam!(Schieben).unwrap_or(false),
+                b_loser_pays_into_stock,
+            ))
+        },
+        VRuleDescription::Kreuzspiel => Box::new(SRulesKreuzspiel::new(
+            payoutparams!(/*n_payout_base*/n_tarif_ruf, /*n_lauf_lbound_default*/3),
+        )),
+    };
+    if !vecruleparam.is_empty() {
+        bail!("Parameters {:?} not supported: {}", vecruleparam, str_rules_with_player);
     }
+    Ok(rules)
 }
 
 pub fn parse_rule_description_simple(str_rules: &str) -> Result<Box<dyn TRules>, Error> {
     crate::rules::parser::parse_rule_description(
         str_rules,
-        (/*n_tarif_extra*/10, /*n_tarif_ruf*/20, /*n_tarif_solo*/50), // TODO? make customizable
-        /*fn_player_to_epi*/|str_epi| EPlayerIndex::checked_from_usize(str_epi.parse()?)
-            .ok_or_else(|| format_err!("Cannot convert {} to EPlayerIndex.", str_epi)),
+        (/*n_tarif_extra*/10, /*n_tarif_ruf*/20, /*n_tarif_solo*/50), // can be overridden in str_rules
+        /*fn_player_to_epi*/|str_epi| str_epi.parse::<EPlayerIndex>()
+            .map_err(|_err| format_err!("Cannot convert {} to EPlayerIndex.", str_epi)),
     )
 }
+
+impl fmt::Display for VRuleParam {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VRuleParam::LaufMin(n_lauf_lbound) => write!(f, "lauf-min={}", n_lauf_lbound),
+            VRuleParam::LaufPrice(n_payout_per_lauf) => write!(f, "lauf-price={}", n_payout_per_lauf),
+            VRuleParam::Priority(prio) => {
+                write!(f, "priority=")?;
+                match prio {
+                    VGameAnnouncementPriority::RufspielLike => write!(f, "rufspiel"),
+                    VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(i_prio)) => write!(f, "{}", i_prio),
+                    VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSteigern{n_points_to_win, n_step}) => {
+                        write!(f, "steigern:{}:{}", n_points_to_win, n_step)
+                    },
+                    VGameAnnouncementPriority::SoloTout(i_prio) => write!(f, "tout:{}", i_prio),
+                    VGameAnnouncementPriority::SoloSie => write!(f, "sie"),
+                }
+            },
+            VRuleParam::AnnounceSchneiderSchwarzPrice(n_payout_announced) => write!(f, "announce-schneider-schwarz-price={}", n_payout_announced),
+            VRuleParam::SchneiderSchwarzAnnounced(eschneiderschwarz) => write!(f, "announced={}", match eschneiderschwarz {
+                ESchneiderSchwarz::Schneider => "schneider",
+                ESchneiderSchwarz::Schwarz => "schwarz",
+            }),
+            VRuleParam::ToutSieLaufende(b_laufende) => write!(f, "tout-sie-laufende={}", b_laufende),
+            VRuleParam::ToutSieSchneiderSchwarz(b_schneider_schwarz) => write!(f, "tout-sie-schneider-schwarz={}", b_schneider_schwarz),
+            VRuleParam::Stichzwang(b_stichzwang) => write!(f, "stichzwang={}", b_stichzwang),
+            VRuleParam::Durchmarsch(durchmarsch) => match durchmarsch {
+                VDurchmarsch::None => write!(f, "durchmarsch=none"),
+                VDurchmarsch::All => write!(f, "durchmarsch=all"),
+                VDurchmarsch::AtLeast(n_points) => write!(f, "durchmarsch={}", n_points),
+            },
+            VRuleParam::Jungfrau(ejungfrau) => write!(f, "jungfrau={}", match ejungfrau {
+                EJungfrau::Ignore => "ignore",
+                EJungfrau::DoubleOnce => "once",
+                EJungfrau::DoubleEach => "each",
+            }),
+            VRuleParam::Schieben(b_schieben) => write!(f, "schieben={}", b_schieben),
+            VRuleParam::LoserPaysIntoStock(b_loser_pays_into_stock) => write!(f, "loser-pays-into-stock={}", b_loser_pays_into_stock),
+        }
+    }
+}
+
+/// Description of rules that can be read by parse_rule_description, including tariffs and all parameters.
+pub struct SRuleDescription<'rules>(pub &'rules dyn TRules);
+
+impl fmt::Display for SRuleDescription<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+        if let Some(epi) = self.0.playerindex() {
+            write!(f, " von {}", epi)?;
+        }
+        let ruleparams = self.0.ruleparams();
+        match ruleparams.on_payout_extra {
+            None => write!(f, " ({})", ruleparams.n_payout_base)?,
+            Some(n_payout_extra) => write!(f, " ({}/{})", n_payout_extra, ruleparams.n_payout_base)?,
+        }
+        if !ruleparams.vecruleparam.is_empty() {
+            write!(f, " [{}]", ruleparams.vecruleparam.iter().format(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parse_rule_description() {
+    use crate::rules::ruleset::*;
+    fn parse(str_rules: &str) -> Result<Box<dyn TRules>, Error> {
+        parse_rule_description_simple(str_rules)
+    }
+    for (str_rules, str_rules_expected, oepi) in [
+        ("Rufspiel mit der Eichel-Sau von 1", "Rufspiel mit der Eichel-Sau", Some(EPlayerIndex::EPI1)),
+        ("Sauspiel auf die Blaue von 3", "Rufspiel mit der Gras-Sau", Some(EPlayerIndex::EPI3)),
+        ("sauspiel schelln von 0", "Rufspiel mit der Schelln-Sau", Some(EPlayerIndex::EPI0)),
+        ("Herz-Solo von 2", "HerzSolo", Some(EPlayerIndex::EPI2)),
+        ("  herz sticht von 2  ", "HerzSolo", Some(EPlayerIndex::EPI2)),
+        ("Wenz von 0", "Wenz", Some(EPlayerIndex::EPI0)),
+        ("Gras-Farbwenz Tout von 0", "GrasWenz-Tout", Some(EPlayerIndex::EPI0)),
+        ("Schelln-Geier von 1", "SchellnGeier", Some(EPlayerIndex::EPI1)),
+        ("Solo-Sie von 3", "Solo-Sie", Some(EPlayerIndex::EPI3)),
+        ("Hochzeit von 1", "Hochzeit", Some(EPlayerIndex::EPI1)),
+        ("Bettel von 2", "Bettel", Some(EPlayerIndex::EPI2)),
+        ("Bettel ouvert von 0", "Bettel Brett", Some(EPlayerIndex::EPI0)),
+        ("Ramsch", "Ramsch", None),
+        ("Kreuzspiel (5/10)", "Kreuzspiel", None),
+        ("Wenz von 1 (10/50) [lauf-min=2]", "Wenz", Some(EPlayerIndex::EPI1)),
+        ("Ramsch (20) [durchmarsch=all, jungfrau=once]", "Ramsch", None),
+        ("Geier von 3 (50/200) [lauf-price=10]", "Geier", Some(EPlayerIndex::EPI3)),
+    ].iter() {
+        let rules = unwrap!(parse(str_rules));
+        assert_eq!(rules.to_string(), *str_rules_expected);
+        assert_eq!(rules.playerindex(), *oepi);
+    }
+    assert!(format!("{:?}", unwrap!(parse("Eichel-Solo von 0 (5/10/30)"))).contains("n_payout_base: 30"));
+    assert!(format!("{:?}", unwrap!(parse("Eichel-Solo von 0 (5/10)"))).contains("n_payout_base: 10"));
+    assert_eq!(
+        SRuleDescription(unwrap!(parse("Geier von 3 (50/200) [lauf-price=10]")).as_ref()).to_string(),
+        "Geier von 3 (50/200) [lauf-min=2, lauf-price=10, priority=0]",
+    );
+    for str_rules in [
+        "Rufspiel mit der Herz-Sau von 0",
+        "Farbwenz von 0",
+        "Herz-Solo",
+        "Herz-Solo von 4",
+        "Herz-Solo von 0 (10/20",
+        "Ramsch von 0",
+        "Schafkopf",
+    ].iter() {
+        assert!(parse(str_rules).is_err(), "{}", str_rules);
+    }
+    assert!(unwrap!(parse("Wenz vonn 0").err()).to_string().contains("column: 6"));
+    for str_rules in [
+        "Wenz von 0 (10/50) [lauf-min=2, lauf-min=3]",
+        "Wenz von 0 (10/50) [stichzwang=true]",
+        "Wenz Tout von 0 [priority=0]",
+        "Solo-Sie von 0 [priority=sie]",
+        "Herz-Solo von 0 [announced=schneider]",
+        "Ramsch (5) [loser-pays-into-stock=true]",
+        "Ramsch [durchmarsch=al]",
+        "Kreuzspiel (1/2/3/4)",
+    ].iter() {
+        assert!(parse(str_rules).is_err(), "{}", str_rules);
+    }
+    // SRuleDescription describes everything a ruleset can produce, so parsed rules pay the same
+    use crate::game::TGamePhase;
+    use rand::{prelude::SliceRandom, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for str_ruleset in [
+        include_str!("../../../rulesets/default.toml"),
+        include_str!("../../../rulesets/bettel.toml"),
+        r#"
+            base-price=15
+            solo-price=40
+            lauf-min=4
+            [rufspiel]
+            extra=5
+            [hochzeit]
+            lauf-min=2
+            [solo]
+            price=60
+            announce-schneider-schwarz=true
+            announce-schneider-schwarz-price=25
+            tout-sie-laufende=false
+            [wenz]
+            lauf-min=1
+            tout-sie-schneider-schwarz=true
+            [farbwenz]
+            [geier]
+            [farbgeier]
+            [bettel]
+            price=30
+            priority="above-wenz"
+            stichzwang=true
+            [bettel-brett]
+            priority="above-tout"
+            [ramsch]
+            price=12
+            durchmarsch="all"
+            jungfrau="once"
+        "#,
+        r#"
+            base-price=10
+            solo-price=50
+            lauf-min=3
+            [rufspiel]
+            [solo]
+            [wenz]
+            [steigern]
+            step=5
+            [bettel]
+            priority="above-solo"
+            [kreuzspiel]
+            extra=20
+            lauf-min=2
+        "#,
+        r#"
+            base-price=10
+            solo-price=50
+            lauf-min=3
+            [rufspiel]
+            [geier]
+            [ramsch]
+            price=20
+            durchmarsch=100
+            schieben=true
+            loser-pays-into-stock=true
+            [stoss]
+            [stock]
+        "#,
+    ].iter() {
+        let ruleset = unwrap!(SRuleSet::from_string(str_ruleset));
+        for rules in ruleset_rules(&ruleset)
+            .flat_map(|rules| {
+                let vecrules_announced = ESchneiderSchwarz::values()
+                    .filter_map(|eschneiderschwarz| rules.with_schneiderschwarz_announced(eschneiderschwarz))
+                    .collect::<Vec<_>>();
+                std::iter::once(rules).chain(vecrules_announced)
+            })
+        {
+            let str_rules = SRuleDescription(rules.as_ref()).to_string();
+            let rules_parsed = unwrap!(parse(&str_rules));
+            assert_eq!(SRuleDescription(rules_parsed.as_ref()).to_string(), str_rules);
+            assert_eq!(format!("{:?}", rules_parsed), format!("{:?}", rules));
+            for _i_game in 0..10 {
+                let ahand = crate::game::random_ahand_playable(rules.as_ref(), EKurzLang::Lang, &mut rng);
+                let mut game = crate::game::SGame::new(
+                    ahand,
+                    crate::game::SDoublings::new(SStaticEPI0{}),
+                    /*ostossparams*/None,
+                    rules.clone(),
+                    /*n_bock*/0,
+                    /*n_stock*/0,
+                );
+                while let Some((epi, _vecepi_stoss)) = game.which_player_can_do_something() {
+                    if let Some(epi_exchange) = game.card_exchange_pending() {
+                        let card = *unwrap!(game.ahand[epi_exchange].cards().choose(&mut rng));
+                        unwrap!(game.exchange_card(epi_exchange, card));
+                    } else {
+                        let card = *unwrap!(game.rules.all_allowed_cards(&game.stichseq, &game.ahand[epi]).choose(&mut rng));
+                        unwrap!(game.zugeben(card, epi));
+                    }
+                }
+                let gamefinishedstiche = SStichSequenceGameFinished::new(&game.stichseq);
+                for n_stock in [0, 20] {
+                    assert_eq!(
+                        rules_parsed.payout(gamefinishedstiche, /*tpln_stoss_doubling*/(1, 1), n_stock),
+                        rules.payout(gamefinishedstiche, /*tpln_stoss_doubling*/(1, 1), n_stock),
+                        "{}", str_rules,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn ruleset_rules(ruleset: &crate::rules::ruleset::SRuleSet) -> impl Iterator<Item=Box<dyn TRules>> + '_ {
+    use crate::rules::ruleset::*;
+    EPlayerIndex::values()
+        .flat_map(move |epi| ruleset.announceable_rules(epi))
+        .chain(ruleset.ofallbackrules.as_ref().and_then(VFallbackRules::rules))
+}
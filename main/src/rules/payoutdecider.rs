@@ -15,6 +15,46 @@ pub struct SPayoutDeciderParams {
     pub laufendeparams : SLaufendeParams,
 }
 
+impl SPayoutDeciderParams {
+    pub fn ruleparams(&self, itruleparam: impl IntoIterator<Item=VRuleParam>) -> SRuleParams {
+        SRuleParams {
+            on_payout_extra: Some(self.n_payout_schneider_schwarz),
+            n_payout_base: self.n_payout_base,
+            vecruleparam: std::iter::once(VRuleParam::LaufMin(self.laufendeparams.n_lauf_lbound))
+                .chain(if_then_some!( // laufende usually pay the extra tariff
+                    self.laufendeparams.n_payout_per_lauf!=self.n_payout_schneider_schwarz,
+                    VRuleParam::LaufPrice(self.laufendeparams.n_payout_per_lauf)
+                ))
+                .chain(itruleparam)
+                .collect(),
+        }
+    }
+
+    pub fn payout_schneider_schwarz(
+        &self,
+        rulestatecache: &SRuleStateCache,
+        ekurzlang: EKurzLang,
+        playerparties: &impl TPlayerParties,
+        b_primary_party_wins: bool,
+    ) -> isize {
+        let n_points_primary_party = EPlayerIndex::values()
+            .filter(|epi| playerparties.is_primary_party(*epi))
+            .map(|epi| rulestatecache.changing.mapepipointstichcount[epi].n_point)
+            .sum::<isize>();
+        if EPlayerIndex::values()
+            .filter(|epi| b_primary_party_wins==playerparties.is_primary_party(*epi))
+            .map(|epi| rulestatecache.changing.mapepipointstichcount[epi].n_stich)
+            .sum::<usize>()==ekurzlang.cards_per_player()
+        {
+            2*self.n_payout_schneider_schwarz // schwarz
+        } else if (b_primary_party_wins && n_points_primary_party>90) || (!b_primary_party_wins && n_points_primary_party<=30) {
+            self.n_payout_schneider_schwarz // schneider
+        } else {
+            0 // "nothing", i.e. neither schneider nor schwarz
+        }
+    }
+}
+
 
 pub trait TPointsToWin : Sync + 'static + Clone + fmt::Debug {
     fn points_to_win(&self) -> isize;
@@ -29,10 +69,73 @@ impl TPointsToWin for SPointsToWin61 {
     }
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct SSchneiderSchwarzAnnouncement {
+    pub on_payout_announced : Option<isize>, // None if schneider/schwarz cannot be announced
+    pub oeschneiderschwarz : Option<ESchneiderSchwarz>,
+}
+
+impl SSchneiderSchwarzAnnouncement {
+    pub fn ruleparams(&self) -> impl Iterator<Item=VRuleParam> {
+        self.on_payout_announced.map(VRuleParam::AnnounceSchneiderSchwarzPrice).into_iter()
+            .chain(self.oeschneiderschwarz.map(VRuleParam::SchneiderSchwarzAnnounced))
+    }
+
+    pub fn announced(&self, eschneiderschwarz: ESchneiderSchwarz) -> Option<Self> {
+        // announcing the same level again is allowed so that finished games can be replayed
+        if_then_some!(self.on_payout_announced.is_some() && self.oeschneiderschwarz<=Some(eschneiderschwarz), Self {
+            on_payout_announced: self.on_payout_announced,
+            oeschneiderschwarz: Some(eschneiderschwarz),
+        })
+    }
+
+    fn payout_announced(&self) -> isize {
+        match self.oeschneiderschwarz {
+            None => 0,
+            Some(ESchneiderSchwarz::Schneider) => unwrap!(self.on_payout_announced),
+            Some(ESchneiderSchwarz::Schwarz) => 2*unwrap!(self.on_payout_announced),
+        }
+    }
+
+    fn points_and_stichs_of_opponents(rulestatecache: &SRuleStateCache, playerparties: &impl TPlayerParties) -> (isize, usize) {
+        EPlayerIndex::values()
+            .filter(|epi| !playerparties.is_primary_party(*epi))
+            .map(|epi| &rulestatecache.changing.mapepipointstichcount[epi])
+            .fold((0, 0), |(n_point, n_stich), pointstichcount| (n_point + pointstichcount.n_point, n_stich + pointstichcount.n_stich))
+    }
+
+    fn made(&self, rulestatecache: &SRuleStateCache, playerparties: &impl TPlayerParties, b_game_finished: bool) -> bool {
+        let (n_points_opponents, n_stich_opponents) = Self::points_and_stichs_of_opponents(rulestatecache, playerparties);
+        match self.oeschneiderschwarz {
+            None => true,
+            Some(ESchneiderSchwarz::Schneider) => {
+                let n_points_primary = EPlayerIndex::values()
+                    .filter(|epi| playerparties.is_primary_party(*epi))
+                    .map(|epi| rulestatecache.changing.mapepipointstichcount[epi].n_point)
+                    .sum::<isize>();
+                debug_assert!(!b_game_finished || (n_points_primary>90)==(n_points_opponents<30));
+                n_points_primary>90
+            },
+            Some(ESchneiderSchwarz::Schwarz) => b_game_finished && 0==n_stich_opponents,
+        }
+    }
+
+    fn missed(&self, rulestatecache: &SRuleStateCache, playerparties: &impl TPlayerParties) -> bool {
+        let (n_points_opponents, n_stich_opponents) = Self::points_and_stichs_of_opponents(rulestatecache, playerparties);
+        match self.oeschneiderschwarz {
+            None => false,
+            Some(ESchneiderSchwarz::Schneider) => 30<=n_points_opponents,
+            Some(ESchneiderSchwarz::Schwarz) => 0<n_stich_opponents,
+        }
+    }
+}
+
 #[derive(Clone, Debug, new)]
 pub struct SPayoutDeciderPointBased<PointsToWin> {
     pub payoutparams : SPayoutDeciderParams,
     pub pointstowin: PointsToWin,
+    #[new(default)]
+    pub schneiderschwarzannouncement: SSchneiderSchwarzAnnouncement,
 }
 
 impl<PointsToWin: TPointsToWin> SPayoutDeciderPointBased<PointsToWin> {
@@ -56,26 +159,13 @@ impl<PointsToWin: TPointsToWin> SPayoutDeciderPointBased<PointsToWin> {
                 .map(|(stich, _epi_winner)| card_points::points_stich(stich))
                 .sum::<isize>()
         );
-        let b_primary_party_wins = n_points_primary_party >= self.pointstowin.points_to_win();
+        let b_primary_party_wins = n_points_primary_party >= self.pointstowin.points_to_win()
+            && self.schneiderschwarzannouncement.made(rulestatecache, playerparties, /*b_game_finished*/true); // missing the announcement loses the game
         internal_payout(
             /*n_payout_single_player*/ self.payoutparams.n_payout_base
-                + { 
-                    if debug_verify_eq!(
-                        EPlayerIndex::values()
-                            .filter(|epi| b_primary_party_wins==playerparties.is_primary_party(*epi))
-                            .map(|epi|  rulestatecache.changing.mapepipointstichcount[epi].n_stich)
-                            .sum::<usize>()==gamefinishedstiche.get().kurzlang().cards_per_player(),
-                        gamefinishedstiche.get().completed_stichs_winner_index(rules)
-                            .all(|(_stich, epi_winner)| b_primary_party_wins==playerparties.is_primary_party(epi_winner))
-                    ) {
-                        2*self.payoutparams.n_payout_schneider_schwarz // schwarz
-                    } else if (b_primary_party_wins && n_points_primary_party>90) || (!b_primary_party_wins && n_points_primary_party<=30) {
-                        self.payoutparams.n_payout_schneider_schwarz // schneider
-                    } else {
-                        0 // "nothing", i.e. neither schneider nor schwarz
-                    }
-                }
-                + self.payoutparams.laufendeparams.payout_laufende::<Rules, _>(rulestatecache, gamefinishedstiche, playerparties),
+                + self.payoutparams.payout_schneider_schwarz(rulestatecache, gamefinishedstiche.get().kurzlang(), playerparties, b_primary_party_wins)
+                + self.payoutparams.laufendeparams.payout_laufende::<Rules, _>(rulestatecache, gamefinishedstiche, playerparties)
+                + self.schneiderschwarzannouncement.payout_announced(),
             playerparties,
             b_primary_party_wins,
         )
@@ -84,7 +174,7 @@ impl<PointsToWin: TPointsToWin> SPayoutDeciderPointBased<PointsToWin> {
     pub fn payouthints<Rules: TRulesNoObj, PlayerParties: TPlayerParties>(
         &self,
         if_dbg_else!({rules}{_rules}): &Rules,
-        if_dbg_else!({stichseq}{_stichseq}): &SStichSequence,
+        stichseq: &SStichSequence,
         _ahand: &EnumMap<EPlayerIndex, SHand>,
         rulestatecache: &SRuleStateCache,
         playerparties: &PlayerParties,
@@ -100,17 +190,21 @@ impl<PointsToWin: TPointsToWin> SPayoutDeciderPointBased<PointsToWin> {
                 }))
         );
         let internal_payouthints = |b_primary_party_wins| {
-            internal_payout(
-                /*n_payout_single_player*/ self.payoutparams.n_payout_base,
-                playerparties,
-                b_primary_party_wins,
+            payouthints_bounded_by(
+                &internal_payout(
+                    /*n_payout_single_player*/ self.payoutparams.n_payout_base
+                        + self.schneiderschwarzannouncement.payout_announced(),
+                    playerparties,
+                    b_primary_party_wins,
+                ),
+                stichseq.kurzlang(),
             )
-                .map(|n_payout| {
-                     assert_ne!(0, *n_payout);
-                     tpl_flip_if(0<*n_payout, (None, Some(*n_payout)))
-                })
         };
-        if /*b_primary_party_wins*/ mapbn_points[/*b_primary*/true] >= self.pointstowin.points_to_win() {
+        if self.schneiderschwarzannouncement.missed(rulestatecache, playerparties) {
+            internal_payouthints(/*b_primary_party_wins*/false)
+        } else if /*b_primary_party_wins*/ mapbn_points[/*b_primary*/true] >= self.pointstowin.points_to_win()
+            && self.schneiderschwarzannouncement.made(rulestatecache, playerparties, stichseq.game_finished())
+        {
             internal_payouthints(/*b_primary_party_wins*/true)
         } else if mapbn_points[/*b_primary*/false] > 120-self.pointstowin.points_to_win() {
             internal_payouthints(/*b_primary_party_wins*/false)
@@ -122,7 +216,6 @@ impl<PointsToWin: TPointsToWin> SPayoutDeciderPointBased<PointsToWin> {
 
 impl SLaufendeParams {
     pub fn payout_laufende<Rules: TRulesNoObj, PlayerParties: TPlayerParties>(&self, rulestatecache: &SRuleStateCache, gamefinishedstiche: SStichSequenceGameFinished, playerparties: &PlayerParties) -> isize {
-        let ekurzlang = gamefinishedstiche.get().kurzlang();
         debug_assert_eq!(
             SRuleStateCacheFixed::new(
                 gamefinishedstiche.get(),
@@ -130,21 +223,44 @@ impl SLaufendeParams {
             ),
             rulestatecache.fixed,
         );
+        self.payout_laufende_fixed::<Rules, _>(&rulestatecache.fixed, gamefinishedstiche.get().kurzlang(), playerparties)
+    }
+
+    // laufende only depend on the initial distribution of cards, so they are known before the game ends
+    pub fn payout_laufende_fixed<Rules: TRulesNoObj, PlayerParties: TPlayerParties>(&self, rulestatecachefixed: &SRuleStateCacheFixed, ekurzlang: EKurzLang, playerparties: &PlayerParties) -> isize {
         let laufende_relevant = |card: SCard| { // TODO should we make this part of SRuleStateCacheFixed?
-            playerparties.is_primary_party(rulestatecache.fixed.who_has_card(card))
+            // None if card lies in the stock, which ends the laufende
+            rulestatecachefixed.who_has_card(card).map(|epi| playerparties.is_primary_party(epi))
         };
         let mut itcard_trumpf_descending = Rules::TrumpfDecider::trumpfs_in_descending_order();
-        let b_might_have_lauf = laufende_relevant(unwrap!(itcard_trumpf_descending.next()));
-        let n_laufende = itcard_trumpf_descending
-            .filter(|card| ekurzlang.supports_card(*card))
-            .take_while(|card| b_might_have_lauf==laufende_relevant(*card))
-            .count()
-            + 1 // consumed by next()
-        ;
+        let ob_might_have_lauf = laufende_relevant(unwrap!(itcard_trumpf_descending.next()));
+        let n_laufende = if ob_might_have_lauf.is_none() {
+            0
+        } else {
+            itcard_trumpf_descending
+                .filter(|card| ekurzlang.supports_card(*card))
+                .take_while(|card| ob_might_have_lauf==laufende_relevant(*card))
+                .count()
+                + 1 // consumed by next()
+        };
         (if n_laufende<self.n_lauf_lbound {0} else {n_laufende}).as_num::<isize>() * self.n_payout_per_lauf
     }
 }
 
+// payouts can only get more extreme than an_payout, except for empty seats, which are not paid
+pub fn payouthints_bounded_by(an_payout: &EnumMap<EPlayerIndex, isize>, ekurzlang: EKurzLang) -> EnumMap<EPlayerIndex, (Option<isize>, Option<isize>)> {
+    EPlayerIndex::map_from_fn(|epi| {
+        let n_payout = an_payout[epi];
+        if ekurzlang.takes_part(epi) {
+            assert_ne!(0, n_payout);
+            tpl_flip_if(0<n_payout, (None, Some(n_payout)))
+        } else {
+            assert_eq!(0, n_payout);
+            (Some(0), Some(0))
+        }
+    })
+}
+
 pub fn internal_payout(n_payout_single_player: isize, playerparties: &impl TPlayerParties, b_primary_party_wins: bool) -> EnumMap<EPlayerIndex, isize> {
     EPlayerIndex::map_from_fn(|epi| {
         n_payout_single_player 
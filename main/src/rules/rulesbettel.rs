@@ -6,23 +6,26 @@ use crate::util::*;
 use std::marker::PhantomData;
 
 pub trait TBettelAllAllowedCardsWithinStich : Sync + 'static + Clone + fmt::Debug + Send {
+    const B_STICHZWANG: bool; // players must play a higher card if possible
     fn all_allowed_cards_within_stich(rulesbettel: &SRulesBettel<Self>, stichseq: &SStichSequence, hand: &SHand) -> SHandVector;
 }
 
 #[derive(Clone, Debug)]
 pub struct SRulesBettel<BettelAllAllowedCardsWithinStich> {
     epi : EPlayerIndex,
-    i_prio : isize,
+    prio : VGameAnnouncementPriority,
     payoutdecider : SPayoutDeciderBettel,
+    b_brett : bool, // hand of active player is shown to everyone after the first stich
     phantom : PhantomData<BettelAllAllowedCardsWithinStich>,
 }
 
 impl<BettelAllAllowedCardsWithinStich: TBettelAllAllowedCardsWithinStich> SRulesBettel<BettelAllAllowedCardsWithinStich> {
-    pub fn new(epi: EPlayerIndex, i_prio: isize, n_payout_base: isize) -> SRulesBettel<BettelAllAllowedCardsWithinStich> {
+    pub fn new(epi: EPlayerIndex, prio: VGameAnnouncementPriority, n_payout_base: isize, b_brett: bool) -> SRulesBettel<BettelAllAllowedCardsWithinStich> {
         SRulesBettel{
             epi,
-            i_prio,
+            prio,
             payoutdecider: SPayoutDeciderBettel{n_payout_base},
+            b_brett,
             phantom: PhantomData,
         }
     }
@@ -33,13 +36,17 @@ impl<BettelAllAllowedCardsWithinStich: TBettelAllAllowedCardsWithinStich> SRules
 
 impl<BettelAllAllowedCardsWithinStich: TBettelAllAllowedCardsWithinStich> fmt::Display for SRulesBettel<BettelAllAllowedCardsWithinStich> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Bettel von {}", self.epi)
+        write!(f, "Bettel")?;
+        if self.b_brett {
+            write!(f, " Brett")?;
+        }
+        Ok(())
     }
 }
 
 impl<BettelAllAllowedCardsWithinStich: TBettelAllAllowedCardsWithinStich> TActivelyPlayableRules for SRulesBettel<BettelAllAllowedCardsWithinStich> {
     fn priority(&self) -> VGameAnnouncementPriority {
-        VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(self.i_prio))
+        self.prio.clone()
     }
 }
 
@@ -102,6 +109,7 @@ pub struct SBettelAllAllowedCardsWithinStichNormal {}
 pub struct SBettelAllAllowedCardsWithinStichStichzwang {}
 
 impl TBettelAllAllowedCardsWithinStich for SBettelAllAllowedCardsWithinStichNormal {
+    const B_STICHZWANG: bool = false;
     fn all_allowed_cards_within_stich(rulesbettel: &SRulesBettel<Self>, stichseq: &SStichSequence, hand: &SHand) -> SHandVector {
         assert!(!stichseq.current_stich().is_empty());
         all_allowed_cards_within_stich_distinguish_farbe_frei(
@@ -113,6 +121,7 @@ impl TBettelAllAllowedCardsWithinStich for SBettelAllAllowedCardsWithinStichNorm
     }
 }
 impl TBettelAllAllowedCardsWithinStich for SBettelAllAllowedCardsWithinStichStichzwang {
+    const B_STICHZWANG: bool = true;
     fn all_allowed_cards_within_stich(rulesbettel: &SRulesBettel<Self>, stichseq: &SStichSequence, hand: &SHand) -> SHandVector {
         let stich = stichseq.current_stich();
         let card_highest = stich[rulesbettel.preliminary_winner_index(stich)];
@@ -152,6 +161,21 @@ impl<BettelAllAllowedCardsWithinStich: TBettelAllAllowedCardsWithinStich> TRules
     fn all_allowed_cards_within_stich(&self, stichseq: &SStichSequence, hand: &SHand) -> SHandVector {
         BettelAllAllowedCardsWithinStich::all_allowed_cards_within_stich(self, stichseq, hand)
     }
+
+    fn ruleparams(&self) -> SRuleParams {
+        SRuleParams {
+            on_payout_extra: None,
+            n_payout_base: self.payoutdecider.n_payout_base,
+            vecruleparam: vec![
+                VRuleParam::Priority(self.prio.clone()),
+                VRuleParam::Stichzwang(BettelAllAllowedCardsWithinStich::B_STICHZWANG),
+            ],
+        }
+    }
+
+    fn revealed_hand_playerindex(&self, stichseq: &SStichSequence) -> Option<EPlayerIndex> {
+        if_then_some!(self.b_brett && !stichseq.completed_stichs().is_empty(), self.epi)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -1,6 +1,6 @@
 use crate::primitives::*;
 use crate::rules::{
-    payoutdecider::*, rulesbettel::*, rulesramsch::*, rulesrufspiel::*, rulessolo::*, *,
+    payoutdecider::*, rulesbettel::*, ruleshochzeit::*, ruleskreuzspiel::*, rulesramsch::*, rulesrufspiel::*, rulessolo::*, *,
 };
 use crate::util::*;
 use std::{fs::File, io::prelude::*, path::Path};
@@ -30,6 +30,11 @@ impl SRuleGroup {
         }
     }
 
+    /// Position of `rules` within `vecorules`, provided that `rules` refers to one of the group's rules.
+    pub fn position(&self, rules: &dyn TActivelyPlayableRules) -> Option<usize> {
+        self.vecorules.iter().position(|orules| orules.as_ref().is_some_and(|rules_group| std::ptr::addr_eq(rules_group.as_ref(), rules)))
+    }
+
     pub fn allowed_rules<'retval, 'hand : 'retval, 'rules : 'retval>(&'rules self, hand: SFullHand<'hand>) -> impl Clone + Iterator<Item=Option<&'rules dyn TActivelyPlayableRules>> + 'retval {
         self.vecorules.iter().map(|orules| orules.as_ref().map(|rules| rules.as_ref()))
             .filter(move |orules| orules.map_or(true, |rules| rules.can_be_played(hand)))
@@ -42,24 +47,114 @@ pub enum VStockOrT<Stock, T> {
     OrT(T),
 }
 
+#[derive(Debug, Clone)]
+pub enum VFallbackRules { // rules to be played if nobody announces a game
+    Ramsch(SRulesRamsch),
+    DerAlteMuss{ // holder of Eichel-Ober has to play
+        payoutparams_rufspiel: SPayoutDeciderParams,
+        payoutparams_solo: SPayoutDeciderParams, // used if no rufsau can be called
+    },
+    Kreuzspiel(SRulesKreuzspiel),
+}
+
+impl VFallbackRules {
+    /// If der Alte has to play, returns der Alte and the rules he can choose from.
+    pub fn der_alte_muss(&self, ahand: &EnumMap<EPlayerIndex, SHand>) -> Option<(EPlayerIndex, SRuleGroup)> {
+        if_then_some!(let VFallbackRules::DerAlteMuss{payoutparams_rufspiel, payoutparams_solo} = self, {
+            let card_alter = SCard::new(EFarbe::Eichel, ESchlag::Ober);
+            let epi_alter = unwrap!(EPlayerIndex::values().find(|epi| ahand[*epi].contains(card_alter)));
+            let hand_alter = SFullHand::new(
+                &ahand[epi_alter],
+                EKurzLang::from_cards_per_player(ahand[epi_alter].cards().len()),
+            );
+            let mut vecorules = EFarbe::values()
+                .filter(|efarbe| EFarbe::Herz!=*efarbe)
+                .map(|efarbe| SRulesRufspiel::new(epi_alter, efarbe, payoutparams_rufspiel.clone()))
+                .filter(|rulesrufspiel| rulesrufspiel.can_be_played(hand_alter))
+                .map(|rulesrufspiel| Some(Box::new(rulesrufspiel) as Box<dyn TActivelyPlayableRules>))
+                .collect::<Vec<_>>();
+            if vecorules.is_empty() {
+                // der Alte cannot call a sau, so he has to play a solo of his choice (any solo can be played)
+                vecorules = EFarbe::values()
+                    .map(|efarbe| Some(sololike(
+                        epi_alter,
+                        efarbe,
+                        ESoloLike::Solo,
+                        SPayoutDeciderPointBased::new(payoutparams_solo.clone(), VGameAnnouncementPrioritySoloLike::SoloSimple(0)),
+                    )))
+                    .collect();
+            }
+            assert!(vecorules.iter().all(|orules| orules.as_ref().is_some_and(|rules| rules.can_be_played(hand_alter))));
+            (epi_alter, SRuleGroup{
+                str_name: "Der Alte muss".to_string(),
+                vecorules,
+            })
+        })
+    }
+
+    /// Rules to be played if nobody announces a game, or `None` if der Alte has to choose his rules (see `der_alte_muss`).
+    pub fn rules(&self) -> Option<Box<dyn TRules>> {
+        match self {
+            VFallbackRules::Ramsch(rulesramsch) => Some(Box::new(rulesramsch.clone())),
+            VFallbackRules::DerAlteMuss{..} => None,
+            VFallbackRules::Kreuzspiel(ruleskreuzspiel) => Some(Box::new(ruleskreuzspiel.clone())),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum EDoublingScope {
     Games,
     GamesAndStock,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EStossDeadline {
+    NoCardPlayed, // stoss only before the first card is played
+    OwnFirstCard, // each player may stoss as long as he has not played a card
+    FirstStich, // stoss allowed until the first stich is complete
+}
+
 #[derive(Clone, new, Debug)]
 pub struct SStossParams {
     pub n_stoss_max : usize,
+    pub estossdeadline : EStossDeadline,
+}
+
+impl SStossParams {
+    pub fn before_deadline(&self, epi: EPlayerIndex, stichseq: &SStichSequence) -> bool {
+        match self.estossdeadline {
+            EStossDeadline::NoCardPlayed => stichseq.no_card_played(),
+            EStossDeadline::OwnFirstCard => stichseq.completed_stichs().is_empty()
+                && stichseq.current_stich().get(epi).is_none(),
+            EStossDeadline::FirstStich => stichseq.completed_stichs().is_empty(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EBockTrigger {
+    KontraLost, // a game with at least one stoss is lost by the active party
+    SechzigSechzig, // both parties have 60 points
+    Durchmarsch, // a ramsch is won by durchmarsch
+}
+
+#[derive(Clone, new, Debug)]
+pub struct SBockParams {
+    pub n_games : usize, // number of games that pay double after a trigger
+    pub vecebocktrigger : Vec<EBockTrigger>,
 }
 
 #[derive(new, Debug, Clone)]
 pub struct SRuleSet {
     pub avecrulegroup : EnumMap<EPlayerIndex, Vec<SRuleGroup>>,
-    pub stockorramsch : VStockOrT</*n_stock*/isize, Box<dyn TRules>>,
+    pub ofallbackrules : Option<VFallbackRules>, // rules played if nobody announces a game
+    pub on_stock_price : Option<isize>, // Some if there is a stock; each player pays the price into it if nobody announces a game and there are no fallback rules
     pub oedoublingscope : Option<EDoublingScope>,
     pub ostossparams : Option<SStossParams>,
+    pub obockparams : Option<SBockParams>,
     pub ekurzlang : EKurzLang,
+    pub str_toml : String, // source the ruleset was read from
 }
 
 pub fn allowed_rules<'retval, 'hand : 'retval, 'rules : 'retval>(vecrulegroup: &'rules [SRuleGroup], hand: SFullHand<'hand>) -> impl Clone + Iterator<Item=Option<&'rules (dyn TActivelyPlayableRules + 'rules)>> + 'retval {
@@ -85,55 +180,144 @@ impl SRuleSet {
             info!("SRuleSet: Did not find {}. Falling back to {}.", str_not_found, str_fallback);
             read_int(&tomltbl, str_fallback)
         };
-        // TODORULES "Der Alte muss"
-        // TODORULES Kreuzspiel as alternative to Ramsch
-        let stockorramsch = match (tomltbl.get("ramsch"), tomltbl.get("stock")) {
-            (Some(_), Some(_)) => {
-                // TODORULES Better alternatives? Allow stock *and* ramsch at the same time?
-                bail!("Currently, having both Ramsch and Stock is not supported.")
-            },
-            (Some(val_ramsch), None) => {
-                let durchmarsch = (match val_ramsch.get("durchmarsch") {
-                    None => Ok(VDurchmarsch::None),
-                    Some(&toml::Value::String(ref str_durchmarsch)) if "all"==str_durchmarsch => {
-                        Ok(VDurchmarsch::All)
-                    },
-                    Some(&toml::Value::Integer(n_durchmarsch)) if (61..=120).contains(&n_durchmarsch) => {
-                        Ok(VDurchmarsch::AtLeast(n_durchmarsch.as_num()))
-                    },
-                    _ => bail!("Invalid value for ramsch.durchmarsch. \"All\" or a number in [61; 120] is supported.")
-                } as Result<_, Error>)?;
-                read_int(val_ramsch, "price").map(|n_price|
-                    VStockOrT::OrT(Box::new(
-                        SRulesRamsch::new(n_price.as_num(), durchmarsch)
-                    ) as Box<dyn TRules>)
-                )
-            },
-            (None, Some(val_stock)) => {
-                read_int(val_stock, "price").or_else(|_err| fallback("stock.price", "base-price")).map(|n_price| VStockOrT::Stock(n_price.as_num()))
-            },
-            (None, None) => {
-                Ok(VStockOrT::Stock(0)) // represent "no stock" by using a zero stock payment
+        let read_payoutparams = |tomlval_game: &toml::Value, str_rule_name_file: &str, str_base_price_fallback: &str| -> Result<SPayoutDeciderParams, Error> {
+            let n_payout_extra = read_int(tomlval_game, "extra").or_else(|_err| fallback(&format!("{}.extra", str_rule_name_file), "base-price"))?;
+            let n_payout_base = read_int(tomlval_game, "price").or_else(|_err| fallback(&format!("{}.price", str_rule_name_file), str_base_price_fallback))?;
+            let n_lauf_lbound = read_int(tomlval_game, "lauf-min").or_else(|_err| fallback(&format!("{}.lauf-min", str_rule_name_file), "lauf-min"))?;
+            Ok(SPayoutDeciderParams::new(
+                n_payout_base.as_num(),
+                /*n_payout_schneider_schwarz*/n_payout_extra.as_num(),
+                SLaufendeParams::new(
+                    /*n_payout_per_lauf*/n_payout_extra.as_num(),
+                    n_lauf_lbound.as_num(),
+                ),
+            ))
+        };
+        let read_toutsieparams = |str_rule_name_file: &str| {
+            let read_bool = |str_key: &str, b_default: bool| {
+                tomltbl.get(str_rule_name_file)
+                    .and_then(|tomlval_game| tomlval_game.get(str_key))
+                    .and_then(|tomlval| tomlval.as_bool())
+                    .unwrap_or(b_default)
+            };
+            SToutSieParams::new(
+                /*b_laufende*/read_bool("tout-sie-laufende", true),
+                /*b_schneider_schwarz*/read_bool("tout-sie-schneider-schwarz", false),
+            )
+        };
+        let read_schneiderschwarzannouncement = |str_rule_name_file: &str, payoutparams: &SPayoutDeciderParams| {
+            let otomlval_game = tomltbl.get(str_rule_name_file);
+            SSchneiderSchwarzAnnouncement {
+                on_payout_announced: if_then_some!(
+                    Some(true)==otomlval_game
+                        .and_then(|tomlval_game| tomlval_game.get("announce-schneider-schwarz"))
+                        .and_then(|tomlval| tomlval.as_bool()),
+                    otomlval_game
+                        .and_then(|tomlval_game| read_int(tomlval_game, "announce-schneider-schwarz-price").ok())
+                        .map_or(payoutparams.n_payout_schneider_schwarz, |n_payout_announced| n_payout_announced.as_num())
+                ),
+                oeschneiderschwarz: None,
+            }
+        };
+        let ekurzlang = {
+            let ekurzlang_deck = match tomltbl.get("deck").and_then(|tomlval_kurzlang| tomlval_kurzlang.as_str()) {
+                Some("kurz") => EKurzLang::Kurz,
+                None | Some("lang") => EKurzLang::Lang,
+                Some(str_kurzlang) => {
+                    info!("SRuleSet: {} is not a valid value for 'deck' (supported values: kurz, lang). Defaulting to 'lang'", str_kurzlang);
+                    EKurzLang::Lang
+                },
+            };
+            match tomltbl.get("players").and_then(|tomlval| tomlval.as_integer()) {
+                None | Some(4) => ekurzlang_deck,
+                Some(3) if EKurzLang::Lang==ekurzlang_deck => EKurzLang::Dreier,
+                Some(3) => bail!("players = 3 requires deck = \"lang\"."),
+                Some(n_players) => bail!("Invalid value {} for players. 3 or 4 is supported.", n_players),
+            }
+        };
+        if ekurzlang.n_players()!=EPlayerIndex::SIZE {
+            for str_rule_name_file in ["rufspiel", "hochzeit", "kreuzspiel", "der-alte-muss"] {
+                if tomltbl.get(str_rule_name_file).is_some() {
+                    bail!("{} requires four players.", str_rule_name_file);
+                }
+            }
+        }
+        let mut vecfallbackrules = Vec::new();
+        if let Some(val_ramsch) = tomltbl.get("ramsch") {
+            let durchmarsch = (match val_ramsch.get("durchmarsch") {
+                None => Ok(VDurchmarsch::None),
+                Some(&toml::Value::String(ref str_durchmarsch)) if "all"==str_durchmarsch => {
+                    Ok(VDurchmarsch::All)
+                },
+                Some(&toml::Value::Integer(n_durchmarsch)) if (61..=120).contains(&n_durchmarsch) => {
+                    Ok(VDurchmarsch::AtLeast(n_durchmarsch.as_num()))
+                },
+                _ => bail!("Invalid value for ramsch.durchmarsch. \"All\" or a number in [61; 120] is supported.")
+            } as Result<_, Error>)?;
+            let ejungfrau = (match val_ramsch.get("jungfrau").map(|tomlval| tomlval.as_str()) {
+                None => Ok(EJungfrau::Ignore),
+                Some(Some("once")) => Ok(EJungfrau::DoubleOnce),
+                Some(Some("each")) => Ok(EJungfrau::DoubleEach),
+                _ => bail!("Invalid value for ramsch.jungfrau. \"once\" or \"each\" is supported.")
+            } as Result<_, Error>)?;
+            let b_schieben = Some(true) == val_ramsch.get("schieben").and_then(|tomlval| tomlval.as_bool());
+            if b_schieben && tomltbl.get("stoss").is_none() {
+                bail!("ramsch.schieben requires [stoss]."); // schieben is represented as stoss
+            }
+            let b_loser_pays_into_stock = Some(true) == val_ramsch.get("loser-pays-into-stock").and_then(|tomlval| tomlval.as_bool());
+            let n_price = read_int(val_ramsch, "price")?;
+            if b_loser_pays_into_stock {
+                if tomltbl.get("stock").is_none() {
+                    bail!("ramsch.loser-pays-into-stock requires [stock].");
+                }
+                if 0!=n_price%2 {
+                    bail!("ramsch.price must be even if the loser pays into the stock (so that the stock can always be split in halves).");
+                }
             }
-        }?;
+            vecfallbackrules.push(VFallbackRules::Ramsch(SRulesRamsch::new(
+                n_price.as_num(),
+                durchmarsch,
+                ejungfrau,
+                b_schieben,
+                b_loser_pays_into_stock,
+            )));
+        }
+        if let Some(val_deraltemuss) = tomltbl.get("der-alte-muss") {
+            let payoutparams_rufspiel = read_payoutparams(val_deraltemuss, "der-alte-muss", "base-price")?;
+            let n_price_solo = read_int(val_deraltemuss, "solo-price").or_else(|_err| fallback("der-alte-muss.solo-price", "solo-price"))?;
+            vecfallbackrules.push(VFallbackRules::DerAlteMuss{
+                payoutparams_solo: SPayoutDeciderParams{
+                    n_payout_base: n_price_solo.as_num(),
+                    ..payoutparams_rufspiel.clone()
+                },
+                payoutparams_rufspiel,
+            });
+        }
+        if let Some(val_kreuzspiel) = tomltbl.get("kreuzspiel") {
+            vecfallbackrules.push(VFallbackRules::Kreuzspiel(SRulesKreuzspiel::new(
+                read_payoutparams(val_kreuzspiel, "kreuzspiel", "base-price")?
+            )));
+        }
+        if 1<vecfallbackrules.len() {
+            bail!("At most one of ramsch, der-alte-muss and kreuzspiel is supported.");
+        }
+        let ofallbackrules = vecfallbackrules.pop();
+        let on_stock_price = tomltbl.get("stock")
+            .map(|val_stock| -> Result<isize, Error> {
+                if ofallbackrules.is_some() && val_stock.get("price").is_some() {
+                    info!("SRuleSet: stock.price is only paid if there are no fallback rules.");
+                }
+                Ok(read_int(val_stock, "price").or_else(|_err| fallback("stock.price", "base-price"))?.as_num())
+            })
+            .transpose()?;
         let mut avecrulegroup = EPlayerIndex::map_from_fn(|_epi| Vec::new());
         for epi in EPlayerIndex::values() {
             let vecrulegroup = &mut avecrulegroup[epi];
             macro_rules! create_rulegroup {($str_rule_name_file: expr, $str_base_price_fallback: expr, $str_group_name: expr, $fn_rules: expr) => {
                 if let Some(tomlval_game) = tomltbl.get($str_rule_name_file) {
-                    let n_payout_extra = read_int(tomlval_game, "extra").or_else(|_err| fallback(&format!("{}.extra", $str_rule_name_file), "base-price"))?;
-                    let n_payout_base = read_int(tomlval_game, "price").or_else(|_err| fallback(&format!("{}.price", $str_rule_name_file), $str_base_price_fallback))?;
-                    let n_lauf_lbound = read_int(tomlval_game, "lauf-min").or_else(|_err| fallback(&format!("{}.lauf-min", $str_rule_name_file), "lauf-min"))?;
                     Ok(vecrulegroup.push(SRuleGroup{
                         str_name: $str_group_name.to_string(),
-                        vecorules: ($fn_rules(SPayoutDeciderParams::new(
-                            n_payout_base.as_num(),
-                            /*n_payout_schneider_schwarz*/n_payout_extra.as_num(),
-                            SLaufendeParams::new(
-                                /*n_payout_per_lauf*/n_payout_extra.as_num(),
-                                n_lauf_lbound.as_num(),
-                            ),
-                        ))),
+                        vecorules: ($fn_rules(read_payoutparams(tomlval_game, $str_rule_name_file, $str_base_price_fallback)?)),
                     })) as Result<_, Error>
                 } else {
                     Ok(())
@@ -161,12 +345,20 @@ impl SRuleSet {
                         .collect()
                 }
             )?;
-            macro_rules! read_sololike {($payoutdecider: ty, $fn_prio: expr, $str_rulename_suffix: expr) => {{
-                type PayoutDecider = $payoutdecider;
+            create_rulegroup!(
+                "hochzeit",
+                "base-price",
+                "Hochzeit",
+                |payoutparams| vec![Some(Box::new(SRulesHochzeit::new(
+                    epi,
+                    payoutparams,
+                )) as Box<dyn TActivelyPlayableRules>)]
+            )?;
+            macro_rules! read_sololike {($fn_payoutdecider: expr, $fn_prio: expr, $str_rulename_suffix: expr) => {{
                 let internal_rulename = |str_rulename| {
                     format!("{}{}", str_rulename, $str_rulename_suffix)
                 };
-                macro_rules! vecrules{($itoefarbe: expr, $esololike: expr, $i_prioindex: expr) => {
+                macro_rules! vecrules{($str_rule_name_file: expr, $itoefarbe: expr, $esololike: expr, $i_prioindex: expr) => {
                     |payoutparams: SPayoutDeciderParams| {
                         $itoefarbe
                             .map(|oefarbe| {
@@ -174,7 +366,7 @@ impl SRuleSet {
                                     epi,
                                     oefarbe,
                                     $esololike,
-                                    PayoutDecider::new(payoutparams.clone(), $i_prioindex),
+                                    ($fn_payoutdecider)(payoutparams.clone(), $i_prioindex, $str_rule_name_file),
                                 ))
                             })
                             .collect()
@@ -183,27 +375,27 @@ impl SRuleSet {
                 create_rulegroup_sololike!(
                     "solo",
                     &internal_rulename("Solo"),
-                    vecrules!(EFarbe::values(), ESoloLike::Solo, $fn_prio(0))
+                    vecrules!("solo", EFarbe::values(), ESoloLike::Solo, $fn_prio(0))
                 )?;
                 create_rulegroup_sololike!(
                     "wenz",
                     &internal_rulename("Wenz"),
-                    vecrules!(std::iter::once(None), ESoloLike::Wenz, $fn_prio(-1))
+                    vecrules!("wenz", std::iter::once(None), ESoloLike::Wenz, $fn_prio(-10))
                 )?;
                 create_rulegroup_sololike!(
                     "farbwenz",
                     &internal_rulename("Farbwenz"),
-                    vecrules!(EFarbe::values(), ESoloLike::Wenz, $fn_prio(-2))
+                    vecrules!("farbwenz", EFarbe::values(), ESoloLike::Wenz, $fn_prio(-20))
                 )?;
                 create_rulegroup_sololike!(
                     "geier",
                     &internal_rulename("Geier"),
-                    vecrules!(std::iter::once(None), ESoloLike::Geier, $fn_prio(-3))
+                    vecrules!("geier", std::iter::once(None), ESoloLike::Geier, $fn_prio(-30))
                 )?;
                 create_rulegroup_sololike!(
                     "farbgeier",
                     &internal_rulename("Farbgeier"),
-                    vecrules!(EFarbe::values(), ESoloLike::Geier, $fn_prio(-4))
+                    vecrules!("farbgeier", EFarbe::values(), ESoloLike::Geier, $fn_prio(-40))
                 )?;
             }}}
             if let Some(tomlval_steigern) = tomltbl.get("steigern") {
@@ -218,49 +410,92 @@ impl SRuleSet {
                     info!("SRuleSet: steigern.steps not specified");
                     10
                 };
-                read_sololike!(SPayoutDeciderPointBased<VGameAnnouncementPrioritySoloLike>, |_i_prio| VGameAnnouncementPrioritySoloLike::SoloSteigern{n_points_to_win: 61, n_step}, "");
+                read_sololike!(
+                    |payoutparams: SPayoutDeciderParams, prio, str_rule_name_file| SPayoutDeciderPointBased{
+                        schneiderschwarzannouncement: read_schneiderschwarzannouncement(str_rule_name_file, &payoutparams),
+                        ..SPayoutDeciderPointBased::new(payoutparams, prio)
+                    },
+                    |_i_prio| VGameAnnouncementPrioritySoloLike::SoloSteigern{n_points_to_win: 61, n_step},
+                    ""
+                );
             } else {
-                read_sololike!(SPayoutDeciderPointBased<VGameAnnouncementPrioritySoloLike>, VGameAnnouncementPrioritySoloLike::SoloSimple, "");
+                read_sololike!(
+                    |payoutparams: SPayoutDeciderParams, prio, str_rule_name_file| SPayoutDeciderPointBased{
+                        schneiderschwarzannouncement: read_schneiderschwarzannouncement(str_rule_name_file, &payoutparams),
+                        ..SPayoutDeciderPointBased::new(payoutparams, prio)
+                    },
+                    VGameAnnouncementPrioritySoloLike::SoloSimple,
+                    ""
+                );
             }
-            read_sololike!(SPayoutDeciderTout, |x|x, " Tout");
-            create_rulegroup_sololike!(
-                "solo",
-                "Sie",
-                &|payoutparams| vec![Some(sololike(
-                    epi,
-                    /*oefarbe*/None,
-                    ESoloLike::Solo,
-                    SPayoutDeciderSie::new(payoutparams),
-                ))]
-            )?;
-            { // Bettel
-                let str_rule_name_file = "bettel";
+            read_sololike!(
+                |payoutparams, i_prio, str_rule_name_file| SPayoutDeciderTout::new(payoutparams, i_prio, read_toutsieparams(str_rule_name_file)),
+                |x|x,
+                " Tout"
+            );
+            macro_rules! create_rulegroup_sie {($str_rule_name_file: expr, $str_group_name: expr, $itoefarbe: expr, $esololike: expr) => {
+                create_rulegroup_sololike!(
+                    $str_rule_name_file,
+                    $str_group_name,
+                    &|payoutparams: SPayoutDeciderParams| {
+                        $itoefarbe
+                            .map(|oefarbe| Some(sololike(
+                                epi,
+                                oefarbe,
+                                $esololike,
+                                SPayoutDeciderSie::new(payoutparams.clone(), read_toutsieparams($str_rule_name_file)),
+                            )))
+                            .collect()
+                    }
+                )
+            }}
+            create_rulegroup_sie!("solo", "Sie", std::iter::once(None), ESoloLike::Solo)?;
+            create_rulegroup_sie!("wenz", "Wenz Sie", std::iter::once(None), ESoloLike::Wenz)?;
+            create_rulegroup_sie!("farbwenz", "Farbwenz Sie", EFarbe::values(), ESoloLike::Wenz)?;
+            create_rulegroup_sie!("geier", "Geier Sie", std::iter::once(None), ESoloLike::Geier)?;
+            create_rulegroup_sie!("farbgeier", "Farbgeier Sie", EFarbe::values(), ESoloLike::Geier)?;
+            for (str_rule_name_file, str_group_name, b_brett) in [
+                ("bettel", "Bettel", /*b_brett*/false),
+                ("bettel-brett", "Bettel Brett", /*b_brett*/true),
+            ] {
                 if let Some(tomlval_bettel) = tomltbl.get(str_rule_name_file) {
                     let n_payout_base = read_int(tomlval_bettel, "price")
                         .or_else(|_err| 
                             fallback(&format!("{}.price", str_rule_name_file), /*str_base_price_fallback*/"base-price")
                         )?;
-                    fn push_bettel<BettelAllAllowedCardsWithinStich: TBettelAllAllowedCardsWithinStich>(vecrulegroup: &mut Vec<SRuleGroup>, epi: EPlayerIndex, n_payout_base: isize) {
+                    // sololike priorities are 0 (Solo), -10 (Wenz), -20 (Farbwenz), -30 (Geier), -40 (Farbgeier)
+                    let prio = match tomlval_bettel.get("priority").and_then(|tomlval| tomlval.as_str()) {
+                        None | Some("lowest") => VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(-999_999)),
+                        Some("above-wenz") => VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(-5)),
+                        Some("above-solo") => VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(1)),
+                        Some("above-tout") => VGameAnnouncementPriority::SoloTout(1),
+                        Some(str_priority) => {
+                            bail!("{} is not a valid value for '{}.priority' (supported values: lowest, above-wenz, above-solo, above-tout)", str_priority, str_rule_name_file);
+                        },
+                    };
+                    fn push_bettel<BettelAllAllowedCardsWithinStich: TBettelAllAllowedCardsWithinStich>(vecrulegroup: &mut Vec<SRuleGroup>, str_group_name: &str, epi: EPlayerIndex, prio: VGameAnnouncementPriority, n_payout_base: isize, b_brett: bool) {
                         vecrulegroup.push(SRuleGroup{
-                            str_name: "Bettel".to_string(),
+                            str_name: str_group_name.to_string(),
                             vecorules: vec![Some(Box::new(SRulesBettel::<BettelAllAllowedCardsWithinStich>::new(
                                 epi,
-                                /*i_prio, large negative number to make less important than any sololike*/-999_999,
-                                n_payout_base.as_num::<isize>(),
+                                prio,
+                                n_payout_base,
+                                b_brett,
                             )) as Box<dyn TActivelyPlayableRules>)],
                         });
                     }
                     if Some(true) == tomlval_bettel.get("stichzwang").and_then(|tomlval| tomlval.as_bool()) {
-                        push_bettel::<SBettelAllAllowedCardsWithinStichStichzwang>(vecrulegroup, epi, n_payout_base.as_num::<isize>());
+                        push_bettel::<SBettelAllAllowedCardsWithinStichStichzwang>(vecrulegroup, str_group_name, epi, prio, n_payout_base.as_num::<isize>(), b_brett);
                     } else {
-                        push_bettel::<SBettelAllAllowedCardsWithinStichNormal>(vecrulegroup, epi, n_payout_base.as_num::<isize>());
+                        push_bettel::<SBettelAllAllowedCardsWithinStichNormal>(vecrulegroup, str_group_name, epi, prio, n_payout_base.as_num::<isize>(), b_brett);
                     }
                 }
             }
         }
         Ok(SRuleSet::new(
             avecrulegroup,
-            stockorramsch,
+            ofallbackrules,
+            on_stock_price,
             tomltbl.get("doubling").map(|tomlval_doubling | {
                 if let Some(str_doubling_stock)=tomlval_doubling.get("stock").and_then(|tomlval| tomlval.as_str()) {
                     if "yes"==str_doubling_stock {
@@ -288,17 +523,56 @@ impl SRuleSet {
                             } else {
                                 n_stoss_max.as_num::<usize>()
                             }
-                        })
+                        }),
+                    match tomlval_stoss.get("deadline").and_then(|tomlval| tomlval.as_str()) {
+                        None | Some("no-card-played") => EStossDeadline::NoCardPlayed,
+                        Some("own-first-card") => EStossDeadline::OwnFirstCard,
+                        Some("first-stich") => EStossDeadline::FirstStich,
+                        Some(str_deadline) => {
+                            info!("SRuleSet: {} is not a valid value for 'stoss.deadline' (supported values: no-card-played, own-first-card, first-stich). Defaulting to 'no-card-played'", str_deadline);
+                            EStossDeadline::NoCardPlayed
+                        },
+                    },
                 )
             }),
-            match tomltbl.get("deck").and_then(|tomlval_kurzlang| tomlval_kurzlang.as_str()) {
-                Some("kurz") => EKurzLang::Kurz,
-                None | Some("lang") => EKurzLang::Lang,
-                Some(str_kurzlang) => {
-                    info!("SRuleSet: {} is not a valid value for 'deck' (supported values: kurz, lang). Defaulting to 'lang'", str_kurzlang);
-                    EKurzLang::Lang
-                },
-            },
+            tomltbl.get("bock").map(|tomlval_bock| {
+                let n_rounds_default = 1;
+                let n_rounds = tomlval_bock.get("rounds")
+                    .and_then(|tomlval| tomlval.as_integer())
+                    .map_or(n_rounds_default, |n_rounds| {
+                        if n_rounds<=0 {
+                            info!("SRuleSet: bock.rounds less than 1. Defaulting to {}.", n_rounds_default);
+                            n_rounds_default
+                        } else {
+                            n_rounds.as_num::<usize>()
+                        }
+                    });
+                let vecebocktrigger = tomlval_bock.get("triggers")
+                    .and_then(|tomlval| tomlval.as_array())
+                    .map_or_else(
+                        || {
+                            info!("SRuleSet: bock.triggers not specified. Defaulting to all triggers.");
+                            vec![EBockTrigger::KontraLost, EBockTrigger::SechzigSechzig, EBockTrigger::Durchmarsch]
+                        },
+                        |slctomlval_trigger| slctomlval_trigger.iter()
+                            .filter_map(|tomlval_trigger| match tomlval_trigger.as_str() {
+                                Some("kontra-lost") => Some(EBockTrigger::KontraLost),
+                                Some("60-60") => Some(EBockTrigger::SechzigSechzig),
+                                Some("durchmarsch") => Some(EBockTrigger::Durchmarsch),
+                                _ => {
+                                    info!("SRuleSet: {} is not a valid value for 'bock.triggers' (supported values: kontra-lost, 60-60, durchmarsch). Ignoring it.", tomlval_trigger);
+                                    None
+                                },
+                            })
+                            .collect()
+                    );
+                SBockParams::new(
+                    /*n_games*/n_rounds * ekurzlang.n_players(), // a round consists of one game dealt by each player
+                    vecebocktrigger,
+                )
+            }),
+            ekurzlang,
+            str_toml.to_string(),
         ))
     }
 
@@ -306,5 +580,12 @@ impl SRuleSet {
         // TODO? ruleset creation wizard
         Self::from_string(&via_out_param_result(|str_toml| File::open(&path)?.read_to_string(str_toml))?.0)
     }
+
+    #[cfg(test)]
+    pub fn announceable_rules(&self, epi: EPlayerIndex) -> impl Iterator<Item=Box<dyn TRules>> + '_ {
+        self.avecrulegroup[epi].iter()
+            .flat_map(|rulegroup| rulegroup.vecorules.iter())
+            .filter_map(|orules| orules.as_ref().map(|rules| rules.upcast().box_clone()))
+    }
 }
 
@@ -0,0 +1,160 @@
+use crate::primitives::*;
+use crate::rules::{payoutdecider::*, rulesrufspiel::STrumpfDeciderRufspiel, trumpfdecider::*, *};
+use crate::util::*;
+use std::{cmp::Ordering, fmt};
+
+// partner must be found within the first stichs, otherwise the hochzeit player plays alone
+const N_STICH_FIND_PARTNER : usize = 3;
+
+#[derive(Clone, Debug)]
+pub struct SRulesHochzeit {
+    epi : EPlayerIndex,
+    payoutdecider: SPayoutDeciderPointBased<SPointsToWin61>,
+}
+
+impl fmt::Display for SRulesHochzeit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hochzeit")
+    }
+}
+
+impl SRulesHochzeit {
+    pub fn new(epi: EPlayerIndex, payoutparams: SPayoutDeciderParams) -> SRulesHochzeit {
+        SRulesHochzeit {
+            epi,
+            payoutdecider: SPayoutDeciderPointBased::new(payoutparams, SPointsToWin61{}),
+        }
+    }
+
+    // partner (None if there is none) and number of stichs needed to find out, if already known
+    fn partner<'stich>(&self, itstichepi_winner: impl Iterator<Item=(&'stich SStich, EPlayerIndex)>) -> Option<(Option<EPlayerIndex>, usize)> {
+        let mut n_stich = 0;
+        for (stich, epi_winner) in itstichepi_winner.take(N_STICH_FIND_PARTNER) {
+            n_stich += 1;
+            if !self.trumpforfarbe(*stich.first()).is_trumpf() && epi_winner!=self.epi {
+                return Some((Some(epi_winner), n_stich));
+            }
+        }
+        if_then_some!(N_STICH_FIND_PARTNER==n_stich,
+            (/*oepi_partner*/None, n_stich) // no partner found
+        )
+    }
+
+    fn playerparties<'stich>(&self, itstichepi_winner: impl Iterator<Item=(&'stich SStich, EPlayerIndex)>) -> Option<SPlayerPartiesHochzeit> {
+        self.partner(itstichepi_winner)
+            .map(|(oepi_partner, _n_stich)| SPlayerPartiesHochzeit{epi: self.epi, oepi_partner})
+    }
+}
+
+impl TActivelyPlayableRules for SRulesHochzeit {
+    fn priority(&self) -> VGameAnnouncementPriority {
+        VGameAnnouncementPriority::RufspielLike
+    }
+}
+
+impl TRulesNoObj for SRulesHochzeit {
+    impl_rules_trumpf_noobj!(STrumpfDeciderRufspiel);
+}
+
+#[derive(Debug)]
+struct SPlayerPartiesHochzeit {
+    epi: EPlayerIndex,
+    oepi_partner: Option<EPlayerIndex>,
+}
+
+impl SPlayerPartiesHochzeit {
+    fn stockaction(&self, epi: EPlayerIndex, n_payout_no_stock: isize) -> EStockAction {
+        if self.oepi_partner.is_some() && self.is_primary_party(epi) {
+            if /*b_player_party_wins*/0<n_payout_no_stock {
+                EStockAction::TakeHalf
+            } else {
+                EStockAction::GiveHalf
+            }
+        } else {
+            EStockAction::Ignore // playing alone, the hochzeit player ignores the stock like other single-player games
+        }
+    }
+}
+
+impl TPlayerParties for SPlayerPartiesHochzeit {
+    fn is_primary_party(&self, epi: EPlayerIndex) -> bool {
+        self.epi==epi || self.oepi_partner==Some(epi)
+    }
+    fn multiplier(&self, epi: EPlayerIndex) -> isize {
+        if self.oepi_partner.is_none() && self.epi==epi {3} else {1}
+    }
+}
+
+impl TRules for SRulesHochzeit {
+    impl_rules_trumpf!();
+
+    fn can_be_played(&self, hand: SFullHand) -> bool {
+        1==hand.get().cards().iter()
+            .filter(|&card| self.trumpforfarbe(*card).is_trumpf())
+            .count()
+    }
+
+    fn playerindex(&self) -> Option<EPlayerIndex> {
+        Some(self.epi)
+    }
+
+    fn finished_playerparties(&self, gamefinishedstiche: SStichSequenceGameFinished) -> Option<Box<dyn TPlayerParties>> {
+        Some(Box::new(unwrap!(self.playerparties(gamefinishedstiche.get().completed_stichs_winner_index(self)))))
+    }
+
+    fn ruleparams(&self) -> SRuleParams {
+        self.payoutdecider.payoutparams.ruleparams(/*itruleparam*/[])
+    }
+
+    fn stoss_allowed(&self, epi: EPlayerIndex, vecstoss: &[SStoss], hand: &SHand) -> bool {
+        EKurzLang::from_cards_per_player(hand.cards().len());
+        (epi==self.epi)==(vecstoss.len()%2==1)
+    }
+
+    fn payoutinfos(&self, gamefinishedstiche: SStichSequenceGameFinished, rulestatecache: &SRuleStateCache) -> EnumMap<EPlayerIndex, SPayoutInfo> {
+        let playerparties = unwrap!(self.playerparties(gamefinishedstiche.get().completed_stichs_winner_index(self)));
+        let an_payout_no_stock = &self.payoutdecider.payout(
+            self,
+            rulestatecache,
+            gamefinishedstiche,
+            &playerparties,
+        );
+        assert!(an_payout_no_stock.iter().all(|n_payout_no_stock| 0!=*n_payout_no_stock));
+        EPlayerIndex::map_from_fn(|epi|
+            SPayoutInfo::new(
+                an_payout_no_stock[epi],
+                playerparties.stockaction(epi, an_payout_no_stock[epi]),
+            )
+        )
+    }
+
+    fn card_exchange(&self, stichseq: &SStichSequence) -> Option<(usize, EPlayerIndex, EPlayerIndex)> {
+        // as soon as the partner is found, the hochzeit player gives his trumpf to the partner, who returns another card
+        self.partner(stichseq.completed_stichs_winner_index(self))
+            .and_then(|(oepi_partner, n_stich)| oepi_partner.map(|epi_partner| (n_stich, self.epi, epi_partner)))
+    }
+
+    fn snapshot_cache_discriminator(&self, stichseq: &SStichSequence) -> usize {
+        // player parties depend on the order of the first stichs
+        match self.playerparties(stichseq.completed_stichs_winner_index(self)) {
+            None => 0,
+            Some(SPlayerPartiesHochzeit{epi: _, oepi_partner: None}) => 1,
+            Some(SPlayerPartiesHochzeit{epi: _, oepi_partner: Some(epi_partner)}) => 2 + epi_partner.to_usize(),
+        }
+    }
+
+    fn payouthints(&self, stichseq: &SStichSequence, ahand: &EnumMap<EPlayerIndex, SHand>, rulestatecache: &SRuleStateCache) -> EnumMap<EPlayerIndex, SPayoutHint> {
+        if let Some(playerparties) = self.playerparties(stichseq.completed_stichs_winner_index(self)) {
+            let mapepipairon_payout = self.payoutdecider.payouthints(self, stichseq, ahand, rulestatecache, &playerparties);
+            EPlayerIndex::map_from_fn(|epi| {
+                let payoutinfo = |n_payout| SPayoutInfo::new(n_payout, playerparties.stockaction(epi, n_payout));
+                SPayoutHint::new((
+                    mapepipairon_payout[epi].0.map(payoutinfo),
+                    mapepipairon_payout[epi].1.map(payoutinfo),
+                ))
+            })
+        } else {
+            EPlayerIndex::map_from_fn(|_epi| SPayoutHint::new((None, None)))
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::primitives::*;
+use crate::rules::{payoutdecider::*, rulesrufspiel::STrumpfDeciderRufspiel, trumpfdecider::*, *};
+use crate::util::*;
+use std::{cmp::Ordering, fmt};
+
+// Kreuzspiel: players sitting opposite each other play together, the party of the first player needs 61 points
+#[derive(Clone, Debug)]
+pub struct SRulesKreuzspiel {
+    payoutdecider: SPayoutDeciderPointBased<SPointsToWin61>,
+}
+
+impl fmt::Display for SRulesKreuzspiel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Kreuzspiel")
+    }
+}
+
+impl SRulesKreuzspiel {
+    pub fn new(payoutparams: SPayoutDeciderParams) -> SRulesKreuzspiel {
+        SRulesKreuzspiel {
+            payoutdecider: SPayoutDeciderPointBased::new(payoutparams, SPointsToWin61{}),
+        }
+    }
+}
+
+impl TRulesNoObj for SRulesKreuzspiel {
+    impl_rules_trumpf_noobj!(STrumpfDeciderRufspiel);
+}
+
+struct SPlayerPartiesKreuz;
+
+impl TPlayerParties for SPlayerPartiesKreuz {
+    fn is_primary_party(&self, epi: EPlayerIndex) -> bool {
+        EPlayerIndex::EPI0==epi || EPlayerIndex::EPI2==epi
+    }
+    fn multiplier(&self, _epi: EPlayerIndex) -> isize {
+        1
+    }
+}
+
+impl TRules for SRulesKreuzspiel {
+    impl_rules_trumpf!();
+
+    fn stoss_allowed(&self, epi: EPlayerIndex, vecstoss: &[SStoss], hand: &SHand) -> bool {
+        EKurzLang::from_cards_per_player(hand.cards().len());
+        SPlayerPartiesKreuz.is_primary_party(epi) == (vecstoss.len()%2==1)
+    }
+
+    fn playerindex(&self) -> Option<EPlayerIndex> {
+        None
+    }
+
+    fn finished_playerparties(&self, _gamefinishedstiche: SStichSequenceGameFinished) -> Option<Box<dyn TPlayerParties>> {
+        Some(Box::new(SPlayerPartiesKreuz))
+    }
+
+    fn ruleparams(&self) -> SRuleParams {
+        self.payoutdecider.payoutparams.ruleparams(/*itruleparam*/[])
+    }
+
+    fn payoutinfos(&self, gamefinishedstiche: SStichSequenceGameFinished, rulestatecache: &SRuleStateCache) -> EnumMap<EPlayerIndex, SPayoutInfo> {
+        self.payoutdecider.payout(
+            self,
+            rulestatecache,
+            gamefinishedstiche,
+            &SPlayerPartiesKreuz,
+        )
+            .map(|n_payout| SPayoutInfo::new(*n_payout, EStockAction::Ignore))
+    }
+
+    fn payouthints(&self, stichseq: &SStichSequence, ahand: &EnumMap<EPlayerIndex, SHand>, rulestatecache: &SRuleStateCache) -> EnumMap<EPlayerIndex, SPayoutHint> {
+        self.payoutdecider.payouthints(self, stichseq, ahand, rulestatecache, &SPlayerPartiesKreuz)
+            .map(|pairon_payout| SPayoutHint::new((
+                pairon_payout.0.map(|n_payout| SPayoutInfo::new(n_payout, EStockAction::Ignore)),
+                pairon_payout.1.map(|n_payout| SPayoutInfo::new(n_payout, EStockAction::Ignore)),
+            )))
+    }
+}
@@ -11,12 +11,38 @@ pub enum VDurchmarsch {
     AtLeast(isize),
 }
 
-// TODO add Jungfrau (needed for Sauspiel analysis, in particular)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EJungfrau {
+    Ignore,
+    DoubleOnce, // loser pays double, no matter how many players did not win a stich
+    DoubleEach, // loser's payment is doubled for each player who did not win a stich
+}
 
 #[derive(new, Clone, Debug)]
 pub struct SRulesRamsch {
     n_price : isize,
     durchmarsch : VDurchmarsch,
+    ejungfrau : EJungfrau,
+    b_schieben : bool, // represented as stoss: each player may double once before the game starts
+    b_loser_pays_into_stock : bool, // loser does not pay the other players, but into the stock
+}
+
+impl SRulesRamsch {
+    #[cfg(test)]
+    pub fn loser_pays_into_stock(&self) -> bool {
+        self.b_loser_pays_into_stock
+    }
+
+    fn is_durchmarsch(&self, n_points_max: isize, fn_single_player_won_all_stichs: impl FnOnce()->bool) -> bool {
+        match self.durchmarsch {
+            VDurchmarsch::All if 61<=n_points_max => fn_single_player_won_all_stichs(), // not necessarily 120 points if cards remain in the stock
+            VDurchmarsch::All | VDurchmarsch::None => false,
+            VDurchmarsch::AtLeast(n_points_durchmarsch) => {
+                assert!(n_points_durchmarsch>=61); // otherwise, it may not be clear who is the durchmarsch winner
+                n_points_max>=n_points_durchmarsch
+            },
+        }
+    }
 }
 
 impl fmt::Display for SRulesRamsch {
@@ -37,17 +63,49 @@ impl TRulesNoObj for SRulesRamsch {
 impl TRules for SRulesRamsch {
     impl_rules_trumpf!();
 
-    fn stoss_allowed(&self, _epi: EPlayerIndex, vecstoss: &[SStoss], hand: &SHand) -> bool {
-        assert!(vecstoss.is_empty());
+    fn stoss_allowed(&self, epi: EPlayerIndex, vecstoss: &[SStoss], hand: &SHand) -> bool {
+        assert!(self.b_schieben || vecstoss.is_empty());
         EKurzLang::from_cards_per_player(hand.cards().len());
-        false
+        self.b_schieben && vecstoss.iter().all(|stoss| stoss.epi!=epi)
     }
 
     fn playerindex(&self) -> Option<EPlayerIndex> {
         None
     }
 
+    fn finished_playerparties(&self, _gamefinishedstiche: SStichSequenceGameFinished) -> Option<Box<dyn TPlayerParties>> {
+        None // everyone plays for himself
+    }
+
+    fn ruleparams(&self) -> SRuleParams {
+        SRuleParams {
+            on_payout_extra: None,
+            n_payout_base: self.n_price,
+            vecruleparam: vec![
+                VRuleParam::Durchmarsch(self.durchmarsch.clone()),
+                VRuleParam::Jungfrau(self.ejungfrau),
+                VRuleParam::Schieben(self.b_schieben),
+                VRuleParam::LoserPaysIntoStock(self.b_loser_pays_into_stock),
+            ],
+        }
+    }
+
+    fn durchmarsch(&self, gamefinishedstiche: SStichSequenceGameFinished) -> bool {
+        let an_points = gamefinishedstiche.get().completed_stichs_winner_index(self)
+            .fold(
+                EPlayerIndex::map_from_fn(|_epi| 0),
+                mutate_return!(|an_points_accu, (stich, epi_winner)| {
+                    an_points_accu[epi_winner] += points_stich(stich);
+                })
+            );
+        let epi_most_points = unwrap!(EPlayerIndex::values().max_by_key(|epi| an_points[*epi]));
+        self.is_durchmarsch(an_points[epi_most_points], || {
+            gamefinishedstiche.get().completed_stichs_winner_index(self).all(|(_stich, epi_winner)| epi_winner==epi_most_points)
+        })
+    }
+
     fn payoutinfos(&self, gamefinishedstiche: SStichSequenceGameFinished, rulestatecache: &SRuleStateCache) -> EnumMap<EPlayerIndex, SPayoutInfo> {
+        assert!(!self.b_loser_pays_into_stock || 0==self.n_price%2); // keep the stock even
         let points_for_player = |epi| rulestatecache.changing.mapepipointstichcount[epi].n_point;
         debug_assert_eq!(
             EPlayerIndex::map_from_fn(points_for_player),
@@ -68,19 +126,10 @@ impl TRules for SRulesRamsch {
             assert_eq!(1, vecepi_most_points.len());
             vecepi_most_points[0]
         };
-        let (epi_single, b_epi_single_wins) = if match self.durchmarsch {
-            VDurchmarsch::All if 120==n_points_max =>
-                debug_verify_eq!(
-                    rulestatecache.changing.mapepipointstichcount[the_one_epi()].n_stich==gamefinishedstiche.get().kurzlang().cards_per_player(),
-                    gamefinishedstiche.get().completed_stichs_winner_index(self).all(|(_stich, epi_winner)| epi_winner==the_one_epi())
-                ),
-            VDurchmarsch::All | VDurchmarsch::None =>
-                false,
-            VDurchmarsch::AtLeast(n_points_durchmarsch) => {
-                assert!(n_points_durchmarsch>=61); // otherwise, it may not be clear who is the durchmarsch winner
-                n_points_max>=n_points_durchmarsch
-            },
-        } {
+        let (epi_single, b_epi_single_wins) = if self.is_durchmarsch(n_points_max, || debug_verify_eq!(
+            rulestatecache.changing.mapepipointstichcount[the_one_epi()].n_stich==gamefinishedstiche.get().kurzlang().cards_per_player(),
+            gamefinishedstiche.get().completed_stichs_winner_index(self).all(|(_stich, epi_winner)| epi_winner==the_one_epi())
+        )) {
             (the_one_epi(), true)
         } else {
             let epi_loser : EPlayerIndex = {
@@ -120,17 +169,103 @@ impl TRules for SRulesRamsch {
             };
             (epi_loser, false)
         };
+        let ekurzlang = gamefinishedstiche.get().kurzlang();
+        let n_jungfrau = if b_epi_single_wins {
+            0 // durchmarsch already accounts for other players not winning any stich
+        } else {
+            debug_verify_eq!(
+                ekurzlang.players()
+                    .filter(|epi| 0==rulestatecache.changing.mapepipointstichcount[*epi].n_stich)
+                    .count(),
+                ekurzlang.players()
+                    .filter(|epi| gamefinishedstiche.get().completed_stichs_winner_index(self).all(|(_stich, epi_winner)| epi_winner!=*epi))
+                    .count()
+            )
+        };
         internal_payout(
-            self.n_price,
-            &SPlayerParties13::new(epi_single),
+            self.n_price * match self.ejungfrau {
+                EJungfrau::Ignore => 1,
+                EJungfrau::DoubleOnce => if 0<n_jungfrau {2} else {1},
+                EJungfrau::DoubleEach => 2isize.pow(n_jungfrau.as_num::<u32>()),
+            },
+            &SPlayerParties13::new(epi_single, ekurzlang),
             b_epi_single_wins,
         )
-            .map(|n_payout| SPayoutInfo::new(*n_payout, EStockAction::Ignore))
+            .map(|n_payout| SPayoutInfo::new(
+                if self.b_loser_pays_into_stock && !b_epi_single_wins && 0<*n_payout {
+                    0
+                } else {
+                    *n_payout
+                },
+                EStockAction::Ignore,
+            ))
     }
 
-    fn payouthints(&self, _stichseq: &SStichSequence, _ahand: &EnumMap<EPlayerIndex, SHand>, _rulestatecache: &SRuleStateCache) -> EnumMap<EPlayerIndex, SPayoutHint> {
-        // TODO sensible payouthints
-        EPlayerIndex::map_from_fn(|_epi| SPayoutHint::new((None, None)))
+    fn payouthints(&self, stichseq: &SStichSequence, _ahand: &EnumMap<EPlayerIndex, SHand>, rulestatecache: &SRuleStateCache) -> EnumMap<EPlayerIndex, SPayoutHint> {
+        let mapepipointstichcount = &rulestatecache.changing.mapepipointstichcount;
+        let n_points_remaining = 120 - debug_verify_eq!(
+            mapepipointstichcount.iter().map(|pointstichcount| pointstichcount.n_point).sum::<isize>(),
+            stichseq.completed_stichs().iter().map(points_stich).sum::<isize>()
+        );
+        let b_durchmarsch_possible = |epi: EPlayerIndex| match self.durchmarsch {
+            VDurchmarsch::None => false,
+            VDurchmarsch::All => mapepipointstichcount[epi].n_stich==stichseq.completed_stichs().len(),
+            VDurchmarsch::AtLeast(n_points_durchmarsch) => mapepipointstichcount[epi].n_point + n_points_remaining >= n_points_durchmarsch,
+        };
+        let ekurzlang = stichseq.kurzlang();
+        let b_can_lose = |epi: EPlayerIndex| {
+            // epi can only lose if no other player already has more points than epi can possibly reach
+            ekurzlang.players().all(|epi_other|
+                mapepipointstichcount[epi_other].n_point <= mapepipointstichcount[epi].n_point + n_points_remaining
+            )
+        };
+        let n_jungfrau_max = ekurzlang.players()
+            .filter(|epi| 0==mapepipointstichcount[*epi].n_stich)
+            .count()
+            .min(ekurzlang.n_players()-1); // loser always wins a stich
+        let n_price_jungfrau_max = self.n_price * match self.ejungfrau {
+            EJungfrau::Ignore => 1,
+            EJungfrau::DoubleOnce => if 0<n_jungfrau_max {2} else {1},
+            EJungfrau::DoubleEach => 2isize.pow(n_jungfrau_max.as_num::<u32>()),
+        };
+        let payouthint = |n_payout_lo, n_payout_hi| SPayoutHint::new((
+            Some(SPayoutInfo::new(n_payout_lo, EStockAction::Ignore)),
+            Some(SPayoutInfo::new(n_payout_hi, EStockAction::Ignore)),
+        ));
+        if let VDurchmarsch::AtLeast(n_points_durchmarsch) = self.durchmarsch {
+            if let Some(epi_durchmarsch) = EPlayerIndex::values()
+                .find(|epi| mapepipointstichcount[*epi].n_point >= n_points_durchmarsch)
+            {
+                return internal_payout(
+                    self.n_price,
+                    &SPlayerParties13::new(epi_durchmarsch, ekurzlang),
+                    /*b_primary_party_wins*/true,
+                )
+                    .map(|n_payout| payouthint(*n_payout, *n_payout));
+            }
+        }
+        let n_opponents = (ekurzlang.n_players()-1).as_num::<isize>();
+        EPlayerIndex::map_from_fn(|epi| {
+            if !ekurzlang.takes_part(epi) {
+                return payouthint(0, 0);
+            }
+            let b_other_player = |fn_pred: &dyn Fn(EPlayerIndex)->bool| ekurzlang.players()
+                .any(|epi_other| epi_other!=epi && fn_pred(epi_other));
+            let (n_payout_lo, n_payout_hi) = unwrap!([
+                (b_durchmarsch_possible(epi), (n_opponents*self.n_price, n_opponents*self.n_price)), // epi has durchmarsch
+                (b_other_player(&b_durchmarsch_possible), (-self.n_price, -self.n_price)), // other player has durchmarsch
+                (b_can_lose(epi), (-n_opponents*n_price_jungfrau_max, -n_opponents*self.n_price)), // epi loses
+                (b_other_player(&b_can_lose), if self.b_loser_pays_into_stock {(0, 0)} else {(self.n_price, n_price_jungfrau_max)}), // other player loses
+            ].iter()
+                .filter(|(b_outcome_possible, _tplnn_payout)| *b_outcome_possible)
+                .map(|(_b_outcome_possible, tplnn_payout)| *tplnn_payout)
+                .fold1(|(n_payout_lo_acc, n_payout_hi_acc), (n_payout_lo, n_payout_hi)| (
+                    std::cmp::min(n_payout_lo_acc, n_payout_lo),
+                    std::cmp::max(n_payout_hi_acc, n_payout_hi),
+                ))
+            );
+            payouthint(n_payout_lo, n_payout_hi)
+        })
     }
 
 }
@@ -77,6 +77,18 @@ impl TRules for SRulesRufspiel {
         Some(self.epi)
     }
 
+    fn finished_playerparties(&self, gamefinishedstiche: SStichSequenceGameFinished) -> Option<Box<dyn TPlayerParties>> {
+        let epi_coplayer = unwrap!(gamefinishedstiche.get().completed_stichs().iter()
+            .flat_map(|stich| stich.iter())
+            .find(|&(_, card)| *card==self.rufsau())
+            .map(|(epi, _)| epi));
+        Some(Box::new(SPlayerParties22{aepi_pri: [self.epi, epi_coplayer]}))
+    }
+
+    fn ruleparams(&self) -> SRuleParams {
+        self.payoutdecider.payoutparams.ruleparams(/*itruleparam*/[])
+    }
+
     fn stoss_allowed(&self, epi: EPlayerIndex, vecstoss: &[SStoss], hand: &SHand) -> bool {
         EKurzLang::from_cards_per_player(hand.cards().len());
         assert!(epi!=self.epi || !hand.contains(self.rufsau()));
@@ -85,7 +97,7 @@ impl TRules for SRulesRufspiel {
 
     fn payoutinfos(&self, gamefinishedstiche: SStichSequenceGameFinished, rulestatecache: &SRuleStateCache) -> EnumMap<EPlayerIndex, SPayoutInfo> {
         let epi_coplayer = debug_verify_eq!(
-            rulestatecache.fixed.who_has_card(self.rufsau()),
+            unwrap!(rulestatecache.fixed.who_has_card(self.rufsau())), // Rufspiel requires four players, so the Rufsau is dealt
             unwrap!(gamefinishedstiche.get().completed_stichs().iter()
                 .flat_map(|stich| stich.iter())
                 .find(|&(_, card)| *card==self.rufsau())
@@ -122,7 +134,7 @@ impl TRules for SRulesRufspiel {
 
     fn payouthints(&self, stichseq: &SStichSequence, ahand: &EnumMap<EPlayerIndex, SHand>, rulestatecache: &SRuleStateCache) -> EnumMap<EPlayerIndex, SPayoutHint> {
         let epi_coplayer = debug_verify_eq!(
-            rulestatecache.fixed.who_has_card(self.rufsau()),
+            unwrap!(rulestatecache.fixed.who_has_card(self.rufsau())), // Rufspiel requires four players, so the Rufsau is dealt
             stichseq.visible_stichs()
                 .iter()
                 .flat_map(|stich| stich.iter())
@@ -196,6 +208,18 @@ impl TRules for SRulesRufspiel {
         }
     }
 
+    fn cards_interchangeable_if_adjacent(&self, card_fst: SCard, card_snd: SCard) -> bool {
+        // the rufsau restricts allowed cards (e.g. it must not be held back when the ruffarbe is gesucht)
+        card_fst!=self.rufsau() && card_snd!=self.rufsau()
+    }
+
+    fn snapshot_cache_discriminator(&self, stichseq: &SStichSequence) -> usize {
+        // allowed cards depend on whether ruffarbe has been gesucht or weggelaufen
+        stichseq.completed_stichs().iter()
+            .filter(|stich| self.is_ruffarbe(*stich.first()))
+            .count()
+    }
+
     fn rulespecific_ai<'rules>(&'rules self) -> Option<Box<dyn TRuleSpecificAI + 'rules>> {
         Some(Box::new(SAIRufspiel::new(self)))
     }
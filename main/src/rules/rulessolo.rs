@@ -32,6 +32,10 @@ pub trait TPayoutDeciderSoloLike : Sync + 'static + Clone + fmt::Debug + TPayout
     fn priorityinfo(&self) -> String {
         "".to_string()
     }
+    fn with_schneiderschwarz_announced(&self, _eschneiderschwarz: ESchneiderSchwarz) -> Option<Self> {
+        None
+    }
+    fn ruleparams(&self) -> SRuleParams;
 }
 
 impl TPointsToWin for VGameAnnouncementPrioritySoloLike {
@@ -104,12 +108,40 @@ impl TPayoutDeciderSoloLike for SPayoutDeciderPointBased<VGameAnnouncementPriori
             },
         }
     }
+
+    fn with_schneiderschwarz_announced(&self, eschneiderschwarz: ESchneiderSchwarz) -> Option<Self> {
+        self.schneiderschwarzannouncement.announced(eschneiderschwarz)
+            .map(|schneiderschwarzannouncement| Self{schneiderschwarzannouncement, ..self.clone()})
+    }
+
+    fn ruleparams(&self) -> SRuleParams {
+        self.payoutparams.ruleparams(
+            std::iter::once(VRuleParam::Priority(self.priority()))
+                .chain(self.schneiderschwarzannouncement.ruleparams())
+        )
+    }
+}
+
+#[derive(Clone, Debug, new)]
+pub struct SToutSieParams { // clubs differ in what is counted for Tout and Sie
+    pub b_laufende : bool,
+    pub b_schneider_schwarz : bool,
+}
+
+impl SToutSieParams {
+    fn ruleparams(&self) -> [VRuleParam; 2] {
+        [
+            VRuleParam::ToutSieLaufende(self.b_laufende),
+            VRuleParam::ToutSieSchneiderSchwarz(self.b_schneider_schwarz),
+        ]
+    }
 }
 
 #[derive(Clone, Debug, new)]
 pub struct SPayoutDeciderTout {
     payoutparams : SPayoutDeciderParams,
     i_prio: isize,
+    toutsieparams: SToutSieParams,
 }
 
 impl TPayoutDecider for SPayoutDeciderTout {
@@ -122,15 +154,26 @@ impl TPayoutDecider for SPayoutDeciderTout {
     ) -> EnumMap<EPlayerIndex, isize>
         where Rules: TRulesNoObj,
     {
-        // TODORULES optionally count schneider/schwarz
+        let b_primary_party_wins = debug_verify_eq!(
+            rulestatecache.changing.mapepipointstichcount[playerparties13.primary_player()].n_stich==gamefinishedstiche.get().kurzlang().cards_per_player(),
+            gamefinishedstiche.get().completed_stichs_winner_index(rules)
+                .all(|(_stich, epi_winner)| playerparties13.is_primary_party(epi_winner))
+        );
         internal_payout(
-            /*n_payout_single_player*/ (self.payoutparams.n_payout_base + self.payoutparams.laufendeparams.payout_laufende::<Rules, _>(rulestatecache, gamefinishedstiche, playerparties13)) * 2,
+            /*n_payout_single_player*/ (self.payoutparams.n_payout_base
+                + if self.toutsieparams.b_laufende {
+                    self.payoutparams.laufendeparams.payout_laufende::<Rules, _>(rulestatecache, gamefinishedstiche, playerparties13)
+                } else {
+                    0
+                }
+                + if self.toutsieparams.b_schneider_schwarz {
+                    self.payoutparams.payout_schneider_schwarz(rulestatecache, gamefinishedstiche.get().kurzlang(), playerparties13, b_primary_party_wins)
+                } else {
+                    0
+                }
+            ) * 2,
             playerparties13,
-            /*b_primary_party_wins*/ debug_verify_eq!(
-                rulestatecache.changing.mapepipointstichcount[playerparties13.primary_player()].n_stich==gamefinishedstiche.get().kurzlang().cards_per_player(),
-                gamefinishedstiche.get().completed_stichs_winner_index(rules)
-                    .all(|(_stich, epi_winner)| playerparties13.is_primary_party(epi_winner))
-            ),
+            b_primary_party_wins,
         )
     }
 
@@ -149,15 +192,21 @@ impl TPayoutDecider for SPayoutDeciderTout {
             !stichseq.completed_stichs_winner_index(rules)
                 .all(|(_stich, epi_winner)| playerparties13.is_primary_party(epi_winner))
         ) {
-            internal_payout(
-                /*n_payout_single_player*/ (self.payoutparams.n_payout_base) * 2, // TODO laufende
-                playerparties13,
-                /*b_primary_party_wins*/ false,
+            payouthints_bounded_by(
+                &internal_payout(
+                    /*n_payout_single_player*/ (self.payoutparams.n_payout_base
+                        + if self.toutsieparams.b_laufende {
+                            self.payoutparams.laufendeparams.payout_laufende_fixed::<Rules, _>(&rulestatecache.fixed, stichseq.kurzlang(), playerparties13)
+                        } else {
+                            0
+                        }
+                        // schneider/schwarz not yet known, but can only increase payout
+                    ) * 2,
+                    playerparties13,
+                    /*b_primary_party_wins*/ false,
+                ),
+                stichseq.kurzlang(),
             )
-                .map(|n_payout| {
-                     assert_ne!(0, *n_payout);
-                     tpl_flip_if(0<*n_payout, (None, Some(*n_payout)))
-                })
         } else {
             EPlayerIndex::map_from_fn(|_epi| (None, None))
         }
@@ -168,74 +217,82 @@ impl TPayoutDeciderSoloLike for SPayoutDeciderTout {
     fn priority(&self) -> VGameAnnouncementPriority {
         VGameAnnouncementPriority::SoloTout(self.i_prio)
     }
+
+    fn ruleparams(&self) -> SRuleParams {
+        self.payoutparams.ruleparams(
+            std::iter::once(VRuleParam::Priority(self.priority()))
+                .chain(self.toutsieparams.ruleparams())
+        )
+    }
 }
 
 #[derive(Clone, Debug, new)]
 pub struct SPayoutDeciderSie {
     payoutparams : SPayoutDeciderParams,
+    toutsieparams: SToutSieParams,
 }
 
-fn cards_valid_for_sie_internal<Rules: TRulesNoObj, ItCard: Iterator<Item=SCard>, FnAllowUnter: Fn(EFarbe)->bool>(
-    rules: &Rules,
+// Sie: the player holds the highest trumpfs. If there are not enough trumpfs (Wenz, Geier), the remaining cards must be Asse.
+fn cards_valid_for_sie<Rules: TRulesNoObj, ItCard: Iterator<Item=SCard>>(
     mut itcard: ItCard,
-    fn_allow_unter: FnAllowUnter,
+    ekurzlang: EKurzLang,
 ) -> bool {
-    itcard.all(|card| {
-        let b_card_valid = match card.schlag() {
-            ESchlag::Ober => true,
-            ESchlag::Unter => fn_allow_unter(card.farbe()),
-            ESchlag::S7 | ESchlag::S8 | ESchlag::S9 | ESchlag::Zehn | ESchlag::Koenig | ESchlag::Ass => false,
-        };
-        assert!(!b_card_valid || rules.trumpforfarbe(card).is_trumpf());
-        b_card_valid
-    })
+    let veccard_sie = Rules::TrumpfDecider::trumpfs_in_descending_order()
+        .chain(
+            EFarbe::values()
+                .map(|efarbe| SCard::new(efarbe, ESchlag::Ass))
+                .filter(|card| !Rules::TrumpfDecider::trumpforfarbe(*card).is_trumpf())
+        )
+        .filter(|card| ekurzlang.supports_card(*card))
+        .take(ekurzlang.cards_per_player())
+        .collect::<Vec<_>>();
+    // with 10 cards per player, Wenz and Geier do not have enough trumpfs and Asse for a Sie
+    veccard_sie.len()==ekurzlang.cards_per_player()
+        && itcard.all(|card| veccard_sie.contains(&card))
 }
 
-// TODO SPayoutDeciderSie should be able to work with any TTrumpfDecider
-fn cards_valid_for_sie<Rules: TRulesNoObj, ItCard: Iterator<Item=SCard>>(
-    rules: &Rules,
-    itcard: ItCard,
-    ekurzlang: EKurzLang,
-) -> bool {
-    match ekurzlang {
-        EKurzLang::Lang => cards_valid_for_sie_internal(rules, itcard, /*fn_allow_unter*/|_| true),
-        EKurzLang::Kurz => cards_valid_for_sie_internal(rules, itcard, /*fn_allow_unter*/|efarbe|
-            match efarbe {
-                EFarbe::Eichel | EFarbe::Gras => true,
-                EFarbe::Herz | EFarbe::Schelln => false,
-            }
-        ),
+impl SPayoutDeciderSie {
+    fn payout_laufende(&self, ekurzlang: EKurzLang) -> isize {
+        if self.toutsieparams.b_laufende {
+            // each card counts as one lauf
+            ekurzlang.cards_per_player().as_num::<isize>() * self.payoutparams.laufendeparams.n_payout_per_lauf
+        } else {
+            0
+        }
     }
 }
 
 impl TPayoutDecider for SPayoutDeciderSie {
     fn payout<Rules>(
         &self,
-        rules: &Rules,
-        _rulestatecache: &SRuleStateCache,
+        _rules: &Rules,
+        rulestatecache: &SRuleStateCache,
         gamefinishedstiche: SStichSequenceGameFinished,
         playerparties13: &SPlayerParties13,
     ) -> EnumMap<EPlayerIndex, isize>
         where Rules: TRulesNoObj,
     {
-        // TODORULES optionally count schneider/schwarz
+        let b_primary_party_wins = cards_valid_for_sie::<Rules, _>(
+            gamefinishedstiche.get().completed_stichs().iter().map(|stich| stich[playerparties13.primary_player()]),
+            gamefinishedstiche.get().kurzlang(),
+        );
         internal_payout(
             /*n_payout_single_player*/ (self.payoutparams.n_payout_base
-            + {
-                gamefinishedstiche.get().completed_stichs().len().as_num::<isize>()
-            } * self.payoutparams.laufendeparams.n_payout_per_lauf) * 4,
+                + self.payout_laufende(gamefinishedstiche.get().kurzlang())
+                + if self.toutsieparams.b_schneider_schwarz {
+                    self.payoutparams.payout_schneider_schwarz(rulestatecache, gamefinishedstiche.get().kurzlang(), playerparties13, b_primary_party_wins)
+                } else {
+                    0
+                }
+            ) * 4,
             playerparties13,
-            /*b_primary_party_wins*/cards_valid_for_sie(
-                rules,
-                gamefinishedstiche.get().completed_stichs().iter().map(|stich| stich[playerparties13.primary_player()]),
-                gamefinishedstiche.get().kurzlang(),
-            )
+            b_primary_party_wins,
         )
     }
 
     fn payouthints<Rules>(
         &self,
-        rules: &Rules,
+        _rules: &Rules,
         stichseq: &SStichSequence,
         ahand: &EnumMap<EPlayerIndex, SHand>,
         _rulestatecache: &SRuleStateCache,
@@ -246,21 +303,22 @@ impl TPayoutDecider for SPayoutDeciderSie {
         let itcard = stichseq.visible_stichs().iter().filter_map(|stich| stich.get(playerparties13.primary_player())).copied()
             .chain(ahand[playerparties13.primary_player()].cards().iter().copied());
         if
-            !cards_valid_for_sie(
-                rules,
+            !cards_valid_for_sie::<Rules, _>(
                 itcard.clone(),
                 stichseq.kurzlang(),
             )
         {
-            internal_payout(
-                /*n_payout_single_player*/ self.payoutparams.n_payout_base * 4,
-                playerparties13,
-                /*b_primary_party_wins*/ false,
+            payouthints_bounded_by(
+                &internal_payout(
+                    /*n_payout_single_player*/ (self.payoutparams.n_payout_base
+                        + self.payout_laufende(stichseq.kurzlang())
+                        // schneider/schwarz not yet known, but can only increase payout
+                    ) * 4,
+                    playerparties13,
+                    /*b_primary_party_wins*/ false,
+                ),
+                stichseq.kurzlang(),
             )
-                .map(|n_payout| {
-                     assert_ne!(0, *n_payout);
-                     tpl_flip_if(0<*n_payout, (None, Some(*n_payout)))
-                })
         } else {
             EPlayerIndex::map_from_fn(|_epi| (None, None))
         }
@@ -271,6 +329,10 @@ impl TPayoutDeciderSoloLike for SPayoutDeciderSie {
     fn priority(&self) -> VGameAnnouncementPriority {
         VGameAnnouncementPriority::SoloSie
     }
+
+    fn ruleparams(&self) -> SRuleParams {
+        self.payoutparams.ruleparams(self.toutsieparams.ruleparams())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -303,6 +365,15 @@ impl<StaticEPI: TStaticValue<EPlayerIndex>, TrumpfDecider: TTrumpfDecider, Payou
 impl<StaticEPI: TStaticValue<EPlayerIndex>, TrumpfDecider: TTrumpfDecider, PayoutDecider: TPayoutDeciderSoloLike> TRules for SRulesSoloLike<StaticEPI, TrumpfDecider, PayoutDecider> {
     impl_rules_trumpf!();
     impl_single_play!();
+
+    fn with_schneiderschwarz_announced(&self, eschneiderschwarz: ESchneiderSchwarz) -> Option<Box<dyn TRules>> {
+        self.payoutdecider.with_schneiderschwarz_announced(eschneiderschwarz)
+            .map(|payoutdecider| Box::new(Self::new(payoutdecider, self.str_name.clone())) as Box<dyn TRules>)
+    }
+
+    fn ruleparams(&self) -> SRuleParams {
+        self.payoutdecider.ruleparams()
+    }
 }
 
 impl<StaticEPI: TStaticValue<EPlayerIndex>, TrumpfDecider: TTrumpfDecider, PayoutDecider: TPayoutDeciderSoloLike> SRulesSoloLike<StaticEPI, TrumpfDecider, PayoutDecider> {
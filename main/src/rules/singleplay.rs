@@ -3,6 +3,10 @@ macro_rules! impl_single_play {() => {
         Some(self.internal_playerindex())
     }
 
+    fn finished_playerparties(&self, gamefinishedstiche: SStichSequenceGameFinished) -> Option<Box<dyn TPlayerParties>> {
+        Some(Box::new(SPlayerParties13::new(self.internal_playerindex(), gamefinishedstiche.get().kurzlang())))
+    }
+
     fn stoss_allowed(&self, epi: EPlayerIndex, vecstoss: &[SStoss], hand: &SHand) -> bool {
         assert!(
             vecstoss.iter()
@@ -18,7 +22,7 @@ macro_rules! impl_single_play {() => {
             self,
             rulestatecache,
             gamefinishedstiche,
-            &SPlayerParties13::new(self.internal_playerindex()),
+            &SPlayerParties13::new(self.internal_playerindex(), gamefinishedstiche.get().kurzlang()),
         ).map(|n_payout| SPayoutInfo::new(*n_payout, EStockAction::Ignore))
     }
 
@@ -28,7 +32,7 @@ macro_rules! impl_single_play {() => {
             stichseq,
             ahand,
             rulestatecache,
-            &SPlayerParties13::new(self.internal_playerindex()),
+            &SPlayerParties13::new(self.internal_playerindex(), stichseq.kurzlang()),
         ).map(|pairon_payout| SPayoutHint::new((
              pairon_payout.0.map(|n_payout| SPayoutInfo::new(n_payout, EStockAction::Ignore)),
              pairon_payout.1.map(|n_payout| SPayoutInfo::new(n_payout, EStockAction::Ignore)),
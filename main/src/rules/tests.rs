@@ -1,9 +1,9 @@
 use crate::game::*;
-use crate::rules::ruleset::SStossParams;
+use crate::rules::ruleset::{EBockTrigger, EStossDeadline, SRuleSet, SStossParams};
 use crate::game_analysis::*;
 use crate::primitives::{card::card_values::*, *};
 use crate::rules::{
-    payoutdecider::*, rulesbettel::*, rulesramsch::*, rulesrufspiel::*, rulessolo::*, *,
+    payoutdecider::*, rulesbettel::*, ruleshochzeit::*, ruleskreuzspiel::*, rulesramsch::*, rulesrufspiel::*, rulessolo::*, *,
 };
 use crate::util::*;
 
@@ -27,8 +27,9 @@ fn internal_test_rules(
                 vecn_doubling.contains(&epi.to_usize())
             ).into_raw(),
         ),
-        /*ostossparams*/Some(SStossParams::new(/*n_stoss_max*/4)),
+        /*ostossparams*/Some(SStossParams::new(/*n_stoss_max*/4, EStossDeadline::NoCardPlayed)),
         rules.box_clone(),
+        /*n_bock*/0,
         n_stock,
     );
     for i_epi in vecn_stoss.into_iter() {
@@ -39,9 +40,12 @@ fn internal_test_rules(
             unwrap!(game.zugeben(*card, epi));
         }
     }
-    let an_payout_check = unwrap!(game.finish()).an_payout;
-    assert_eq!(EPlayerIndex::map_from_fn(|epi| an_payout_check[epi]), EPlayerIndex::map_from_raw(an_payout));
+    let gameresult = unwrap!(game.finish());
+    assert_eq!(EPlayerIndex::map_from_fn(|epi| gameresult.an_payout[epi]), EPlayerIndex::map_from_raw(an_payout));
     assert_eq!(-an_payout.iter().sum::<isize>(), n_stock_payout);
+    let mut n_stock_after_game = n_stock;
+    gameresult.apply_payout(&mut n_stock_after_game, |_epi, _n_payout| {});
+    assert_eq!(n_stock_after_game, n_stock + n_stock_payout);
 }
 
 pub trait TCardArrayKurzLang {
@@ -133,6 +137,7 @@ impl TPayoutDeciderSoloLikeDefault for SPayoutDeciderTout {
         Self::new(
             SPayoutDeciderParams::new(n_payout_base, n_payout_schneider_schwarz, laufendeparams),
             0,
+            SToutSieParams::new(/*b_laufende*/true, /*b_schneider_schwarz*/false),
         )
     }
 }
@@ -1810,7 +1815,7 @@ fn test_rulesramsch() {
     use EPlayerIndex::*;
     test_rules_manual(
         "0 has durchmarsch all",
-        &SRulesRamsch::new(10, VDurchmarsch::All),
+        &SRulesRamsch::new(10, VDurchmarsch::All, EJungfrau::Ignore, /*b_schieben*/false, /*b_loser_pays_into_stock*/false),
         vec![],
         vec![],
         /*n_stock*/20,
@@ -1828,7 +1833,7 @@ fn test_rulesramsch() {
     );
     test_rules_manual(
         "0 has durchmarsch 120",
-        &SRulesRamsch::new(10, VDurchmarsch::AtLeast(120)),
+        &SRulesRamsch::new(10, VDurchmarsch::AtLeast(120), EJungfrau::Ignore, /*b_schieben*/false, /*b_loser_pays_into_stock*/false),
         vec![],
         vec![],
         /*n_stock*/160,
@@ -1846,7 +1851,7 @@ fn test_rulesramsch() {
     );
     test_rules_manual(
         "0 has 120, but no durchmarsch",
-        &SRulesRamsch::new(10, VDurchmarsch::All),
+        &SRulesRamsch::new(10, VDurchmarsch::All, EJungfrau::Ignore, /*b_schieben*/false, /*b_loser_pays_into_stock*/false),
         vec![],
         vec![],
         /*n_stock*/40,
@@ -1862,6 +1867,175 @@ fn test_rulesramsch() {
         ],
         ([-30, 10, 10, 10], 0),
     );
+    let slcpairepiacard_stich_jungfrau = [
+        (EPI0, [EO,GO,HO,SO]),
+        (EPI0, [EU,GU,HU,SU]),
+        (EPI0, [HA,HZ,HK,H9]),
+        (EPI0, [EA,EZ,EK,E9]),
+        (EPI0, [GA,GZ,GK,G9]),
+        (EPI0, [SA,SZ,SK,S9]),
+        (EPI0, [E8,E7,G8,G7]),
+        (EPI0, [H7,H8,S8,S7]),
+    ];
+    for (ejungfrau, b_schieben, vecn_stoss, an_payout) in [
+        (EJungfrau::Ignore, false, vec![], [-30, 10, 10, 10]),
+        (EJungfrau::DoubleOnce, false, vec![], [-60, 20, 20, 20]),
+        (EJungfrau::DoubleEach, false, vec![], [-120, 40, 40, 40]),
+        (EJungfrau::Ignore, true, vec![1], [-60, 20, 20, 20]),
+        (EJungfrau::DoubleOnce, true, vec![1, 0], [-240, 80, 80, 80]),
+    ] {
+        test_rules_manual(
+            &format!("0 has 120 and loses, 2 and 3 are jungfrau ({:?}, schieben: {:?})", ejungfrau, vecn_stoss),
+            &SRulesRamsch::new(10, VDurchmarsch::All, ejungfrau, b_schieben, /*b_loser_pays_into_stock*/false),
+            vec![],
+            vecn_stoss,
+            /*n_stock*/0,
+            &slcpairepiacard_stich_jungfrau,
+            (an_payout, 0),
+        );
+    }
+    {
+        use crate::rules::ruleset::*;
+        let str_ruleset_schieben = r"
+            base-price=10
+            [ramsch]
+            price=10
+            schieben=true
+        ";
+        assert!(SRuleSet::from_string(str_ruleset_schieben).is_err());
+        assert!(SRuleSet::from_string(&format!("{}\n[stoss]", str_ruleset_schieben)).is_ok());
+    }
+}
+
+#[test]
+fn test_rulesramsch_stock() {
+    use crate::rules::ruleset::*;
+    use EPlayerIndex::*;
+    let ruleset = unwrap!(SRuleSet::from_string(r"
+        base-price=10
+        [ramsch]
+        price=10
+        loser-pays-into-stock=true
+        [stock]
+    "));
+    assert!(matches!(ruleset.ofallbackrules, Some(VFallbackRules::Ramsch(_))));
+    assert_eq!(ruleset.on_stock_price, Some(10));
+    for (str_ruleset, b_fallbackrules) in [
+        (r"
+            base-price=10
+            [ramsch]
+            price=10
+            [stock]
+            price=30
+        ", true),
+        (r"
+            base-price=10
+            lauf-min=3
+            [kreuzspiel]
+            [stock]
+        ", true),
+        (r"
+            base-price=10
+            lauf-min=3
+            [der-alte-muss]
+            solo-price=50
+            [stock]
+        ", true),
+        (r"
+            base-price=10
+            [stock]
+            price=30
+        ", false),
+    ] {
+        let ruleset = unwrap!(SRuleSet::from_string(str_ruleset));
+        assert_eq!(ruleset.ofallbackrules.is_some(), b_fallbackrules);
+        assert!(ruleset.on_stock_price.is_some());
+    }
+    for str_ruleset_inconsistent in [
+        r"
+            base-price=10
+            [ramsch]
+            price=10
+            loser-pays-into-stock=true
+        ",
+        r"
+            base-price=10
+            [ramsch]
+            price=5
+            loser-pays-into-stock=true
+            [stock]
+        ", // stock could become odd
+    ] {
+        assert!(SRuleSet::from_string(str_ruleset_inconsistent).is_err());
+    }
+    let slcpairepiacard_stich = [
+        (EPI0, [EO,GO,HO,SO]),
+        (EPI0, [EU,GU,HU,SU]),
+        (EPI0, [HA,HZ,HK,H9]),
+        (EPI0, [EA,EZ,EK,E9]),
+        (EPI0, [GA,GZ,GK,G9]),
+        (EPI0, [SA,SZ,SK,S9]),
+        (EPI0, [E8,E7,G8,G7]),
+        (EPI0, [H7,H8,S8,S7]),
+    ];
+    for (n_price, ejungfrau, n_stock_initial, an_payout, n_stock_payout) in [
+        (10, EJungfrau::Ignore, 0, [-30, 0, 0, 0], 30),
+        (10, EJungfrau::DoubleEach, 40, [-120, 0, 0, 0], 120),
+        (6, EJungfrau::Ignore, 20, [-18, 0, 0, 0], 18),
+    ] {
+        test_rules_manual(
+            &format!("0 loses and pays into stock ({}, {:?}, {})", n_price, ejungfrau, n_stock_initial),
+            &SRulesRamsch::new(n_price, VDurchmarsch::All, ejungfrau, /*b_schieben*/false, /*b_loser_pays_into_stock*/true),
+            vec![],
+            vec![],
+            n_stock_initial,
+            &slcpairepiacard_stich,
+            (an_payout, n_stock_payout),
+        );
+    }
+    test_rules_manual(
+        "0 has durchmarsch and is paid by the others",
+        &SRulesRamsch::new(10, VDurchmarsch::All, EJungfrau::Ignore, /*b_schieben*/false, /*b_loser_pays_into_stock*/true),
+        vec![],
+        vec![],
+        /*n_stock*/20,
+        &[
+            (EPI0, [EO,GO,HO,SO]),
+            (EPI0, [EU,GU,HU,SU]),
+            (EPI0, [HA,HZ,HK,H9]),
+            (EPI0, [EA,EZ,EK,E9]),
+            (EPI0, [GA,GZ,GK,G9]),
+            (EPI0, [SA,SZ,SK,S9]),
+            (EPI0, [E8,E7,G8,G7]),
+            (EPI0, [H8,H7,S8,S7]),
+        ],
+        ([30, -10, -10, -10], 0),
+    );
+    // next winner takes the stock filled by the ramsch loser
+    test_rules_manual(
+        "Rufspiel: Players win stock",
+        &rulesrufspiel_new_test(
+            EPlayerIndex::EPI0,
+            EFarbe::Eichel,
+            /*n_payout_base*/10,
+            /*n_payout_schneider_schwarz*/10,
+            SLaufendeParams::new(10, 3),
+        ),
+        vec![],
+        vec![],
+        /*n_stock*/34,
+        &[
+            (EPI0, [EO, GO, HO, SO]),
+            (EPI0, [EU, GU, HU, SU]),
+            (EPI0, [HA, HZ, HK, H9]),
+            (EPI0, [EZ, EA, EK, E9]),
+            (EPI1, [E8, E7, S7, S8]),
+            (EPI1, [SA, SZ, SK, S9]),
+            (EPI1, [GA, GZ, GK, H8]),
+            (EPI0, [H7, G9, G8, G7]),
+        ],
+        ([30+17, 30+17, -30, -30], -34),
+    );
 }
 
 #[test]
@@ -1869,7 +2043,7 @@ fn test_rulesbettel() {
     use EPlayerIndex::*;
     test_rules_manual(
         "3 wins Bettel",
-        &SRulesBettel::<SBettelAllAllowedCardsWithinStichNormal>::new(EPlayerIndex::EPI3, /*i_prio*/0, /*n_payout_base*/10),
+        &SRulesBettel::<SBettelAllAllowedCardsWithinStichNormal>::new(EPlayerIndex::EPI3, VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(0)), /*n_payout_base*/10, /*b_brett*/false),
         vec![],
         vec![],
         /*n_stock*/20,
@@ -1887,7 +2061,7 @@ fn test_rulesbettel() {
     );
     test_rules_manual(
         "2 looses Bettel",
-        &SRulesBettel::<SBettelAllAllowedCardsWithinStichNormal>::new(EPlayerIndex::EPI2, /*i_prio*/0, /*n_payout_base*/10),
+        &SRulesBettel::<SBettelAllAllowedCardsWithinStichNormal>::new(EPlayerIndex::EPI2, VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(0)), /*n_payout_base*/10, /*b_brett*/false),
         vec![],
         vec![],
         /*n_stock*/40,
@@ -1905,6 +2079,769 @@ fn test_rulesbettel() {
     );
 }
 
+#[test]
+fn test_rulesbettel_brett() {
+    use EPlayerIndex::*;
+    let vecstich = make_stich_vector(&[
+        (EPI0, [EO,EZ,EK,E9]),
+        (EPI2, [HO,H9,HA,HZ]),
+        (EPI0, [H8,H7,HU,SO]),
+        (EPI2, [G8,G9,GA,GO]),
+        (EPI0, [E8,E7,GK,SU]),
+        (EPI0, [SA,SZ,SK,S9]),
+        (EPI0, [EU,GZ,HK,S7]),
+        (EPI0, [EA,GU,S8,G7]),
+    ]);
+    let mut game = SGame::new(
+        EPlayerIndex::map_from_fn(|epi| SHand::new_from_iter(vecstich.iter().map(|stich| stich[epi]))),
+        SDoublings::new(SStaticEPI0{}),
+        /*ostossparams*/None,
+        Box::new(SRulesBettel::<SBettelAllAllowedCardsWithinStichNormal>::new(
+            EPI2,
+            VGameAnnouncementPriority::SoloLike(VGameAnnouncementPrioritySoloLike::SoloSimple(0)),
+            /*n_payout_base*/10,
+            /*b_brett*/true,
+        )),
+        /*n_bock*/0,
+        /*n_stock*/0,
+    );
+    for (epi, card) in vecstich[0].iter() {
+        assert!(game.revealed_hand().is_none());
+        unwrap!(game.zugeben(*card, epi));
+    }
+    let (epi_revealed, hand_revealed) = unwrap!(game.revealed_hand());
+    assert_eq!(epi_revealed, EPI2);
+    assert_eq!(hand_revealed.cards(), game.ahand[EPI2].cards());
+}
+
+#[test]
+fn test_rulegroup_priorities() {
+    // the Bettel priorities must fit in without changing the order of the other games
+    let ruleset = unwrap!(SRuleSet::from_string(r#"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [rufspiel]
+        [hochzeit]
+        [solo]
+        [wenz]
+        [farbwenz]
+        [geier]
+        [farbgeier]
+        [bettel]
+        priority="above-wenz"
+    "#));
+    let priority = |str_group: &str| unwrap!(
+        unwrap!(ruleset.avecrulegroup[EPlayerIndex::EPI0].iter().find(|rulegroup| rulegroup.str_name==str_group))
+            .vecorules.iter().flatten().next()
+    ).priority();
+    for slcstr_group in [
+        "Rufspiel",
+        "Farbgeier",
+        "Geier",
+        "Farbwenz",
+        "Wenz",
+        "Bettel",
+        "Solo",
+        "Farbgeier Tout",
+        "Geier Tout",
+        "Farbwenz Tout",
+        "Wenz Tout",
+        "Solo Tout",
+        "Sie",
+    ].windows(2) {
+        assert!(priority(slcstr_group[0])<priority(slcstr_group[1]), "{:?}", slcstr_group);
+    }
+    for (str_group_lhs, str_group_rhs) in [
+        ("Rufspiel", "Hochzeit"),
+        ("Sie", "Wenz Sie"),
+        ("Sie", "Farbgeier Sie"),
+    ] {
+        assert_eq!(priority(str_group_lhs), priority(str_group_rhs));
+    }
+}
+
+#[test]
+fn test_ruleshochzeit() {
+    use EPlayerIndex::*;
+    let ruleshochzeit = SRulesHochzeit::new(
+        EPlayerIndex::EPI0,
+        SPayoutDeciderParams::new(
+            /*n_payout_base*/20,
+            /*n_payout_schneider_schwarz*/10,
+            SLaufendeParams::new(10, 3),
+        ),
+    );
+    {
+        // 1 wins first farb-stich, becomes partner and returns SO for 0's only trumpf HO
+        let ahand_dealt = EPlayerIndex::map_from_raw([
+            [EA,EZ,HO,EK,GA,GZ,SA,SZ],
+            [E9,EO,GO,SO,G9,H7,S9,H8],
+            [E8,EU,GU,HU,G8,SU,S8,H9],
+            [E7,HA,HZ,HK,G7,GK,SK,S7],
+        ]).map_into(SHand::new_from_iter);
+        let vecstich = make_stich_vector(&[
+            (EPI0, [EA,E9,E8,E7]),
+            (EPI0, [EZ,EO,EU,HA]),
+            (EPI1, [GO,GU,HZ,SO]),
+            (EPI1, [HO,HU,HK,EK]),
+            (EPI1, [G9,G8,G7,GA]),
+            (EPI0, [GZ,H7,SU,GK]),
+            (EPI2, [S8,SK,SA,S9]),
+            (EPI0, [SZ,H8,H9,S7]),
+        ]);
+        let sorted_cards = |hand: &SHand| {
+            let mut veccard = hand.cards().to_vec();
+            veccard.sort_unstable_by_key(|card| card.to_usize());
+            veccard
+        };
+        let cardexchange = SCardExchange{
+            epi_active: EPI0,
+            card_active: HO,
+            epi_partner: EPI1,
+            card_partner: SO,
+            n_cards_played: 8,
+        };
+        let mut game = SGame::new(
+            ahand_dealt.explicit_clone(),
+            SDoublings::new(SStaticEPI0{}),
+            /*ostossparams*/None,
+            Box::new(ruleshochzeit.clone()),
+            /*n_bock*/0,
+            /*n_stock*/0,
+        );
+        for (i_stich, stich) in vecstich.iter().enumerate() {
+            if 2==i_stich {
+                assert_eq!(game.card_exchange_pending(), Some(EPI1));
+                assert!(game.zugeben(GO, EPI1).is_err());
+                assert!(game.exchange_card(EPI0, EA).is_err());
+                assert!(game.exchange_card(EPI1, HO).is_err());
+                unwrap!(game.exchange_card(EPI1, SO));
+                assert_eq!(game.ocardexchange, Some(cardexchange.clone()));
+                assert!(game.ahand[EPI0].contains(SO) && !game.ahand[EPI0].contains(HO));
+                assert!(game.ahand[EPI1].contains(HO) && !game.ahand[EPI1].contains(SO));
+            } else {
+                assert!(game.card_exchange_pending().is_none());
+            }
+            if game.ocardexchange.is_some() {
+                // hands sampled by the AI must be compatible with the game so far despite the exchange
+                for epi in EPlayerIndex::values() {
+                    assert!(crate::ai::handiterators::forever_rand_hands(
+                        &game.stichseq,
+                        game.ahand[epi].clone(),
+                        epi,
+                        /*ohand_revealed*/None,
+                        game.rules.as_ref(),
+                    ).next().is_some());
+                }
+            }
+            for (epi, card) in stich.iter() {
+                unwrap!(game.zugeben(*card, epi));
+            }
+        }
+        for epi in EPlayerIndex::values() {
+            assert_eq!(sorted_cards(&game.dealt_hand(epi)), sorted_cards(&ahand_dealt[epi]));
+        }
+        let stichseq = game.stichseq.clone();
+        assert_eq!(unwrap!(game.clone().finish()).an_payout, EPlayerIndex::map_from_raw([70, 70, -70, -70]));
+        let game_finished = |ocardexchange| SGame::new_finished(
+            Box::new(ruleshochzeit.clone()),
+            SDoublings::new(SStaticEPI0{}),
+            /*ostossparams*/None,
+            /*vecstoss*/vec![],
+            /*oeschneiderschwarz_announced*/None,
+            ocardexchange,
+            /*n_bock*/0,
+            /*n_stock*/0,
+            SStichSequenceGameFinished::new(&stichseq),
+            /*fn_before_zugeben*/|_game, _i_stich, _epi, _card| {},
+        );
+        assert!(game_finished(None).is_err());
+        assert_eq!(unwrap!(game_finished(Some(cardexchange.clone()))).ocardexchange, Some(cardexchange));
+        // taking back the stich that determined the partner also takes back the exchange
+        unwrap!(game.rewind_to(/*i_stich*/1, /*n_cards*/3));
+        assert!(game.ocardexchange.is_none());
+        for epi in EPlayerIndex::values() {
+            assert_eq!(sorted_cards(&game.dealt_hand(epi)), sorted_cards(&ahand_dealt[epi]));
+        }
+    }
+    test_rules_manual(
+        "0 does not find partner and plays alone",
+        &ruleshochzeit,
+        vec![],
+        vec![],
+        /*n_stock*/0,
+        &[
+            (EPI0, [EA,E9,E8,E7]),
+            (EPI0, [GA,G9,G8,G7]),
+            (EPI0, [SA,S9,S8,S7]),
+            (EPI0, [EZ,EO,EU,HA]),
+            (EPI1, [GO,GU,HZ,HO]),
+            (EPI1, [SO,HU,HK,EK]),
+            (EPI1, [H7,SU,GK,GZ]),
+            (EPI2, [H9,SK,SZ,H8]),
+        ],
+        ([-60, 20, 20, 20], 0),
+    );
+}
+
+#[test]
+fn test_ruleskreuzspiel() {
+    use EPlayerIndex::*;
+    let ruleskreuzspiel = SRulesKreuzspiel::new(SPayoutDeciderParams::new(
+        /*n_payout_base*/20,
+        /*n_payout_schneider_schwarz*/10,
+        SLaufendeParams::new(10, 3),
+    ));
+    test_rules_manual(
+        "0 and 2 win",
+        &ruleskreuzspiel,
+        vec![],
+        vec![],
+        /*n_stock*/0,
+        &[
+            (EPI0, [EO,HO,GO,SO]),
+            (EPI0, [EU,SU,GU,HU]),
+            (EPI0, [EA,E8,EK,E7]),
+            (EPI0, [HA,HK,HZ,H9]),
+            (EPI0, [GA,G8,GZ,GK]),
+            (EPI0, [SA,S8,SZ,SK]),
+            (EPI0, [EZ,E9,S9,H8]),
+            (EPI3, [H7,G9,G7,S7]),
+        ],
+        ([30, -30, 30, -30], 0),
+    );
+    test_rules_manual(
+        "1 and 3 win, contra by 1",
+        &ruleskreuzspiel,
+        vec![],
+        vec![1],
+        /*n_stock*/0,
+        &[
+            (EPI0, [EA,EO,EK,E7]),
+            (EPI1, [GA,GZ,G7,G8]),
+            (EPI1, [SA,SZ,S7,S8]),
+            (EPI1, [HA,HO,HU,H9]),
+            (EPI2, [HZ,SO,GO,GU]),
+            (EPI0, [EU,HK,SU,H8]),
+            (EPI0, [EZ,H7,E8,E9]),
+            (EPI1, [SK,S9,GK,G9]),
+        ],
+        ([-40, 40, -40, 40], 0),
+    );
+}
+
+#[test]
+fn test_tout_sie_params() {
+    use EPlayerIndex::*;
+    let payoutparams = || SPayoutDeciderParams::new(/*n_payout_base*/50, /*n_payout_schneider_schwarz*/10, SLaufendeParams::new(10, 3));
+    for (toutsieparams, an_payout) in [
+        (SToutSieParams::new(/*b_laufende*/true, /*b_schneider_schwarz*/false), [-200, 600, -200, -200]),
+        (SToutSieParams::new(/*b_laufende*/false, /*b_schneider_schwarz*/false), [-100, 300, -100, -100]),
+        (SToutSieParams::new(/*b_laufende*/true, /*b_schneider_schwarz*/true), [-240, 720, -240, -240]),
+        (SToutSieParams::new(/*b_laufende*/false, /*b_schneider_schwarz*/true), [-140, 420, -140, -140]),
+    ].iter() {
+        test_rules(
+            "../../testdata/games/9.html",
+            sololike(EPlayerIndex::EPI1, EFarbe::Herz, ESoloLike::Wenz, SPayoutDeciderTout::new(payoutparams(), /*i_prio*/0, toutsieparams.clone())).upcast(),
+            [[H8,EZ,E9,E8,GA,GZ,GK,G7],[EU,GU,HU,SU,HA,HK,H9,SA],[HO,EA,EK,EO,SZ,SK,S8,S7],[HZ,H7,E7,GO,G9,G8,SO,S9],],
+            vec![],
+            vec![],
+            &[(EPI0, [GA,HA,HO,G8]),(EPI1, [GU,S7,H7,H8]),(EPI1, [EU,S8,HZ,E8]),(EPI1, [HU,EO,E7,G7]),(EPI1, [SU,EK,G9,E9]),(EPI1, [HK,SK,GO,EZ]),(EPI1, [H9,SZ,S9,GK]),(EPI1, [SA,EA,SO,GZ]),],
+            *an_payout,
+        );
+    }
+    for (toutsieparams, an_payout_won, an_payout_lost) in [
+        (SToutSieParams::new(/*b_laufende*/true, /*b_schneider_schwarz*/false), [1560, -520, -520, -520], [-1560, 520, 520, 520]),
+        (SToutSieParams::new(/*b_laufende*/false, /*b_schneider_schwarz*/false), [600, -200, -200, -200], [-600, 200, 200, 200]),
+        // schwarz only counts for the winning party
+        (SToutSieParams::new(/*b_laufende*/true, /*b_schneider_schwarz*/true), [1800, -600, -600, -600], [-1560, 520, 520, 520]),
+    ].iter() {
+        test_rules_manual(
+            "Sie (won)",
+            sololike(EPlayerIndex::EPI0, None, ESoloLike::Solo, SPayoutDeciderSie::new(payoutparams(), toutsieparams.clone())).upcast(),
+            vec![],
+            vec![],
+            /*n_stock*/0,
+            &[(EPI0, [EO,EA,EZ,EK]),(EPI0, [GO,E9,E8,E7]),(EPI0, [HO,GA,GZ,GK]),(EPI0, [SO,G9,G8,G7]),(EPI0, [EU,HA,HZ,HK]),(EPI0, [GU,H9,H8,H7]),(EPI0, [HU,SA,SZ,SK]),(EPI0, [SU,S9,S8,S7]),],
+            (*an_payout_won, 0),
+        );
+        test_rules_manual(
+            "Sie (lost)",
+            sololike(EPlayerIndex::EPI0, None, ESoloLike::Solo, SPayoutDeciderSie::new(payoutparams(), toutsieparams.clone())).upcast(),
+            vec![],
+            vec![],
+            /*n_stock*/0,
+            &[(EPI0, [EO,SU,EZ,EK]),(EPI0, [GO,E9,E8,E7]),(EPI0, [HO,GA,GZ,GK]),(EPI0, [SO,G9,G8,G7]),(EPI0, [EU,HA,HZ,HK]),(EPI0, [GU,H9,H8,H7]),(EPI0, [HU,SA,SZ,SK]),(EPI0, [EA,S9,S8,S7]),],
+            (*an_payout_lost, 0),
+        );
+    }
+}
+
+#[test]
+fn test_sie_generic() {
+    use EPlayerIndex::*;
+    use crate::rules::ruleset::*;
+    let payoutdecider = || SPayoutDeciderSie::new(
+        SPayoutDeciderParams::new(/*n_payout_base*/50, /*n_payout_schneider_schwarz*/10, SLaufendeParams::new(10, 3)),
+        SToutSieParams::new(/*b_laufende*/true, /*b_schneider_schwarz*/false),
+    );
+    test_rules_manual(
+        "Wenz-Sie",
+        sololike(EPlayerIndex::EPI0, None, ESoloLike::Wenz, payoutdecider()).upcast(),
+        vec![],
+        vec![],
+        /*n_stock*/0,
+        &[(EPI0, [EU,EZ,HZ,E8]),(EPI0, [GU,EK,HK,E7]),(EPI0, [HU,EO,HO,G8]),(EPI0, [SU,E9,H9,G7]),(EPI0, [EA,GZ,SZ,H8]),(EPI0, [GA,GK,SK,H7]),(EPI0, [HA,GO,SO,S8]),(EPI0, [SA,G9,S9,S7]),],
+        ([1560, -520, -520, -520], 0),
+    );
+    test_rules_manual(
+        "Geier-Sie",
+        sololike(EPlayerIndex::EPI0, None, ESoloLike::Geier, payoutdecider()).upcast(),
+        vec![],
+        vec![],
+        /*n_stock*/0,
+        &[(EPI0, [EO,EZ,HZ,E8]),(EPI0, [GO,EK,HK,E7]),(EPI0, [HO,EU,HU,G8]),(EPI0, [SO,E9,H9,G7]),(EPI0, [EA,GZ,SZ,H8]),(EPI0, [GA,GK,SK,H7]),(EPI0, [HA,GU,SU,S8]),(EPI0, [SA,G9,S9,S7]),],
+        ([1560, -520, -520, -520], 0),
+    );
+    test_rules_manual(
+        "Geier-Sie (lost, holding Unter instead of Ober)",
+        sololike(EPlayerIndex::EPI0, None, ESoloLike::Geier, payoutdecider()).upcast(),
+        vec![],
+        vec![],
+        /*n_stock*/0,
+        &[(EPI0, [EA,EZ,HZ,E8]),(EPI0, [GA,GZ,HK,G8]),(EPI0, [HA,EK,H9,H8]),(EPI0, [SA,E9,SZ,S8]),(EPI0, [EU,GK,SK,E7]),(EPI0, [GU,G9,S9,G7]),(EPI0, [HU,EO,SO,H7]),(EPI1, [GO,HO,S7,SU]),],
+        ([-1560, 520, 520, 520], 0),
+    );
+    let ruleset = unwrap!(SRuleSet::from_string(r"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [solo]
+        [wenz]
+        [farbgeier]
+    "));
+    assert_eq!(
+        ruleset.avecrulegroup[EPI0].iter()
+            .filter(|rulegroup| rulegroup.str_name.ends_with("Sie"))
+            .map(|rulegroup| (
+                rulegroup.str_name.clone(),
+                rulegroup.vecorules.iter().map(|orules| unwrap!(orules.as_ref()).to_string()).collect::<Vec<_>>(),
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("Sie".to_string(), vec!["Solo-Sie".to_string()]),
+            ("Wenz Sie".to_string(), vec!["Wenz-Sie".to_string()]),
+            ("Farbgeier Sie".to_string(), ["EichelGeier-Sie", "GrasGeier-Sie", "HerzGeier-Sie", "SchellnGeier-Sie"].iter().map(|str_rules| str_rules.to_string()).collect()),
+        ],
+    );
+}
+
+#[test]
+fn test_fallbackrules() {
+    use crate::rules::ruleset::*;
+    let ruleset = unwrap!(SRuleSet::from_string(r"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [der-alte-muss]
+    "));
+    let fallbackrules = unwrap!(ruleset.ofallbackrules.as_ref());
+    let test_deraltemuss = |aacard_hand: [[SCard; 8]; 4], epi_alter, slcstr_rules: &[&str]| {
+        let ahand = EPlayerIndex::map_from_raw(aacard_hand).map(TCardArrayKurzLang::to_hand);
+        let (epi_alter_found, rulegroup) = unwrap!(fallbackrules.der_alte_muss(&ahand));
+        assert_eq!(epi_alter_found, epi_alter);
+        let hand_alter = SFullHand::new(&ahand[epi_alter], EKurzLang::Lang);
+        assert_eq!(
+            rulegroup.allowed_rules(hand_alter).map(|orules| unwrap!(orules).to_string()).collect::<Vec<_>>(),
+            slcstr_rules,
+        );
+        assert_eq!(rulegroup.vecorules.len(), slcstr_rules.len()); // all offered rules can be played
+        assert!(rulegroup.vecorules.iter().all(|orules| unwrap!(orules.as_ref()).playerindex()==Some(epi_alter)));
+        assert!(fallbackrules.rules().is_none());
+    };
+    test_deraltemuss(
+        [
+            [EA,EZ,EK,E9,E8,E7,GA,GZ],
+            [EO,GO,HO,SO,EU,GU,HU,SU],
+            [HA,HZ,HK,H9,H8,H7,GK,G9],
+            [G8,G7,SA,SZ,SK,S9,S8,S7],
+        ],
+        EPlayerIndex::EPI1,
+        &["EichelSolo", "GrasSolo", "HerzSolo", "SchellnSolo"], // der Alte has no farbe at all
+    );
+    test_deraltemuss(
+        [
+            [EA,EZ,EK,E9,E8,E7,GA,GZ],
+            [HA,HZ,HK,H9,H8,H7,GK,G9],
+            [G8,G7,SA,SZ,SK,S9,S8,S7],
+            [EO,GO,HO,SO,EU,GU,HU,SK],
+        ],
+        EPlayerIndex::EPI3,
+        &["Rufspiel mit der Schelln-Sau"],
+    );
+    test_deraltemuss(
+        [
+            [EO,GO,HO,SO,EU,GU,HU,GA],
+            [HA,HZ,HK,H9,H8,H7,GK,G9],
+            [G8,G7,SK,SZ,SU,S9,S8,S7],
+            [EZ,EA,EK,E9,E8,E7,SA,GZ],
+        ],
+        EPlayerIndex::EPI0,
+        &["EichelSolo", "GrasSolo", "HerzSolo", "SchellnSolo"], // der Alte only has the Gras-Sau
+    );
+    test_deraltemuss(
+        [
+            [EO,GO,HO,SO,EA,GA,SA,HA],
+            [HU,HZ,HK,H9,H8,H7,GK,G9],
+            [G8,G7,SK,SZ,SU,S9,S8,S7],
+            [EZ,EU,EK,E9,E8,E7,GU,GZ],
+        ],
+        EPlayerIndex::EPI0,
+        &["EichelSolo", "GrasSolo", "HerzSolo", "SchellnSolo"],
+    );
+    { // der Alte has to choose his game once everybody passed
+        let ahand = EPlayerIndex::map_from_raw([
+            [EO,GO,HO,SO,EU,GU,HU,GA],
+            [HA,HZ,HK,H9,H8,H7,GK,G9],
+            [G8,G7,SK,SZ,SU,S9,S8,S7],
+            [EZ,EA,EK,E9,E8,E7,SA,GZ],
+        ]).map(TCardArrayKurzLang::to_hand);
+        let mut gamepreparations = SGamePreparations{
+            ahand,
+            doublings: SDoublings::new(SStaticEPI0{}),
+            ruleset: ruleset.clone(),
+            gameannouncements: SGameAnnouncements::new(SStaticEPI0{}),
+            n_bock: 0,
+            n_stock: 0,
+            observers: Default::default(),
+        };
+        for epi in EPlayerIndex::values() {
+            unwrap!(gamepreparations.announce_game(epi, /*orules*/None));
+        }
+        let mut determinerules = match unwrap!(gamepreparations.finish()) {
+            VGamePreparationsFinish::DetermineRules(determinerules) => determinerules,
+            _ => panic!("Expected DetermineRules"),
+        };
+        let (epi_alter, vecrulegroup) = unwrap!(determinerules.which_player_can_do_something());
+        assert_eq!(epi_alter, EPlayerIndex::EPI0);
+        assert!(determinerules.resign(epi_alter).is_err());
+        let rules = unwrap!(allowed_rules(&vecrulegroup, determinerules.fullhand(epi_alter))
+            .flatten()
+            .find(|rules| rules.to_string()=="GrasSolo"));
+        assert!(determinerules.announce_game(epi_alter, TActivelyPlayableRulesBoxClone::box_clone(rules)).is_err()); // der Alte chooses among the offered rules
+        assert!(determinerules.announce_game_der_alte(epi_alter, /*i_rules*/4).is_err());
+        unwrap!(determinerules.announce_game_der_alte(epi_alter, unwrap!(vecrulegroup[0].position(rules))));
+        assert!(determinerules.which_player_can_do_something().is_none());
+        assert_eq!(unwrap!(determinerules.finish()).rules.to_string(), "GrasSolo");
+    }
+    assert!(SRuleSet::from_string(r"
+        base-price=10
+        solo-price=50
+        lauf-min=3
+        [ramsch]
+        price=10
+        [kreuzspiel]
+    ").is_err());
+}
+
+#[test]
+fn test_stoss_deadline() {
+    use EPlayerIndex::*;
+    let vecstich = make_stich_vector(&[
+        (EPI0, [EO, GO, HO, SO]),
+        (EPI0, [EU, GU, HU, SU]),
+        (EPI0, [HA, HZ, HK, H9]),
+        (EPI0, [EZ, EA, EK, E9]),
+        (EPI1, [E8, E7, S7, S8]),
+        (EPI1, [SA, SZ, SK, S9]),
+        (EPI1, [GA, GZ, GK, H8]),
+        (EPI0, [H7, G9, G8, G7]),
+    ]);
+    let rulesrufspiel = rulesrufspiel_new_test(
+        EPlayerIndex::EPI0,
+        EFarbe::Eichel,
+        /*n_payout_base*/10,
+        /*n_payout_schneider_schwarz*/10,
+        SLaufendeParams::new(10, 3),
+    );
+    for (estossdeadline, slcvecepi_stoss_expected) in [
+        (EStossDeadline::NoCardPlayed, [vec![EPI2, EPI3], vec![], vec![], vec![], vec![]]),
+        (EStossDeadline::OwnFirstCard, [vec![EPI2, EPI3], vec![EPI2, EPI3], vec![EPI2, EPI3], vec![EPI3], vec![]]),
+        (EStossDeadline::FirstStich, [vec![EPI2, EPI3], vec![EPI2, EPI3], vec![EPI2, EPI3], vec![EPI2, EPI3], vec![]]),
+    ] {
+        let mut game = SGame::new(
+            EPlayerIndex::map_from_fn(|epi|
+                SHand::new_from_iter(vecstich.iter().map(|stich| stich[epi]))
+            ),
+            SDoublings::new_full(SStaticEPI0{}, [false; EPlayerIndex::SIZE]),
+            Some(SStossParams::new(/*n_stoss_max*/4, estossdeadline)),
+            Box::new(rulesrufspiel.clone()),
+            /*n_bock*/0,
+            /*n_stock*/0,
+        );
+        for (i_card, vecepi_stoss_expected) in slcvecepi_stoss_expected.iter().enumerate() {
+            assert_eq!(&unwrap!(game.which_player_can_do_something()).1, vecepi_stoss_expected, "{:?}, {} cards played", estossdeadline, i_card);
+            if i_card<EPlayerIndex::SIZE {
+                let (epi, card) = unwrap!(vecstich[0].iter().nth(i_card));
+                unwrap!(game.zugeben(*card, epi));
+            }
+        }
+    }
+    // re after contra within first stich: rufspiel partner is determined by initial hand
+    let mut game = SGame::new(
+        EPlayerIndex::map_from_fn(|epi|
+            SHand::new_from_iter(vecstich.iter().map(|stich| stich[epi]))
+        ),
+        SDoublings::new_full(SStaticEPI0{}, [false; EPlayerIndex::SIZE]),
+        Some(SStossParams::new(/*n_stoss_max*/4, EStossDeadline::FirstStich)),
+        Box::new(rulesrufspiel.clone()),
+        /*n_bock*/0,
+        /*n_stock*/0,
+    );
+    for (epi, card) in vecstich[0].iter().take(3) {
+        unwrap!(game.zugeben(*card, epi));
+    }
+    unwrap!(game.stoss(EPI3));
+    assert_eq!(unwrap!(game.which_player_can_do_something()).1, vec![EPI0, EPI1]);
+    unwrap!(game.stoss(EPI1));
+    assert_eq!(unwrap!(game.which_player_can_do_something()).1, vec![EPI2, EPI3]);
+}
+
+#[test]
+fn test_schneiderschwarz_announcement() {
+    use EPlayerIndex::*;
+    // EPI0 makes schneider, but EPI3 wins the last stich
+    let vecstich = make_stich_vector(&[
+        (EPI0, [EU,GA,HA,SZ]),
+        (EPI0, [GU,GZ,HZ,SK]),
+        (EPI0, [HU,GK,HK,SO]),
+        (EPI0, [SU,G9,H9,S8]),
+        (EPI0, [EA,E9,E8,E7]),
+        (EPI0, [EZ,GO,HO,EO]),
+        (EPI0, [EK,G8,H8,S9]),
+        (EPI0, [S7,G7,H7,SA]),
+    ]);
+    let payoutdecider = SPayoutDeciderPointBased{
+        schneiderschwarzannouncement: SSchneiderSchwarzAnnouncement{
+            on_payout_announced: Some(10),
+            oeschneiderschwarz: None,
+        },
+        ..SPayoutDeciderPointBased::new(
+            SPayoutDeciderParams::new(/*n_payout_base*/50, /*n_payout_schneider_schwarz*/10, SLaufendeParams::new(10, 2)),
+            VGameAnnouncementPrioritySoloLike::SoloSimple(0),
+        )
+    };
+    let new_game = || SGame::new(
+        EPlayerIndex::map_from_fn(|epi|
+            SHand::new_from_iter(vecstich.iter().map(|stich| stich[epi]))
+        ),
+        SDoublings::new_full(SStaticEPI0{}, [false; EPlayerIndex::SIZE]),
+        /*ostossparams*/None,
+        sololike(EPI0, None, ESoloLike::Wenz, payoutdecider.clone()).upcast_box(),
+        /*n_bock*/0,
+        /*n_stock*/0,
+    );
+    for (oeschneiderschwarz, an_payout) in [
+        (None, [300, -100, -100, -100]),
+        (Some(ESchneiderSchwarz::Schneider), [330, -110, -110, -110]),
+        (Some(ESchneiderSchwarz::Schwarz), [-330, 110, 110, 110]), // missed announcement loses the game
+    ] {
+        let mut game = new_game();
+        assert_eq!(
+            game.schneiderschwarz_announceable(),
+            Some((EPI0, vec![ESchneiderSchwarz::Schneider, ESchneiderSchwarz::Schwarz])),
+        );
+        if let Some(eschneiderschwarz) = oeschneiderschwarz {
+            assert!(game.announce_schneiderschwarz(EPI1, eschneiderschwarz).is_err());
+            unwrap!(game.announce_schneiderschwarz(EPI0, eschneiderschwarz));
+        }
+        for stich in vecstich.iter() {
+            for (epi, card) in stich.iter() {
+                unwrap!(game.zugeben(*card, epi));
+                assert_eq!(game.schneiderschwarz_announceable(), None);
+            }
+        }
+        assert_eq!(game.oeschneiderschwarz_announced, oeschneiderschwarz);
+        assert_eq!(unwrap!(game.finish()).an_payout.into_raw(), an_payout);
+    }
+    let mut game = new_game();
+    unwrap!(game.announce_schneiderschwarz(EPI0, ESchneiderSchwarz::Schneider));
+    assert_eq!(game.schneiderschwarz_announceable(), Some((EPI0, vec![ESchneiderSchwarz::Schwarz])));
+    assert!(game.announce_schneiderschwarz(EPI0, ESchneiderSchwarz::Schneider).is_err());
+}
+
+#[test]
+fn test_bock() {
+    use EPlayerIndex::*;
+    let ruleset = unwrap!(SRuleSet::from_string(r#"
+        base-price=10
+        solo-price=50
+        lauf-min=2
+        [wenz]
+        announce-schneider-schwarz=true
+        [bock]
+        rounds=1
+        triggers=["kontra-lost", "60-60"]
+    "#));
+    let bockparams = unwrap!(ruleset.obockparams.as_ref());
+    assert_eq!(bockparams.n_games, 4);
+    assert_eq!(bockparams.vecebocktrigger, vec![EBockTrigger::KontraLost, EBockTrigger::SechzigSechzig]);
+    // EPI0 wins all stichs but the last one
+    let vecstich = make_stich_vector(&[
+        (EPI0, [EU,GA,HA,SZ]),
+        (EPI0, [GU,GZ,HZ,SK]),
+        (EPI0, [HU,GK,HK,SO]),
+        (EPI0, [SU,G9,H9,S8]),
+        (EPI0, [EA,E9,E8,E7]),
+        (EPI0, [EZ,GO,HO,EO]),
+        (EPI0, [EK,G8,H8,S9]),
+        (EPI0, [S7,G7,H7,SA]),
+    ]);
+    let play_game = |n_bock, ostoss, oeschneiderschwarz| {
+        let mut game = SGame::new(
+            EPlayerIndex::map_from_fn(|epi|
+                SHand::new_from_iter(vecstich.iter().map(|stich| stich[epi]))
+            ),
+            SDoublings::new_full(SStaticEPI0{}, [false; EPlayerIndex::SIZE]),
+            ruleset.ostossparams.clone().or_else(|| Some(SStossParams::new(/*n_stoss_max*/4, EStossDeadline::NoCardPlayed))),
+            unwrap!(ruleset.avecrulegroup[EPI0].iter()
+                .find(|rulegroup| rulegroup.str_name=="Wenz")
+                .and_then(|rulegroup| rulegroup.vecorules[0].as_ref())
+            ).upcast().box_clone(),
+            n_bock,
+            /*n_stock*/0,
+        );
+        if let Some(epi_stoss) = ostoss {
+            unwrap!(game.stoss(epi_stoss));
+        }
+        if let Some(eschneiderschwarz) = oeschneiderschwarz {
+            unwrap!(game.announce_schneiderschwarz(EPI0, eschneiderschwarz));
+        }
+        for stich in vecstich.iter() {
+            for (epi, card) in stich.iter() {
+                unwrap!(game.zugeben(*card, epi));
+            }
+        }
+        unwrap!(game.finish())
+    };
+    assert_eq!(play_game(/*n_bock*/0, None, None).an_payout.into_raw(), [300, -100, -100, -100]);
+    assert_eq!(play_game(/*n_bock*/1, None, None).an_payout.into_raw(), [600, -200, -200, -200]);
+    assert_eq!(play_game(/*n_bock*/2, None, None).an_payout.into_raw(), [1200, -400, -400, -400]);
+    let mut bock = SBock::default();
+    // won game with kontra does not trigger bock
+    bock.register_gameresult(Some(bockparams), &play_game(/*n_bock*/0, Some(EPI1), None));
+    assert_eq!(bock.n_bock(), 0);
+    // lost game with kontra triggers bock for the next round
+    let gameresult_lost = play_game(/*n_bock*/0, Some(EPI1), Some(ESchneiderSchwarz::Schwarz));
+    assert!(gameresult_lost.bock_triggered(EBockTrigger::KontraLost));
+    assert!(!gameresult_lost.bock_triggered(EBockTrigger::SechzigSechzig));
+    bock.register_gameresult(Some(bockparams), &gameresult_lost);
+    for _i_game in 0..bockparams.n_games-1 {
+        assert_eq!(bock.n_bock(), 1);
+        bock.register_gameresult(Some(bockparams), &play_game(bock.n_bock(), None, None));
+    }
+    assert_eq!(bock.n_bock(), 1);
+    bock.register_gameresult(Some(bockparams), &play_game(bock.n_bock(), Some(EPI1), Some(ESchneiderSchwarz::Schwarz)));
+    assert_eq!(bock.n_bock(), 1); // previous bock ended, new bock started
+    bock.register_gameresult(None, &play_game(bock.n_bock(), Some(EPI1), Some(ESchneiderSchwarz::Schwarz)));
+    assert_eq!(bock.n_bock(), 1);
+    let play_stichs = |rules: Box<dyn TRules>, ostoss, slcpairepiacard_stich: &[(EPlayerIndex, [SCard; 4])]| {
+        let vecstich = make_stich_vector(slcpairepiacard_stich);
+        let mut game = SGame::new(
+            EPlayerIndex::map_from_fn(|epi|
+                SHand::new_from_iter(vecstich.iter().map(|stich| stich[epi]))
+            ),
+            SDoublings::new(SStaticEPI0{}),
+            Some(SStossParams::new(/*n_stoss_max*/4, EStossDeadline::NoCardPlayed)),
+            rules,
+            /*n_bock*/0,
+            /*n_stock*/0,
+        );
+        if let Some(epi_stoss) = ostoss {
+            unwrap!(game.stoss(epi_stoss));
+        }
+        for stich in vecstich.iter() {
+            for (epi, card) in stich.iter() {
+                unwrap!(game.zugeben(*card, epi));
+            }
+        }
+        unwrap!(game.finish())
+    };
+    // EPI0 plays a solo and gets exactly 60 points
+    let gameresult_sechzig = play_stichs(
+        sololike(
+            EPI0,
+            EFarbe::Herz,
+            ESoloLike::Solo,
+            SPayoutDeciderPointBased::new(
+                SPayoutDeciderParams::new(/*n_payout_base*/50, /*n_payout_schneider_schwarz*/10, SLaufendeParams::new(10, 3)),
+                VGameAnnouncementPrioritySoloLike::SoloSimple(0),
+            ),
+        ).upcast_box(),
+        /*ostoss*/None,
+        &[
+            (EPI0, [EO,GO,HO,SO]),
+            (EPI0, [EU,GU,HU,SU]),
+            (EPI0, [HA,HK,H9,H8]),
+            (EPI0, [EA,EZ,EK,E9]),
+            (EPI0, [G7,GA,G8,G9]),
+            (EPI1, [HZ,H7,SA,E8]),
+            (EPI1, [GZ,GK,SZ,E7]),
+            (EPI1, [S9,S8,SK,S7]),
+        ],
+    );
+    assert!(gameresult_sechzig.an_payout[EPI0] < 0);
+    assert!(gameresult_sechzig.bock_triggered(EBockTrigger::SechzigSechzig));
+    assert!(!gameresult_sechzig.bock_triggered(EBockTrigger::KontraLost));
+    assert!(!gameresult_sechzig.bock_triggered(EBockTrigger::Durchmarsch));
+    let mut bock = SBock::default();
+    bock.register_gameresult(Some(bockparams), &gameresult_sechzig);
+    assert_eq!(bock.n_bock(), 1);
+    // EPI0 wins all stichs in a ramsch
+    let slcpairepiacard_stich_durchmarsch = [
+        (EPI0, [EO,GO,HO,SO]),
+        (EPI0, [EU,GU,HU,SU]),
+        (EPI0, [HA,HZ,HK,H9]),
+        (EPI0, [EA,EZ,EK,E9]),
+        (EPI0, [GA,GZ,GK,G9]),
+        (EPI0, [SA,SZ,SK,S9]),
+        (EPI0, [H8,E8,G8,S8]),
+        (EPI0, [H7,E7,G7,S7]),
+    ];
+    for (durchmarsch, b_durchmarsch) in [
+        (VDurchmarsch::All, true),
+        (VDurchmarsch::AtLeast(91), true),
+        (VDurchmarsch::None, false),
+    ] {
+        let gameresult_ramsch = play_stichs(
+            Box::new(SRulesRamsch::new(/*n_price*/10, durchmarsch, EJungfrau::Ignore, /*b_schieben*/false, /*b_loser_pays_into_stock*/false)),
+            /*ostoss*/None,
+            &slcpairepiacard_stich_durchmarsch,
+        );
+        assert_eq!(gameresult_ramsch.bock_triggered(EBockTrigger::Durchmarsch), b_durchmarsch);
+        assert!(!gameresult_ramsch.bock_triggered(EBockTrigger::SechzigSechzig));
+    }
+    // Kreuzspiel has no active player, but EPI0 and EPI2 form the primary party
+    let gameresult_kreuzspiel = play_stichs(
+        Box::new(SRulesKreuzspiel::new(SPayoutDeciderParams::new(/*n_payout_base*/10, /*n_payout_schneider_schwarz*/10, SLaufendeParams::new(10, 3)))),
+        /*ostoss*/Some(EPI1),
+        &[ // EPI1 wins all stichs
+            (EPI0, [SO,EO,GO,HO]),
+            (EPI1, [EU,GU,HU,SU]),
+            (EPI1, [HA,HZ,HK,H9]),
+            (EPI1, [EA,EZ,EK,E9]),
+            (EPI1, [GA,GZ,GK,G9]),
+            (EPI1, [SA,SZ,SK,S9]),
+            (EPI1, [H8,E8,G8,S8]),
+            (EPI1, [H7,E7,G7,S7]),
+        ],
+    );
+    assert!(gameresult_kreuzspiel.an_payout[EPI0] < 0);
+    assert!(gameresult_kreuzspiel.bock_triggered(EBockTrigger::KontraLost));
+    assert!(!gameresult_kreuzspiel.bock_triggered(EBockTrigger::SechzigSechzig));
+}
+
 #[test]
 fn test_stock() {
     use EPlayerIndex::*;
@@ -1955,3 +2892,209 @@ fn test_stock() {
         );
     }
 }
+
+#[test]
+fn test_gamerecord() {
+    use crate::game::record::SGameRecord;
+    use crate::rules::ruleset::VStockOrT;
+    use EPlayerIndex::*;
+    let ruleset = unwrap!(SRuleSet::from_string(r#"
+        base-price=10
+        solo-price=50
+        lauf-min=2
+        [wenz]
+        announce-schneider-schwarz=true
+        [stock]
+        [doubling]
+        [stoss]
+        deadline="first-stich"
+    "#));
+    let vecstich = make_stich_vector(&[
+        (EPI0, [EU,GA,HA,SZ]),
+        (EPI0, [GU,GZ,HZ,SK]),
+        (EPI0, [HU,GK,HK,SO]),
+        (EPI0, [SU,G9,H9,S8]),
+        (EPI0, [EA,E9,E8,E7]),
+        (EPI0, [EZ,GO,HO,EO]),
+        (EPI0, [EK,G8,H8,S9]),
+        (EPI0, [S7,G7,H7,SA]),
+    ]);
+    let prepare_game = |b_play_wenz| {
+        let mut gamepreparations = SGamePreparations{
+            ahand: EPlayerIndex::map_from_fn(|epi|
+                SHand::new_from_iter(vecstich.iter().map(|stich| stich[epi]))
+            ),
+            doublings: SDoublings::new_full(SStaticEPI0{}, [false, true, false, false]),
+            ruleset: ruleset.clone(),
+            gameannouncements: SGameAnnouncements::new(SStaticEPI0{}),
+            n_bock: 0,
+            n_stock: 0,
+            observers: Default::default(),
+        };
+        for epi in EPlayerIndex::values() {
+            let orules = if_then_some!(b_play_wenz && EPI0==epi,
+                TActivelyPlayableRulesBoxClone::box_clone(unwrap!(ruleset.avecrulegroup[EPI0].iter()
+                    .find(|rulegroup| rulegroup.str_name=="Wenz")
+                    .and_then(|rulegroup| rulegroup.vecorules[0].as_ref())
+                ).as_ref())
+            );
+            unwrap!(gamepreparations.announce_game(epi, orules));
+        }
+        unwrap!(gamepreparations.finish())
+    };
+    let mut game = match prepare_game(/*b_play_wenz*/true) {
+        VGamePreparationsFinish::DetermineRules(determinerules) => unwrap!(determinerules.finish()),
+        _ => panic!("Expected DetermineRules"),
+    };
+    unwrap!(game.announce_schneiderschwarz(EPI0, ESchneiderSchwarz::Schneider));
+    unwrap!(game.stoss(EPI1));
+    for (i_stich, stich) in vecstich.iter().enumerate() {
+        for (epi, card) in stich.iter() {
+            if 0==i_stich && EPI2==epi {
+                unwrap!(game.stoss(EPI0)); // re after two cards were played
+            }
+            unwrap!(game.zugeben(*card, epi));
+        }
+    }
+    let gameresult = unwrap!(game.finish());
+    let gamerecord = gameresult.gamerecord.clone();
+    assert_eq!(gamerecord.bidhistory.vecorulesid_gameannouncement.len(), EPlayerIndex::SIZE);
+    assert_eq!(gamerecord.an_payout, gameresult.an_payout.explicit_clone().into_raw());
+    let gamerecord_read : SGameRecord = unwrap!(serde_json::from_str(&unwrap!(serde_json::to_string(&gamerecord))));
+    assert_eq!(gamerecord_read, gamerecord);
+    match unwrap!(gamerecord_read.to_game()) {
+        VStockOrT::OrT(game) => {
+            assert_eq!(
+                game.vecstoss.iter().map(|stoss| (stoss.epi, stoss.n_cards_played)).collect::<Vec<_>>(),
+                vec![(EPI1, 0), (EPI0, 2)],
+            );
+            assert_eq!(unwrap!(game.finish()).gamerecord, gamerecord);
+        },
+        VStockOrT::Stock(()) => panic!("Expected game"),
+    }
+    let mut gamerecord_invalid = gamerecord.clone();
+    gamerecord_invalid.veccard_played.swap(0, 1);
+    assert!(gamerecord_invalid.to_game().is_err());
+    match prepare_game(/*b_play_wenz*/false) {
+        VGamePreparationsFinish::Stock(gameresult) => {
+            assert!(matches!(unwrap!(gameresult.gamerecord.to_game()), VStockOrT::Stock(())));
+        },
+        _ => panic!("Expected Stock"),
+    }
+}
+
+#[test]
+fn test_game_rewind() {
+    use EPlayerIndex::*;
+    let vecstich = make_stich_vector(&[
+        (EPI0, [EO, GO, HO, SO]),
+        (EPI0, [EU, GU, HU, SU]),
+        (EPI0, [HA, HZ, HK, H9]),
+        (EPI0, [EZ, EA, EK, E9]),
+        (EPI1, [E8, E7, S7, S8]),
+        (EPI1, [SA, SZ, SK, S9]),
+        (EPI1, [GA, GZ, GK, H8]),
+        (EPI0, [H7, G9, G8, G7]),
+    ]);
+    let mut game = SGame::new(
+        EPlayerIndex::map_from_fn(|epi|
+            SHand::new_from_iter(vecstich.iter().map(|stich| stich[epi]))
+        ),
+        SDoublings::new_full(SStaticEPI0{}, [false; EPlayerIndex::SIZE]),
+        Some(SStossParams::new(/*n_stoss_max*/4, EStossDeadline::FirstStich)),
+        Box::new(rulesrufspiel_new_test(
+            EPlayerIndex::EPI0,
+            EFarbe::Eichel,
+            /*n_payout_base*/10,
+            /*n_payout_schneider_schwarz*/10,
+            SLaufendeParams::new(10, 3),
+        )),
+        /*n_bock*/0,
+        /*n_stock*/0,
+    );
+    assert!(game.undo().is_err());
+    unwrap!(game.stoss(EPI2));
+    for (epi, card) in vecstich[0].iter().take(2) {
+        unwrap!(game.zugeben(*card, epi));
+    }
+    unwrap!(game.stoss(EPI0));
+    for (epi, card) in vecstich[0].iter().skip(2).chain(vecstich[1].iter()) {
+        unwrap!(game.zugeben(*card, epi));
+    }
+    unwrap!(game.zugeben(HA, EPI0));
+    let assert_state = |game: &SGame, n_cards_played: usize, vecepi_stoss: Vec<EPlayerIndex>| {
+        assert_eq!(game.stichseq.count_played_cards(), n_cards_played);
+        assert_eq!(game.vecstoss.iter().map(|stoss| stoss.epi).collect::<Vec<_>>(), vecepi_stoss);
+        for epi in EPlayerIndex::values() {
+            assert_eq!(
+                game.ahand[epi].cards().len() + game.stichseq.visible_stichs().iter().filter(|stich| stich.get(epi).is_some()).count(),
+                EKurzLang::Lang.cards_per_player(),
+            );
+        }
+    };
+    assert_state(&game, 9, vec![EPI2, EPI0]);
+    unwrap!(game.undo());
+    assert_state(&game, 8, vec![EPI2, EPI0]);
+    assert!(game.ahand[EPI0].contains(HA));
+    assert!(game.rewind_to(/*i_stich*/2, /*n_cards*/1).is_err());
+    assert!(game.rewind_to(/*i_stich*/0, /*n_cards*/4).is_err());
+    unwrap!(game.undo_until(EPI3));
+    assert_state(&game, 7, vec![EPI2, EPI0]);
+    assert_eq!(unwrap!(game.which_player_can_do_something()).0, EPI3);
+    unwrap!(game.rewind_to(/*i_stich*/0, /*n_cards*/2));
+    assert_state(&game, 2, vec![EPI2, EPI0]);
+    unwrap!(game.rewind_to(/*i_stich*/0, /*n_cards*/1));
+    assert_state(&game, 1, vec![EPI2]);
+    assert_eq!(unwrap!(game.which_player_can_do_something()).1, vec![EPI0, EPI1]);
+    unwrap!(game.rewind_to(/*i_stich*/0, /*n_cards*/0));
+    assert_state(&game, 0, vec![EPI2]);
+    unwrap!(game.zugeben(EO, EPI0));
+}
+
+#[test]
+fn test_dreier() {
+    use EPlayerIndex::*;
+    for str_ruleset_invalid in [
+        "base-price=10\nplayers=3\ndeck=\"kurz\"\n[solo]",
+        "base-price=10\nplayers=5\n[solo]",
+        "base-price=10\nlauf-min=3\nplayers=3\n[rufspiel]",
+        "base-price=10\nlauf-min=3\nplayers=3\n[hochzeit]",
+        "base-price=10\nlauf-min=3\nplayers=3\n[kreuzspiel]",
+    ] {
+        assert!(SRuleSet::from_string(str_ruleset_invalid).is_err());
+    }
+    let ruleset = unwrap!(SRuleSet::from_string("base-price=10\nsolo-price=50\nlauf-min=3\nplayers=3\n[solo]"));
+    assert_eq!(ruleset.ekurzlang, EKurzLang::Dreier);
+    let ahand = EnumMap::from_raw([
+        SHand::new_from_iter([EO, GO, HO, SO, EU, GU, HU, SU, HA, HZ]),
+        SHand::new_from_iter([EA, EZ, EK, E9, E8, E7, GA, GZ, GK, G9]),
+        SHand::new_from_iter([G8, G7, SA, SZ, SK, S9, S8, S7, HK, H9]),
+        SHand::new_from_iter([]), // H8 and H7 remain in the stock
+    ]);
+    let rules = unwrap!(crate::rules::ruleset::allowed_rules(&ruleset.avecrulegroup[EPI0], SFullHand::new(&ahand[EPI0], ruleset.ekurzlang))
+        .flatten()
+        .find(|rules| "HerzSolo"==rules.to_string()));
+    let mut doublings = SDoublings::new_with_players(SStaticEPI0{}, ruleset.ekurzlang.n_players());
+    for _epi in ruleset.ekurzlang.players() {
+        doublings.push(false);
+    }
+    let mut game = SGame::new(
+        ahand,
+        doublings,
+        /*ostossparams*/None,
+        TActivelyPlayableRulesBoxClone::box_clone(rules).upcast().box_clone(),
+        /*n_bock*/0,
+        /*n_stock*/0,
+    );
+    for acard_stich in [
+        [EO, EA, HK], [GO, EZ, H9], [HO, EK, G8], [SO, E9, G7], [EU, E8, SA],
+        [GU, E7, SZ], [HU, GA, SK], [SU, GZ, S9], [HA, GK, S8], [HZ, G9, S7],
+    ] {
+        for (epi, card) in ruleset.ekurzlang.players().zip(acard_stich) {
+            assert_eq!(unwrap!(game.which_player_can_do_something()).0, epi); // EPI0 wins each stich
+            unwrap!(game.zugeben(card, epi));
+        }
+    }
+    // 50 (solo) + 20 (schwarz) + 10*10 (laufende, HK ends the run) from each of the two opponents
+    assert_eq!(unwrap!(game.finish()).an_payout.into_raw(), [340, -170, -170, 0]);
+}
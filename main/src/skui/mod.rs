@@ -207,6 +207,7 @@ pub struct SAskForAlternativeKeyBindings {
     key_next : i32,
     key_choose : i32,
     key_suggest : i32,
+    okey_undo : Option<i32>,
 }
 
 pub fn choose_card_from_hand_key_bindings() -> SAskForAlternativeKeyBindings {
@@ -215,6 +216,14 @@ pub fn choose_card_from_hand_key_bindings() -> SAskForAlternativeKeyBindings {
         key_next : ncurses::KEY_RIGHT,
         key_choose : ncurses::KEY_UP,
         key_suggest : '?' as i32,
+        okey_undo : None,
+    }
+}
+
+pub fn choose_card_from_hand_or_undo_key_bindings() -> SAskForAlternativeKeyBindings {
+    SAskForAlternativeKeyBindings {
+        okey_undo : Some('u' as i32),
+        ..choose_card_from_hand_key_bindings()
     }
 }
 
@@ -224,6 +233,7 @@ pub fn choose_alternative_from_list_key_bindings() -> SAskForAlternativeKeyBindi
         key_next : ncurses::KEY_DOWN,
         key_choose : ncurses::KEY_RIGHT,
         key_suggest : '?' as i32,
+        okey_undo : None,
     }
 }
 
@@ -234,6 +244,21 @@ pub fn ask_for_alternative<'vect, T>(
     fn_callback: impl Fn(ncurses::WINDOW, usize, &Option<T>),
     fn_suggest: impl Fn()->Option<T>
 ) -> &'vect T {
+    assert!(askforalternativekeybindings.okey_undo.is_none());
+    match ask_for_alternative_or_undo(vect, askforalternativekeybindings, fn_filter, fn_callback, fn_suggest) {
+        Some(t) => t,
+        None => unreachable!(), // no undo key
+    }
+}
+
+/// Returns None if the user pressed the undo key.
+pub fn ask_for_alternative_or_undo<'vect, T>(
+    vect: &'vect [T],
+    askforalternativekeybindings: &SAskForAlternativeKeyBindings,
+    fn_filter: impl Fn(&T)->bool,
+    fn_callback: impl Fn(ncurses::WINDOW, usize, &Option<T>),
+    fn_suggest: impl Fn()->Option<T>
+) -> Option<&'vect T> {
     do_in_window(&VSkUiWindow::Interaction, |ncwin| {
         let mut ot_suggest = None;
         let vect = vect.iter().enumerate().filter(|&(_i_t, t)| fn_filter(t)).collect::<Vec<_>>();
@@ -241,10 +266,13 @@ pub fn ask_for_alternative<'vect, T>(
         let mut i_alternative = 0; // initially, point to 0th alternative
         fn_callback(ncwin, vect[i_alternative].0, &ot_suggest);
         ncurses::refresh();
-        if 1<vect.len() {
+        if 1<vect.len() || askforalternativekeybindings.okey_undo.is_some() {
             let mut ch = askforalternativekeybindings.key_prev;
             while ch!=askforalternativekeybindings.key_choose {
-                if ch==askforalternativekeybindings.key_prev {
+                if Some(ch)==askforalternativekeybindings.okey_undo {
+                    ncurses::werase(ncwin);
+                    return None;
+                } else if ch==askforalternativekeybindings.key_prev {
                     if 0<i_alternative {
                         i_alternative -= 1
                     }
@@ -262,7 +290,7 @@ pub fn ask_for_alternative<'vect, T>(
             }
         }
         ncurses::werase(ncwin);
-        vect[i_alternative].1
+        Some(vect[i_alternative].1)
     })
 }
 
@@ -1,6 +1,7 @@
 use crate::game_analysis::*;
 use crate::game::*;
-use crate::rules::ruleset::SStossParams;
+use crate::game::record::SGameRecord;
+use crate::rules::ruleset::{EStossDeadline, SStossParams, VStockOrT};
 use crate::primitives::*;
 use crate::primitives::cardvector::*;
 use crate::util::{*, parser::*};
@@ -15,6 +16,7 @@ pub fn subcommand(str_subcommand: &str) -> clap::App {
             .takes_value(true)
             .multiple(true)
         )
+        .arg(super::clap_arg_no_equivalent_cards())
 }
 
 pub fn analyze_sauspiel_html(str_html: &str) -> Result<SGame, failure::Error> {
@@ -153,8 +155,9 @@ pub fn analyze_sauspiel_html(str_html: &str) -> Result<SGame, failure::Error> {
                 ).into_raw(),
             )
         },
-        /*ostossparams*/Some(SStossParams::new(/*n_stoss_max*/4)),
+        /*ostossparams*/Some(SStossParams::new(/*n_stoss_max*/4, EStossDeadline::OwnFirstCard)),
         rules,
+        /*n_bock*/0,
         /*n_stock*/0, // Sauspiel does not support stock
     );
     for resepi in get_doublings_stoss("Kontra und Retour")? {
@@ -195,6 +198,9 @@ fn analyze_plain(str_lines: &str) -> impl Iterator<Item=Result<SGame, failure::E
                 ),
                 /*ostossparams*/None,
                 /*vecstoss*/vec![],
+                /*oeschneiderschwarz_announced*/None,
+                /*ocardexchange*/None,
+                /*n_bock*/0,
                 /*n_stock*/0,
                 SStichSequenceGameFinished::new(&stichseq),
                 /*fn_before_zugeben*/|_game, _i_stich, _epi, _card| {},
@@ -224,6 +230,17 @@ pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
                         };
                         if let resgame@Ok(_) = analyze_sauspiel_html(&str_input) {
                             push_game(path.to_string_lossy().into_owned(), resgame)
+                        } else if let Ok(vecgamerecord) = str_input.lines()
+                            .map(serde_json::from_str::<SGameRecord>)
+                            .collect::<Result<Vec<_>, _>>()
+                        {
+                            for (i, gamerecord) in vecgamerecord.iter().enumerate() {
+                                match gamerecord.to_game() {
+                                    Ok(VStockOrT::Stock(())) => {/*nothing to analyze*/},
+                                    Ok(VStockOrT::OrT(game)) => push_game(format!("{}_{}", path.to_string_lossy(), i), Ok(game)),
+                                    Err(err) => push_game(format!("{}_{}", path.to_string_lossy(), i), Err(err)),
+                                }
+                            }
                         } else {
                             let mut b_found_plain = false;
                             for (i, resgame) in analyze_plain(&str_input).filter(|res| res.is_ok()).enumerate() {
@@ -249,5 +266,6 @@ pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
         &std::path::Path::new("./analyze"), // TODO make customizable
         /*fn_link*/|str_description: &str| str_description.to_string(),
         vecgame.into_iter(),
+        /*b_equivalent_cards*/!clapmatches.is_present("no_equivalent_cards"),
     )
 }
@@ -1,4 +1,4 @@
-use crate::game::run::run_simple_game_loop;
+use crate::game::{run::run_simple_game_loop, session::{SSession, VSessionLength}};
 use crate::player::{*, playercomputer::*, playerhuman::*};
 use crate::primitives::*;
 use crate::skui;
@@ -10,28 +10,55 @@ pub fn subcommand(str_subcommand: &str) -> clap::App {
         .about("Simulate players to play against")
         .arg(clap_arg("ruleset", "rulesets/default.toml"))
         .arg(clap_arg("ai", "cheating"))
+        .arg(super::clap_arg_time_budget())
+        .arg(super::clap_arg_no_equivalent_cards())
+        .arg(super::clap_arg_cache_size())
         .arg(clap_arg("numgames", "4"))
+        .arg(super::clap_arg_seed())
+        .arg(clap::Arg::with_name("record").long("record").takes_value(true).help("Append finished games to this file"))
+        .arg(super::clap_arg_deals())
 }
 
 pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
-    let _tui = skui::STuiGuard::init_ui();
-    let (mut aattable, n_stock) = run_simple_game_loop(
-        /*aplayer*/EPlayerIndex::map_from_fn(|epi| -> Box<dyn TPlayer> {
-            if EPlayerIndex::EPI1==epi {
-                Box::new(SPlayerHuman{ai : super::ai(clapmatches)})
-            } else {
-                Box::new(SPlayerComputer{ai: super::ai(clapmatches)})
-            }
-        }),
-        /*n_games*/unwrap!(clapmatches.value_of("numgames")).parse::<usize>().unwrap_or(4),
-        super::get_ruleset(clapmatches)?,
+    super::seed_rng(clapmatches)?;
+    let ruleset = super::get_ruleset(clapmatches)?;
+    let dealsource = super::get_dealsource(clapmatches, &ruleset)?;
+    let n_games = match dealsource.count_deals() {
+        Some(n_deals) if clapmatches.occurrences_of("numgames")==0 => n_deals,
+        _ => unwrap!(clapmatches.value_of("numgames")).parse::<usize>().unwrap_or(4),
+    };
+    let mut session = SSession::new(
+        ruleset,
+        dealsource,
+        VSessionLength::Games(n_games),
+        /*mapepiparticipant*/EPlayerIndex::map_from_fn(|epi| epi),
+        /*opath_record*/clapmatches.value_of("record").map(std::path::PathBuf::from),
     );
-    aattable.sort_unstable_by_key(|attable| attable.n_money);
+    let aplayer = EPlayerIndex::map_from_fn(|epi| -> Box<dyn TPlayer> {
+        if EPlayerIndex::EPI1==epi {
+            Box::new(SPlayerHuman{ai : super::ai(clapmatches)})
+        } else {
+            Box::new(SPlayerComputer{ai: super::ai(clapmatches)})
+        }
+    });
+    {
+        let _tui = skui::STuiGuard::init_ui();
+        run_simple_game_loop(
+            &aplayer,
+            &mut session,
+            /*fn_gameresult*/|_gameresult| {},
+        );
+    }
+    let mut vecpairepin_money = session.balances().to_vec();
+    vecpairepin_money.sort_unstable_by_key(|&(_epi, n_money)| n_money);
     println!("Results:");
-    for attable in aattable.iter() {
-        println!("{} {}", attable.player.name(), attable.n_money);
+    for (epi, n_money) in vecpairepin_money {
+        println!("{} {}", aplayer[epi].name(), n_money);
+    }
+    println!("Stock: {}", session.n_stock());
+    for transfer in session.settlement() {
+        println!("{} pays {} to {}", aplayer[transfer.participant_from].name(), transfer.n_money, aplayer[transfer.participant_to].name());
     }
-    println!("Stock: {}", n_stock);
     Ok(())
 }
 
@@ -39,6 +66,8 @@ pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
 fn test_game_loop() {
     use rand::prelude::IteratorRandom;
     use crate::ai;
+    use crate::game::TGamePhase;
+    use crate::rules::ruleset::VStockOrT;
     use crate::player::{
         *,
         playercomputer::*,
@@ -85,6 +114,10 @@ fn test_game_loop() {
                 r#"[ramsch]
                 price=50
                 durchmarsch = "all""#,
+                r#"[ramsch]
+                price=20
+                jungfrau = "each"
+                schieben = true"#,
                 r"[stock]",
                 r"[stock]
                 price=30",
@@ -101,8 +134,14 @@ fn test_game_loop() {
                 r"[stoss]
                 max=3
                 ",
+                r#"[stoss]
+                deadline="own-first-card"
+                "#,
             ]
         )
+            .filter(|&(_n_base_price, _n_solo_price, _n_lauf_min, _str_allowed_games, str_no_active_game, str_extras)| {
+                !str_no_active_game.contains("schieben") || str_extras.contains("[stoss]") // schieben requires stoss
+            })
             .map(|(n_base_price, n_solo_price, n_lauf_min, str_allowed_games, str_no_active_game, str_extras)| {
                 let str_ruleset = format!(
                     "base-price={}
@@ -119,17 +158,72 @@ fn test_game_loop() {
             .choose_multiple(&mut rng, 2)
     {
         run_simple_game_loop(
-            EPlayerIndex::map_from_fn(|epi| -> Box<dyn TPlayer> {
+            &EPlayerIndex::map_from_fn(|epi| -> Box<dyn TPlayer> {
                 Box::new(SPlayerComputer{ai: {
                     if epi<EPlayerIndex::EPI2 {
                         ai::SAi::new_cheating(/*n_rank_rules_samples*/1, /*n_suggest_card_branches*/2)
-                    } else {
+                    } else if epi==EPlayerIndex::EPI2 {
                         ai::SAi::new_simulating(/*n_rank_rules_samples*/1, /*n_suggest_card_branches*/1, /*n_suggest_card_samples*/1)
+                    } else {
+                        ai::SAi::new_ismcts(/*n_rank_rules_samples*/10, /*n_suggest_card_iterations*/100)
                     }
                 }})
             }),
-            /*n_games*/4,
-            ruleset,
+            &mut SSession::new(
+                ruleset.clone(),
+                /*dealsource*/crate::game::deal::VDealSource::new_random(ruleset.ekurzlang),
+                VSessionLength::Games(4),
+                /*mapepiparticipant*/EPlayerIndex::map_from_fn(|epi| epi),
+                /*opath_record*/None,
+            ),
+            /*fn_gameresult*/|gameresult| {
+                match unwrap!(gameresult.gamerecord.to_game()) {
+                    VStockOrT::Stock(()) => assert!(matches!(gameresult.stockorgame, VStockOrT::Stock(()))),
+                    VStockOrT::OrT(game) => assert_eq!(unwrap!(game.finish()).gamerecord, gameresult.gamerecord),
+                }
+            },
+        );
+    }
+}
+
+#[test]
+fn test_game_loop_dreier() {
+    use crate::ai;
+    use crate::game::TGamePhase;
+    use crate::rules::ruleset::VStockOrT;
+    use crate::player::playercomputer::*;
+    for str_no_active_game in ["[ramsch]\nprice=20", "[stock]", ""] {
+        let ruleset = unwrap!(crate::rules::ruleset::SRuleSet::from_string(&format!(
+            "base-price=10\nsolo-price=50\nlauf-min=2\nplayers=3\n[solo]\n[wenz]\n[bettel]\n[stoss]\n{}",
+            str_no_active_game,
+        )));
+        assert_eq!(ruleset.ekurzlang, EKurzLang::Dreier);
+        let mut session = SSession::new(
+            ruleset.clone(),
+            /*dealsource*/crate::game::deal::VDealSource::new_random(ruleset.ekurzlang),
+            VSessionLength::Games(3),
+            /*mapepiparticipant*/EPlayerIndex::map_from_fn(|epi| epi),
+            /*opath_record*/None,
+        );
+        run_simple_game_loop(
+            &EPlayerIndex::map_from_fn(|epi| -> Box<dyn TPlayer> {
+                Box::new(SPlayerComputer{ai: if EPlayerIndex::EPI0==epi {
+                    ai::SAi::new_cheating(/*n_rank_rules_samples*/1, /*n_suggest_card_branches*/1)
+                } else {
+                    ai::SAi::new_ismcts(/*n_rank_rules_samples*/10, /*n_suggest_card_iterations*/100)
+                }})
+            }),
+            &mut session,
+            /*fn_gameresult*/|gameresult| {
+                assert_eq!(gameresult.an_payout[EPlayerIndex::EPI3], 0);
+                match unwrap!(gameresult.gamerecord.to_game()) {
+                    VStockOrT::Stock(()) => assert!(matches!(gameresult.stockorgame, VStockOrT::Stock(()))),
+                    VStockOrT::OrT(game) => assert_eq!(unwrap!(game.finish()).gamerecord, gameresult.gamerecord),
+                }
+            },
         );
+        // one game per dealer, the empty seat does not rotate
+        assert_eq!(session.seating(), &EPlayerIndex::map_from_fn(|epi| epi));
+        assert!(session.balances().iter().all(|(epi, _n_money)| *epi!=EPlayerIndex::EPI3));
     }
 }
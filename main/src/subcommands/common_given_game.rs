@@ -28,6 +28,7 @@ pub fn subcommand_given_game(str_subcommand: &'static str, str_about: &'static s
         .arg(clap::Arg::with_name("simulate_hands").long("simulate-hands").takes_value(true))
         .arg(clap::Arg::with_name("verbose").long("verbose").short("v"))
         .arg(clap::Arg::with_name("constrain_hands").long("constrain-hands").takes_value(true))
+        .arg(super::clap_arg_seed())
 }
 
 pub trait TWithCommonArgs {
@@ -46,6 +47,7 @@ pub fn with_common_args(
     clapmatches: &clap::ArgMatches,
     withcommanargs: impl TWithCommonArgs
 ) -> Result<(), Error> {
+    super::seed_rng(clapmatches)?;
     let b_verbose = clapmatches.is_present("verbose");
     let hand_fixed = super::str_to_hand(&unwrap!(clapmatches.value_of("hand")))?;
     let veccard_as_played = &cardvector::parse_cards::<Vec<_>>(
@@ -99,20 +101,20 @@ pub fn with_common_args(
     cartesian_match!(forward,
         match ((oiteratehands, eremainingcards)) {
             (Some(All), _)|(None, _1|_2|_3|_4) => (
-                all_possible_hands(&stichseq, hand_fixed.clone(), epi_fixed, rules)
+                all_possible_hands(&stichseq, hand_fixed.clone(), epi_fixed, /*ohand_revealed*/None, rules)
                     .filter(|ahand| oconstraint.as_ref().map_or(true, |relation|
                         relation.eval(ahand, rules)
                     ))
             ),
             (Some(Sample(n_samples)), _) => (
-                forever_rand_hands(&stichseq, hand_fixed.clone(), epi_fixed, rules)
+                forever_rand_hands(&stichseq, hand_fixed.clone(), epi_fixed, /*ohand_revealed*/None, rules)
                     .filter(|ahand| oconstraint.as_ref().map_or(true, |relation|
                         relation.eval(ahand, rules)
                     ))
                     .take(n_samples)
             ),
             (None, _5|_6|_7|_8) => (
-                forever_rand_hands(&stichseq, hand_fixed.clone(), epi_fixed, rules)
+                forever_rand_hands(&stichseq, hand_fixed.clone(), epi_fixed, /*ohand_revealed*/None, rules)
                     .filter(|ahand| oconstraint.as_ref().map_or(true, |relation|
                         relation.eval(ahand, rules)
                     ))
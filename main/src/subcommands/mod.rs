@@ -9,6 +9,7 @@ mod common_given_game;
 
 use crate::util::*;
 use crate::rules::ruleset::SRuleSet;
+use crate::game::deal::VDealSource;
 use crate::ai::SAi;
 use crate::primitives::hand::SHand;
 
@@ -18,12 +19,67 @@ fn clap_arg(str_long: &'static str, str_default: &'static str) -> clap::Arg<'sta
         .default_value(str_default)
 }
 
+fn clap_arg_seed() -> clap::Arg<'static, 'static> {
+    clap::Arg::with_name("seed")
+        .long("seed")
+        .takes_value(true)
+        .help("Seed for random numbers (AI decisions are only reproducible with RAYON_NUM_THREADS=1)")
+}
+
+pub fn seed_rng(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
+    if let Some(str_seed) = clapmatches.value_of("seed") {
+        rng::set_seed(str_seed.parse()?);
+    }
+    Ok(())
+}
+
+fn clap_arg_deals() -> clap::Arg<'static, 'static> {
+    clap::Arg::with_name("deals")
+        .long("deals")
+        .takes_value(true)
+        .help("Deal the cards from this file (one deal per line, cards of all players starting with the first one) instead of randomly")
+}
+
+pub fn get_dealsource(clapmatches: &clap::ArgMatches, ruleset: &SRuleSet) -> Result<VDealSource, Error> {
+    match clapmatches.value_of("deals") {
+        Some(str_path_deals) => VDealSource::from_file(std::path::Path::new(str_path_deals), ruleset.ekurzlang),
+        None => Ok(VDealSource::new_random(ruleset.ekurzlang)),
+    }
+}
+
 pub fn get_ruleset(clapmatches: &clap::ArgMatches) -> Result<SRuleSet, Error> {
     SRuleSet::from_file(std::path::Path::new(unwrap!(clapmatches.value_of("ruleset"))))
 }
 
+fn clap_arg_time_budget() -> clap::Arg<'static, 'static> {
+    clap::Arg::with_name("time_budget")
+        .long("time-budget")
+        .takes_value(true)
+        .help("Milliseconds the AI may spend to suggest a card (instead of a fixed search effort)")
+}
+
+fn clap_arg_cache_size() -> clap::Arg<'static, 'static> {
+    clap::Arg::with_name("cache_size")
+        .long("cache-size")
+        .takes_value(true)
+        .help("Memory (in MiB) to cache explored snapshots, 0 to disable")
+}
+
+pub fn n_bytes_snapshot_cache(clapmatches: &clap::ArgMatches) -> Result<usize, Error> {
+    Ok(match clapmatches.value_of("cache_size") {
+        None => crate::ai::N_BYTES_SNAPSHOT_CACHE_DEFAULT,
+        Some(str_cache_size) => str_cache_size.parse::<usize>()? * 1024 * 1024,
+    })
+}
+
+fn clap_arg_no_equivalent_cards() -> clap::Arg<'static, 'static> {
+    clap::Arg::with_name("no_equivalent_cards")
+        .long("no-equivalent-cards")
+        .help("Explore all allowed cards instead of only representatives of cards that win the same stichs")
+}
+
 pub fn ai(subcommand_matches: &clap::ArgMatches) -> SAi {
-    match unwrap!(subcommand_matches.value_of("ai")) {
+    let ai = match unwrap!(subcommand_matches.value_of("ai")) {
         "cheating" => SAi::new_cheating(/*n_rank_rules_samples*/50, /*n_suggest_card_branches*/2),
         "simulating" => 
             SAi::new_simulating(
@@ -31,10 +87,27 @@ pub fn ai(subcommand_matches: &clap::ArgMatches) -> SAi {
                 /*n_suggest_card_branches*/2,
                 /*n_suggest_card_samples*/10,
             ),
+        "ismcts" => SAi::new_ismcts(/*n_rank_rules_samples*/1000, /*n_suggest_card_iterations*/10000),
         _ => {
             println!("Warning: AI not recognized. Defaulting to 'cheating'");
             SAi::new_cheating(/*n_rank_rules_samples*/50, /*n_suggest_card_branches*/2)
         }
+    };
+    let ai = ai.with_equivalent_cards(!subcommand_matches.is_present("no_equivalent_cards"));
+    let ai = match n_bytes_snapshot_cache(subcommand_matches) {
+        Ok(n_bytes_snapshot_cache) => ai.with_snapshot_cache_size(n_bytes_snapshot_cache),
+        Err(_) => {
+            println!("Warning: Cache size not recognized. Using default.");
+            ai
+        },
+    };
+    match subcommand_matches.value_of("time_budget").map(str::parse::<u64>) {
+        None => ai,
+        Some(Ok(n_millis)) => ai.with_time_budget(std::time::Duration::from_millis(n_millis)),
+        Some(Err(_)) => {
+            println!("Warning: Time budget not recognized. Ignoring it.");
+            ai
+        },
     }
 }
 
@@ -10,9 +10,11 @@ pub fn subcommand(str_subcommand: &str) -> clap::App {
         .arg(clap_arg("ai", "cheating"))
         .arg(clap_arg("hand", ""))
         .arg(clap_arg("position", "0"))
+        .arg(super::clap_arg_seed())
 }
 
 pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
+    super::seed_rng(clapmatches)?;
     let ruleset = super::get_ruleset(clapmatches)?;
     let hand = super::str_to_hand(&clapmatches.value_of("hand").ok_or_else(||format_err!("No hand given as parameter."))?)?;
     let hand = Some(hand).filter(|hand| hand.cards().len()==ruleset.ekurzlang.cards_per_player()).ok_or_else(||format_err!("Could not convert hand to a full hand of cards"))?;
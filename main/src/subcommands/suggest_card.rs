@@ -11,6 +11,9 @@ pub fn subcommand(str_subcommand: &'static str) -> clap::App {
         .arg(clap::Arg::with_name("repeat_hands").long("repeat-hands").takes_value(true))
         .arg(clap::Arg::with_name("branching").long("branching").takes_value(true))
         .arg(clap::Arg::with_name("prune").long("prune").takes_value(true))
+        .arg(super::clap_arg_no_equivalent_cards())
+        .arg(super::clap_arg_time_budget())
+        .arg(super::clap_arg_cache_size())
 }
 
 pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
@@ -28,11 +31,22 @@ pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
             b_verbose: bool,
         ) -> Result<(), Error> {
             let clapmatches = self.clapmatches;
+            let oinstant_deadline = if_then_some!(let Some(str_time_budget) = clapmatches.value_of("time_budget"),
+                std::time::Instant::now() + std::time::Duration::from_millis(str_time_budget.parse()?)
+            );
             let epi_fixed = determinebestcard.epi_fixed;
             let determinebestcardresult = { // we are interested in payout => single-card-optimization useless
                 macro_rules! forward{(($func_filter_allowed_cards: expr), ($foreachsnapshot: ident),) => {{ // TODORUST generic closures
                     let n_repeat_hand = clapmatches.value_of("repeat_hands").unwrap_or("1").parse()?;
-                    determine_best_card(
+                    let n_bytes_snapshot_cache = super::n_bytes_snapshot_cache(clapmatches)?;
+                    let osnapshotcache = if_then_some!(0<n_bytes_snapshot_cache, SSnapshotCache::new(n_bytes_snapshot_cache));
+                    let nodecounter = SNodeCounter::new($foreachsnapshot::new(
+                        rules,
+                        epi_fixed,
+                        /*tpln_stoss_doubling*/(0, 0), // TODO? make customizable
+                        /*n_stock*/0, // TODO? make customizable
+                    ));
+                    let determinebestcardresult = determine_best_card(
                         &determinebestcard,
                         itahand
                             .inspect(|ahand| {
@@ -47,14 +61,18 @@ pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
                                 )
                             }),
                         $func_filter_allowed_cards,
-                        &$foreachsnapshot::new(
-                            rules,
-                            epi_fixed,
-                            /*tpln_stoss_doubling*/(0, 0), // TODO? make customizable
-                            /*n_stock*/0, // TODO? make customizable
-                        ),
+                        &nodecounter,
+                        osnapshotcache.as_ref(),
                         /*opath_out_dir*/None, // TODO? make customizable
-                    )
+                        oinstant_deadline,
+                    );
+                    if b_verbose {
+                        println!("Explored snapshots: {}", nodecounter.count_nodes());
+                        if let Some(snapshotcache) = osnapshotcache {
+                            println!("Cached snapshots: {}", snapshotcache.count_entries());
+                        }
+                    }
+                    determinebestcardresult
                 }}}
                 use super::common_given_game::ERemainingCards::*;
                 cartesian_match!(
@@ -75,7 +93,7 @@ pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
                         }),
                         eremainingcards
                     )) {
-                        (Some(None), _)|(None,_1|_2|_3|_4) => (&|_,_| (/*no filtering*/)),
+                        (Some(None), _)|(None,_1|_2|_3|_4) => (&equivalent_cards_filter(rules, /*b_equivalent_cards*/!clapmatches.is_present("no_equivalent_cards"))),
                         (Some(Some((n_lo, n_hi))), _) => (&branching_factor(move |_stichseq| {
                             let n_lo = n_lo.max(1);
                             (n_lo, (n_hi.max(n_lo+1)))
@@ -2,12 +2,13 @@
 
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{Arc, Mutex},
 };
 use crate::util::*;
 use crate::game::*;
 use crate::rules::*;
-use crate::rules::ruleset::{SRuleGroup, SRuleSet, VStockOrT, allowed_rules};
+use crate::rules::ruleset::{SRuleGroup, VStockOrT, allowed_rules};
 
 use futures::prelude::*;
 use futures::{
@@ -29,6 +30,11 @@ use itertools::Itertools;
 pub fn subcommand(str_subcommand: &str) -> clap::App {
     clap::SubCommand::with_name(str_subcommand)
         .arg(super::clap_arg("ruleset", "rulesets/default.toml"))
+        .arg(super::clap_arg_seed())
+        .arg(clap::Arg::with_name("record").long("record").takes_value(true).help("Append finished games to this file"))
+        .arg(super::clap_arg_deals())
+        .arg(clap::Arg::with_name("allow_undo").long("allow-undo").help("Allow players to take back cards"))
+        .arg(clap::Arg::with_name("numgames").long("numgames").takes_value(true).help("Stop after this many games (default: unlimited)"))
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -55,12 +61,11 @@ type VGamePhaseActivePlayerInfo<'a> = VGamePhaseGeneric<
     (&'a SGameResult, <SGameResult as TGamePhase>::ActivePlayerInfo),
 >;
 type SActivelyPlayableRulesIdentifier = String;
-fn find_rules_by_id(slcrulegroup: &[SRuleGroup], hand: SFullHand, orulesid: &Option<SActivelyPlayableRulesIdentifier>) -> Result<Option<Box<dyn TActivelyPlayableRules>>, ()> {
+fn find_rules_by_id<'rules>(slcrulegroup: &'rules [SRuleGroup], hand: SFullHand, orulesid: &Option<SActivelyPlayableRulesIdentifier>) -> Result<Option<&'rules dyn TActivelyPlayableRules>, ()> {
     allowed_rules(slcrulegroup, hand)
         .find(|orules|
             &orules.map(<dyn TActivelyPlayableRules>::to_string)==orulesid
         )
-        .map(|orules| orules.map(TActivelyPlayableRulesBoxClone::box_clone)) // TODO box_clone needed?
         .ok_or(())
 }
 
@@ -81,14 +86,25 @@ fn rules_to_gamephaseaction<'retval, 'rules : 'retval, 'hand : 'retval>(slcruleg
 #[derive(Debug, Serialize, Deserialize, Clone)]
 enum VGameAction {
     Stoss,
+    AnnounceSchneiderSchwarz(ESchneiderSchwarz),
+    ExchangeCard(SCard),
     Zugeben(SCard),
+    Undo, // takes back cards up to and including the most recent card of the player
+}
+
+// players may only undo on their turn or take back the card they just played, so they cannot revoke others' moves at will
+fn undo_allowed(game: &SGame, epi: EPlayerIndex) -> bool {
+    game.which_player_can_do_something().is_some_and(|(epi_current, _vecepi_stoss)| epi_current==epi)
+        || game.stichseq.visible_stichs().iter().rev()
+            .find_map(|stich| stich.iter().last())
+            .is_some_and(|(epi_card, _card)| epi_card==epi)
 }
 type VGamePhaseAction = VGamePhaseGeneric<
     /*DealCards announce_doubling*/ /*b_doubling*/bool,
     /*GamePreparations announce_game*/Option<SActivelyPlayableRulesIdentifier>,
     /*DetermineRules*/Option<SActivelyPlayableRulesIdentifier>,
     /*Game*/VGameAction,
-    /*GameResult*/(),
+    /*GameResult*/ /*b_finish_round*/bool,
 >;
 #[derive(Serialize, Deserialize)]
 enum VPlayerCmd {
@@ -123,8 +139,8 @@ struct STimeoutCmd {
 struct SPeer {
     sockaddr: SocketAddr,
     txmsg: UnboundedSender<Message>,
-    n_money: isize,
     str_name: String,
+    ostr_notice: Option<String>, // shown once along with the next message
 }
 
 #[derive(Debug, Default)]
@@ -142,20 +158,49 @@ struct SPlayers {
 struct STable{
     players: SPlayers,
     ogamephase: Option<VGamePhase>,
-    n_stock: isize, // TODO would that be better within VGamePhase?
-    ruleset: SRuleSet,
+    session: session::SSession<Option<String>>, // participants are identified by name; seats are only dealt once all are taken
+    b_allow_undo: bool,
 }
 
 impl STable {
-    fn new(ruleset: SRuleSet) -> Self {
+    fn new(session: session::SSession<Option<String>>, b_allow_undo: bool) -> Self {
         Self {
             players: SPlayers::default(),
             ogamephase: None,
-            n_stock: 0,
-            ruleset,
+            session,
+            b_allow_undo,
         }
     }
 
+    fn deal_cards(&mut self) -> Option<VGamePhase> {
+        if_then_some!(
+            !self.session.is_finished()
+                && self.players.mapepiopeer
+                    .iter()
+                    .all(|activepeer| activepeer.opeer.is_some()),
+            {
+                let mapepiostr_name = self.players.mapepiopeer.map(|activepeer|
+                    activepeer.opeer.as_ref().map(|peer| peer.str_name.clone())
+                );
+                if &mapepiostr_name!=self.session.seating() { // participants joined or left the table
+                    self.session.seat(mapepiostr_name);
+                }
+                VGamePhase::DealCards(self.session.deal_cards())
+            }
+        )
+    }
+
+    fn str_participant(ostr_name: &Option<String>) -> String {
+        ostr_name.clone().unwrap_or_else(|| "<BOT>".to_string())
+    }
+
+    fn name_in_use(&self, str_name: &str) -> bool {
+        self.players.mapepiopeer.iter()
+            .filter_map(|activepeer| activepeer.opeer.as_ref())
+            .chain(self.players.vecpeer.iter())
+            .any(|peer| peer.str_name==str_name)
+    }
+
     fn insert(&mut self, self_mutex: Arc<Mutex<Self>>, peer: SPeer) {
         match self.players.mapepiopeer
             .iter_mut()
@@ -172,15 +217,10 @@ impl STable {
                 self.players.vecpeer.push(peer);
             }
         }
-        if self.ogamephase.is_none()
-            && self.players.mapepiopeer
-                .iter()
-                .all(|opeer| opeer.opeer.is_some())
-        {
-            self.ogamephase = Some(VGamePhase::DealCards(SDealCards::new(
-                self.ruleset.clone(),
-                self.n_stock,
-            )));
+        if self.ogamephase.is_none() {
+            self.ogamephase = self.deal_cards();
+        }
+        if self.ogamephase.is_some() {
             self.on_incoming_message(
                 self_mutex,
                 /*oepi*/None,
@@ -205,6 +245,7 @@ impl SPlayers {
         oslcstich: Option<&[SStich]>,
         orules: Option<&dyn TRules>,
         f_cards: impl Fn(EPlayerIndex) -> Vec<SCard>,
+        ohand_revealed: Option<(EPlayerIndex, SHand)>,
         mut f_active: impl FnMut(EPlayerIndex, &mut Option<STimeoutCmd>)->VMessage,
         mut f_inactive: impl FnMut(&mut SPeer)->VMessage,
         oepi_timeout: Option<EPlayerIndex>,
@@ -246,7 +287,16 @@ impl SPlayers {
                 mapepistr: [String; EPlayerIndex::SIZE],
                 otplepistr_rules: Option<(EPlayerIndex, String)>,
                 oepi_timeout: Option<EPlayerIndex>,
+                otplepivecstr_revealed: Option<(EPlayerIndex, Vec<String>)>, // e.g. hand of Bettel Brett player
             }
+            let msg = match (peer.ostr_notice.take(), msg) {
+                (None, msg) => msg,
+                (Some(str_notice), VMessage::Info(str_info)) => VMessage::Info(format!("{} {}", str_notice, str_info)),
+                (Some(str_notice), VMessage::Ask{str_question, vecstrgamephaseaction}) => VMessage::Ask{
+                    str_question: format!("{} {}", str_notice, str_question),
+                    vecstrgamephaseaction,
+                },
+            };
             let card_in_stich = |stich: &SStich, epi| {
                 stich.get(playerindex_client_to_server(epi)).map(SCard::to_string)
             };
@@ -289,6 +339,16 @@ impl SPlayers {
                         format!("{}", rules),
                     )),
                     oepi_timeout.map(playerindex_server_to_client),
+                    ohand_revealed.as_ref().map(|(epi_revealed, hand_revealed)| {
+                        let mut veccard_revealed = hand_revealed.cards().to_vec();
+                        if let Some(rules) = orules {
+                            rules.sort_cards_first_trumpf_then_farbe(&mut veccard_revealed);
+                        }
+                        (
+                            playerindex_server_to_client(*epi_revealed),
+                            veccard_revealed.iter().map(SCard::to_string).collect(),
+                        )
+                    }),
                 ))).into()
             ));
         };
@@ -303,6 +363,9 @@ impl SPlayers {
                     rulesramsch::SRulesRamsch::new( // TODO rules dummy is ugly
                         /*n_price*/0, // irrelevant
                         rulesramsch::VDurchmarsch::None, // irrelevant
+                        rulesramsch::EJungfrau::Ignore, // irrelevant
+                        /*b_schieben*/false, // irrelevant
+                        /*b_loser_pays_into_stock*/false, // irrelevant
                     ).sort_cards_first_trumpf_then_farbe(&mut veccard);
                 }
                 communicate(Some(epi), veccard, msg, peer);
@@ -347,7 +410,7 @@ impl STable {
                                     &gamepreparations.ruleset.avecrulegroup[epi],
                                     gamepreparations.fullhand(epi),
                                     orulesid
-                                ) {
+                                ).map(|orules| orules.map(TActivelyPlayableRulesBoxClone::box_clone)) {
                                     handle_err(gamepreparations.announce_game(epi, orules));
                                 }
                             },
@@ -359,7 +422,12 @@ impl STable {
                                         orulesid
                                     ) {
                                         handle_err(if let Some(rules) = orules {
-                                            determinerules.announce_game(epi, rules)
+                                            if determinerules.orulegroup_der_alte_muss.is_some() {
+                                                let i_rules = unwrap!(vecrulegroup.iter().find_map(|rulegroup| rulegroup.position(rules)));
+                                                determinerules.announce_game_der_alte(epi, i_rules)
+                                            } else {
+                                                determinerules.announce_game(epi, TActivelyPlayableRulesBoxClone::box_clone(rules))
+                                            }
                                         } else {
                                             determinerules.resign(epi)
                                         });
@@ -369,10 +437,25 @@ impl STable {
                             (VGamePhase::Game(ref mut game), VGamePhaseAction::Game(ref gameaction)) => {
                                 handle_err(match gameaction {
                                     VGameAction::Stoss => game.stoss(epi),
+                                    VGameAction::AnnounceSchneiderSchwarz(eschneiderschwarz) => game.announce_schneiderschwarz(epi, *eschneiderschwarz),
+                                    VGameAction::ExchangeCard(card) => game.exchange_card(epi, *card),
                                     VGameAction::Zugeben(card) => game.zugeben(*card, epi),
+                                    VGameAction::Undo => if self.b_allow_undo && undo_allowed(game, epi) {
+                                        for activepeer in self.players.mapepiopeer.iter_mut() {
+                                            if let Some(timeoutcmd) = activepeer.otimeoutcmd.take() {
+                                                timeoutcmd.aborthandle.abort();
+                                            }
+                                        }
+                                        game.undo_until(epi)
+                                    } else {
+                                        Err(format_err!("Undo not allowed."))
+                                    },
                                 });
                             },
-                            (VGamePhase::GameResult(gameresult), VGamePhaseAction::GameResult(())) => {
+                            (VGamePhase::GameResult(gameresult), VGamePhaseAction::GameResult(b_finish_round)) => {
+                                if b_finish_round {
+                                    self.session.finish_current_round();
+                                }
                                 gameresult.confirm(epi);
                             },
                             (_gamephase, _cmd) => {
@@ -393,7 +476,19 @@ impl STable {
                      * E2 E3 S0 S1 [S2 ... SN E0 E1]
                      */
                     // Players: E0 E1 E2 E3 [S0 S1 S2 ... SN] (S0 is longest waiting inactive player)
-                    table.players.mapepiopeer.as_raw_mut().rotate_left(1);
+                    // The session has passed on the deal, so its seating tells who sits where.
+                    let mut vecpeer_seated = table.players.mapepiopeer.iter_mut()
+                        .filter_map(|activepeer| activepeer.opeer.take())
+                        .collect::<Vec<_>>();
+                    for epi in EPlayerIndex::values() {
+                        table.players.mapepiopeer[epi].opeer = table.session.seating()[epi].as_ref().and_then(|str_name| {
+                            vecpeer_seated.iter()
+                                .position(|peer| &peer.str_name==str_name)
+                                .map(|i_peer| vecpeer_seated.remove(i_peer))
+                        });
+                    }
+                    // Peers that renamed themselves while seated are not found in the seating; they take the next free seats.
+                    table.players.vecpeer.splice(0..0, vecpeer_seated);
                     // Players: E1 E2 E3 E0 [S0 S1 S2 ... SN]
                     if let Some(peer_epi3) = table.players.mapepiopeer[EPlayerIndex::EPI3].opeer.take() {
                         table.players.vecpeer.push(peer_epi3);
@@ -407,9 +502,7 @@ impl STable {
                         }
                     }
                     // Players: E1 E2 E3 S0 [S1 S2 ... SN E0] (E1, E2, E3 may be None)
-                    if_then_some!(table.players.mapepiopeer.iter().all(|activepeer| activepeer.opeer.is_some()),
-                        VGamePhase::DealCards(SDealCards::new(table.ruleset.clone(), table.n_stock))
-                    )
+                    table.deal_cards()
                     // TODO should we clear timeouts?
                 }
                 fn simple_transition<R: From<VGamePhase>, GamePhase: TGamePhase>(
@@ -426,12 +519,7 @@ impl STable {
                             Ok(VGamePreparationsFinish::DetermineRules(determinerules)) => Some(DetermineRules(determinerules)),
                             Ok(VGamePreparationsFinish::DirectGame(game)) => Some(Game(game)),
                             Ok(VGamePreparationsFinish::Stock(gameresult)) => {
-                                let mapepiopeer = &mut self.players.mapepiopeer;
-                                gameresult.apply_payout(&mut self.n_stock, |epi, n_payout| {
-                                    if let Some(ref mut peer) = mapepiopeer[epi].opeer {
-                                        peer.n_money += n_payout;
-                                    }
-                                });
+                                self.session.register_gameresult(gameresult);
                                 next_game(self)
                             },
                             Err(gamepreparations) => Some(GamePreparations(gamepreparations)),
@@ -440,12 +528,7 @@ impl STable {
                         Game(game) => simple_transition(game, GameResult, Game),
                         GameResult(gameresult) => match gameresult.finish() {
                             Ok(gameresult) => {
-                                let mapepiopeer = &mut self.players.mapepiopeer;
-                                gameresult.apply_payout(&mut self.n_stock, |epi, n_payout| {
-                                    if let Some(ref mut peer) = mapepiopeer[epi].opeer {
-                                        peer.n_money += n_payout;
-                                    }
-                                });
+                                self.session.register_gameresult(gameresult);
                                 next_game(self)
                             },
                             Err(gameresult) => Some(GameResult(gameresult)),
@@ -491,6 +574,7 @@ impl STable {
                                 /*oslcstich*/None,
                                 None,
                                 |epi| dealcards.first_hand_for(epi).into(),
+                                /*ohand_revealed*/None,
                                 |epi, otimeoutcmd| {
                                     if epi_doubling==epi {
                                         ask_with_timeout(
@@ -518,6 +602,7 @@ impl STable {
                                 /*oslcstich*/None,
                                 None,
                                 |epi| gamepreparations.fullhand(epi).get().cards().to_vec(),
+                                /*ohand_revealed*/None,
                                 |epi, otimeoutcmd| {
                                     if epi_announce_game==epi {
                                         let itgamephaseaction_rules = rules_to_gamephaseaction(
@@ -585,6 +670,7 @@ impl STable {
                                 /*oslcstich*/None,
                                 None,
                                 |epi| determinerules.fullhand(epi).get().cards().to_vec(),
+                                /*ohand_revealed*/None,
                                 |epi, otimeoutcmd| {
                                     if epi_determine==epi {
                                         let itgamephaseaction_rules = rules_to_gamephaseaction(
@@ -596,12 +682,16 @@ impl STable {
                                         ask_with_timeout(
                                             otimeoutcmd,
                                             epi_determine,
-                                            format!(
-                                                "Du bist an {}. Stelle. Von {}. Stelle wird {} geboten. Spielst Du etwas staerkeres?", // TODO umlaut-tactics?
-                                                epi.to_usize() + 1, // EPlayerIndex is 0-based
-                                                determinerules.pairepirules_current_bid.0.to_usize() + 1, // EPlayerIndex is 0-based
-                                                determinerules.pairepirules_current_bid.1.to_string(),
-                                            ),
+                                            if determinerules.orulegroup_der_alte_muss.is_some() {
+                                                "Du hast den Alten und musst spielen. Was spielst Du?".to_string()
+                                            } else {
+                                                format!(
+                                                    "Du bist an {}. Stelle. Von {}. Stelle wird {} geboten. Spielst Du etwas staerkeres?", // TODO umlaut-tactics?
+                                                    epi.to_usize() + 1, // EPlayerIndex is 0-based
+                                                    determinerules.pairepirules_current_bid.0.to_usize() + 1, // EPlayerIndex is 0-based
+                                                    determinerules.pairepirules_current_bid.1.to_string(),
+                                                )
+                                            },
                                             itgamephaseaction_rules,
                                             self_mutex.clone(),
                                             gamephaseaction_rules_default,
@@ -615,32 +705,66 @@ impl STable {
                             );
                         },
                         Game((game, (epi_card, vecepi_stoss))) => {
+                            let b_allow_undo = self.b_allow_undo;
                             self.players.for_each(
                                 Some(game.stichseq.visible_stichs()),
                                 Some(game.rules.as_ref()),
                                 |epi| game.ahand[epi].cards().to_vec(),
+                                game.revealed_hand(),
                                 |epi, otimeoutcmd| {
-                                    let ostrgamephaseaction = if_then_some!(vecepi_stoss.contains(&epi),
+                                    let vecstrgamephaseaction = if_then_some!(vecepi_stoss.contains(&epi),
                                         ("Stoss".into(), VGamePhaseAction::Game(VGameAction::Stoss))
-                                    );
-                                    if epi_card==epi {
+                                    ).into_iter()
+                                        .chain(
+                                            game.schneiderschwarz_announceable()
+                                                .filter(|(epi_announce, _veceschneiderschwarz)| epi==*epi_announce)
+                                                .into_iter()
+                                                .flat_map(|(_epi_announce, veceschneiderschwarz)| veceschneiderschwarz)
+                                                .map(|eschneiderschwarz| (
+                                                    format!("{} ansagen", eschneiderschwarz),
+                                                    VGamePhaseAction::Game(VGameAction::AnnounceSchneiderSchwarz(eschneiderschwarz)),
+                                                ))
+                                        )
+                                        .chain(if_then_some!(
+                                            b_allow_undo
+                                                && game.stichseq.visible_stichs().iter().any(|stich| stich.get(epi).is_some())
+                                                && undo_allowed(game, epi),
+                                            ("Zuruecknehmen".into(), VGamePhaseAction::Game(VGameAction::Undo))
+                                        ))
+                                        .collect::<Vec<_>>();
+                                    if game.card_exchange_pending()==Some(epi) {
+                                        ask_with_timeout(
+                                            otimeoutcmd,
+                                            epi,
+                                            "Welche Karte gibst Du fuer den Trumpf ab?".into(),
+                                            vecstrgamephaseaction.into_iter()
+                                                .chain(game.ahand[epi].cards().iter().map(|card| (
+                                                    card.to_string(),
+                                                    VGamePhaseAction::Game(VGameAction::ExchangeCard(*card)),
+                                                ))),
+                                            self_mutex.clone(),
+                                            VGamePhaseAction::Game(VGameAction::ExchangeCard(
+                                                *unwrap!(game.ahand[epi].cards().choose(&mut rng::thread_rng()))
+                                            )),
+                                        )
+                                    } else if epi_card==epi {
                                         ask_with_timeout(
                                             otimeoutcmd,
                                             epi_card,
                                             "".into(),
-                                            ostrgamephaseaction.into_iter(),
+                                            vecstrgamephaseaction.into_iter(),
                                             self_mutex.clone(),
                                             VGamePhaseAction::Game(VGameAction::Zugeben(
                                                 *unwrap!(game.rules.all_allowed_cards(
                                                     &game.stichseq,
                                                     &game.ahand[epi_card],
-                                                ).choose(&mut rand::thread_rng()))
+                                                ).choose(&mut rng::thread_rng()))
                                             )),
                                         )
-                                    } else if let Some(strgamephaseaction) = ostrgamephaseaction {
+                                    } else if !vecstrgamephaseaction.is_empty() {
                                         VMessage::Ask{
                                             str_question: "".into(),
-                                            vecstrgamephaseaction: vec![strgamephaseaction],
+                                            vecstrgamephaseaction,
                                         }
                                     } else {
                                         VMessage::Info(format!("Asking {:?} for card", epi_card))
@@ -659,6 +783,7 @@ impl STable {
                                     game.rules.as_ref()
                                 ),
                                 |_epi| Vec::new(),
+                                /*ohand_revealed*/None,
                                 |epi, otimeoutcmd| {
                                     if !mapepib_confirmed[epi] {
                                         ask_with_timeout(
@@ -669,9 +794,12 @@ impl STable {
                                             } else {
                                                 format!("Gewinn: {}", gameresult.an_payout[epi])
                                             }),
-                                            std::iter::once(("Ok".into(), VGamePhaseAction::GameResult(()))),
+                                            vec![
+                                                ("Ok".into(), VGamePhaseAction::GameResult(/*b_finish_round*/false)),
+                                                ("Runde fertig spielen".into(), VGamePhaseAction::GameResult(/*b_finish_round*/true)),
+                                            ].into_iter(),
                                             self_mutex.clone(),
-                                            VGamePhaseAction::GameResult(()),
+                                            VGamePhaseAction::GameResult(/*b_finish_round*/false),
                                         )
                                     } else {
                                         VMessage::Info("Game finished".into())
@@ -685,12 +813,27 @@ impl STable {
                 }
             }
         } else {
+            let str_info = if self.session.is_finished() {
+                format!("Session finished. Stock: {}. {}",
+                    self.session.n_stock(),
+                    self.session.settlement().iter()
+                        .map(|transfer| format!("{} pays {} to {}",
+                            Self::str_participant(&transfer.participant_from),
+                            transfer.n_money,
+                            Self::str_participant(&transfer.participant_to),
+                        ))
+                        .join(", "),
+                )
+            } else {
+                "Waiting for more players.".into()
+            };
             self.players.for_each(
                 /*oslcstich*/None,
                 None,
                 |_epi| Vec::new(),
-                |_oepi, _otimeoutcmd| VMessage::Info("Waiting for more players.".into()),
-                |_peer| VMessage::Info("Waiting for more players.".into()),
+                /*ohand_revealed*/None,
+                |_oepi, _otimeoutcmd| VMessage::Info(str_info.clone()),
+                |_peer| VMessage::Info(str_info.clone()),
                 None,
             );
         }
@@ -772,8 +915,8 @@ async fn handle_connection(table: Arc<Mutex<STable>>, tcpstream: TcpStream, sock
     unwrap!(table.lock()).insert(table_mutex.clone(), SPeer{
         sockaddr,
         txmsg,
-        n_money: 0,
-        str_name: "<Name>".into(), // TODO can we initialize it right away?
+        str_name: format!("<{}>", sockaddr), // unique until the player logs in
+        ostr_notice: None,
     });
     let (sink_ws_out, stream_ws_in) = wsstream.split();
     let broadcast_incoming = stream_ws_in
@@ -796,6 +939,18 @@ async fn handle_connection(table: Arc<Mutex<STable>>, tcpstream: TcpStream, sock
             use VPlayerCmd::*;
             match serde_json::from_str(str_msg) {
                 Ok(GamePhaseAction(gamephaseaction)) => table.on_incoming_message(table_mutex.clone(), oepi, Some(gamephaseaction)),
+                Ok(PlayerLogin{str_player_name}) if table.name_in_use(&str_player_name) => {
+                    println!("Name already in use: {}", str_player_name); // names identify participants
+                    let players = &mut table.players;
+                    if let Some(peer) = players.mapepiopeer.iter_mut()
+                        .filter_map(|activepeer| activepeer.opeer.as_mut())
+                        .chain(players.vecpeer.iter_mut())
+                        .find(|peer| peer.sockaddr==sockaddr)
+                    {
+                        peer.ostr_notice = Some(format!("Name {} already in use.", str_player_name));
+                    }
+                    table.on_incoming_message(table_mutex.clone(), oepi, /*ogamephaseaction*/None); // resend state including notice
+                },
                 Ok(PlayerLogin{str_player_name}) => {
                     if let Some(ref epi)=oepi {
                         if let Some(ref mut peer) = table.players.mapepiopeer[*epi].opeer {
@@ -815,9 +970,9 @@ async fn handle_connection(table: Arc<Mutex<STable>>, tcpstream: TcpStream, sock
     unwrap!(table.lock()).remove(&sockaddr);
 }
 
-async fn internal_run(ruleset: SRuleSet) -> Result<(), Error> {
+async fn internal_run(session: session::SSession<Option<String>>, b_allow_undo: bool) -> Result<(), Error> {
     let str_addr = "127.0.0.1:8080";
-    let table = Arc::new(Mutex::new(STable::new(ruleset)));
+    let table = Arc::new(Mutex::new(STable::new(session, b_allow_undo)));
     // Create the event loop and TCP listener we'll accept connections on.
     let listener = unwrap!(TcpListener::bind(&str_addr).await);
     println!("Listening on: {}", str_addr);
@@ -829,6 +984,25 @@ async fn internal_run(ruleset: SRuleSet) -> Result<(), Error> {
 }
 
 pub fn run(clapmatches: &clap::ArgMatches) -> Result<(), Error> {
-    task::block_on(internal_run(super::get_ruleset(clapmatches)?))
+    super::seed_rng(clapmatches)?;
+    let ruleset = super::get_ruleset(clapmatches)?;
+    if ruleset.ekurzlang.n_players()!=EPlayerIndex::SIZE {
+        bail!("websocket only supports rulesets for four players.");
+    }
+    let dealsource = super::get_dealsource(clapmatches, &ruleset)?;
+    let sessionlength = match clapmatches.value_of("numgames") {
+        None => session::VSessionLength::Unlimited,
+        Some(str_n_games) => session::VSessionLength::Games(str_n_games.parse::<usize>()?),
+    };
+    task::block_on(internal_run(
+        session::SSession::new(
+            ruleset,
+            dealsource,
+            sessionlength,
+            /*mapepiparticipant*/EPlayerIndex::map_from_fn(|_epi| None),
+            /*opath_record*/clapmatches.value_of("record").map(PathBuf::from),
+        ),
+        /*b_allow_undo*/clapmatches.is_present("allow_undo"),
+    ))
 }
 
@@ -6,6 +6,7 @@ pub mod box_clone;
 pub mod staticvalue;
 pub mod assign;
 pub mod parser;
+pub mod rng;
 pub use self::{assign::*, box_clone::*, staticvalue::*};
 pub use derive_new::new;
 pub use failure::{bail, format_err, Error};
@@ -0,0 +1,93 @@
+use crate::util::*;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::{cell::RefCell, sync::Mutex};
+
+// None means that random number generators are seeded from entropy.
+static ON_SEED: Mutex<Option<u64>> = Mutex::new(None);
+
+thread_local!(static ORNG: RefCell<Option<StdRng>> = const { RefCell::new(None) }); // created on first use
+
+fn set_seed_internal(on_seed: Option<u64>) {
+    *unwrap!(ON_SEED.lock()) = on_seed;
+    ORNG.with(|orng| *orng.borrow_mut() = None);
+}
+
+/// Makes random number generators reproducible.
+/// Affects the calling thread and all generators created afterwards, so it should be called before spawning threads.
+pub fn set_seed(n_seed: u64) {
+    set_seed_internal(Some(n_seed));
+}
+
+// the seed is global, so seeded sections must not overlap (e.g. in tests running in parallel)
+#[cfg(test)]
+static MUTEX_WITH_SEED: Mutex<()> = Mutex::new(());
+
+/// Runs `func` with random number generators seeded by `n_seed`, and restores the previous seed afterwards.
+/// Waits until no other thread runs `with_seed`.
+#[cfg(test)]
+pub fn with_seed<R>(n_seed: u64, func: impl FnOnce()->R) -> R {
+    let _mutexguard = MUTEX_WITH_SEED.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    let on_seed_before = *unwrap!(ON_SEED.lock());
+    set_seed(n_seed);
+    let r = func();
+    set_seed_internal(on_seed_before);
+    r
+}
+
+pub fn new_rng() -> StdRng {
+    new_rng_with_offset(0)
+}
+
+fn new_rng_with_offset(n_seed_offset: u64) -> StdRng {
+    unwrap!(ON_SEED.lock()).map_or_else(
+        StdRng::from_entropy,
+        |n_seed| StdRng::seed_from_u64(n_seed.wrapping_add(n_seed_offset)),
+    )
+}
+
+// rayon workers get their own seeds so that they do not all draw the same numbers
+fn new_thread_rng() -> StdRng {
+    new_rng_with_offset(rayon::current_thread_index().map_or(0, |i_thread| i_thread.as_num::<u64>() + 1))
+}
+
+/// Replacement for `rand::thread_rng` that respects `set_seed`.
+pub struct SThreadRng;
+
+pub fn thread_rng() -> SThreadRng {
+    SThreadRng
+}
+
+impl RngCore for SThreadRng {
+    fn next_u32(&mut self) -> u32 {
+        ORNG.with(|orng| orng.borrow_mut().get_or_insert_with(new_thread_rng).next_u32())
+    }
+    fn next_u64(&mut self) -> u64 {
+        ORNG.with(|orng| orng.borrow_mut().get_or_insert_with(new_thread_rng).next_u64())
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        ORNG.with(|orng| orng.borrow_mut().get_or_insert_with(new_thread_rng).fill_bytes(dest))
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        ORNG.with(|orng| orng.borrow_mut().get_or_insert_with(new_thread_rng).try_fill_bytes(dest))
+    }
+}
+
+#[test]
+fn test_seeded_rng() {
+    use rand::Rng;
+    let vecn_random = |rng: &mut dyn RngCore| (0..10).map(|_| rng.gen_range(0..1000)).collect::<Vec<usize>>();
+    let threadpool = unwrap!(rayon::ThreadPoolBuilder::new().num_threads(2).build());
+    let (vecn_thread, vecn_new, vecvecn_worker) = with_seed(42, || (
+        vecn_random(&mut thread_rng()),
+        vecn_random(&mut new_rng()),
+        threadpool.broadcast(|_broadcastcontext| vecn_random(&mut thread_rng())),
+    ));
+    assert_ne!(vecvecn_worker[0], vecvecn_worker[1]); // each worker draws its own numbers
+    assert!(!vecvecn_worker.contains(&vecn_thread));
+    with_seed(42, || {
+        assert_eq!(vecn_random(&mut thread_rng()), vecn_thread);
+        assert_eq!(vecn_random(&mut new_rng()), vecn_new);
+    });
+    let _mutexguard = MUTEX_WITH_SEED.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    assert!(unwrap!(ON_SEED.lock()).is_none());
+}
@@ -49,6 +49,7 @@ class SSiteState {
     readonly mapepistr: Array<string>;
     readonly otplepistr_rules: null | [EPlayerIndex, string]
     readonly oepi_timeout: null | EPlayerIndex;
+    readonly otplepivecstr_revealed: null | [EPlayerIndex, Array<string>];
 }
 
 function assert(b: any) {
@@ -183,6 +184,9 @@ ws.onmessage = function(msg) {
             if (dbg(sitestate.otplepistr_rules) && i_epi==sitestate.otplepistr_rules[0]) {
                 div_player.textContent += ": " + sitestate.otplepistr_rules[1];
             }
+            if (sitestate.otplepivecstr_revealed && i_epi==sitestate.otplepivecstr_revealed[0]) {
+                div_player.textContent += " (" + sitestate.otplepivecstr_revealed[1].join(" ") + ")";
+            }
             if (sitestate.oepi_timeout===i_epi) {
                 div_player.className = "playerpanel_active";
             } else {
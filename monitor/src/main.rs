@@ -6,8 +6,18 @@ use openschafkopf_util::*;
 use serde_json::json;
 use std::io::{Read, Write};
 
+const N_MILLIS_TIME_BUDGET_DEFAULT : u64 = 2000;
+
 fn main() -> Result<(), failure::Error> {
     openschafkopf_logging::init_logging()?;
+    // other arguments are passed by the browser, so we only look for our option
+    let str_time_budget = std::env::args()
+        .skip_while(|str_arg| str_arg!="--time-budget")
+        .nth(1)
+        .map(|str_time_budget| str_time_budget.parse::<u64>())
+        .transpose()?
+        .unwrap_or(N_MILLIS_TIME_BUDGET_DEFAULT)
+        .to_string();
     use std::sync::{Arc, Mutex};
     let ocmd_openschafkopf: Arc<Mutex<Option<std::process::Child>>> = Arc::new(Mutex::new(None));
     let (sendstr, recvstr) = std::sync::mpsc::channel();
@@ -150,6 +160,8 @@ fn main() -> Result<(), failure::Error> {
                             str_hand.to_owned(),
                             "--cards-on-table".to_owned(),
                             str_cards_as_played.to_owned(),
+                            "--time-budget".to_owned(), // suggestions are only useful while the card is still to be played
+                            str_time_budget.clone(),
                         ])
                         .stdout(std::process::Stdio::piped())
                         .spawn()